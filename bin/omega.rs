@@ -6,6 +6,7 @@
 
 /// @see online
 /// https://hrkz.github.io/omega/
+use cycle::base::algebra::poly::Poly;
 use cycle::*;
 
 use lang::{Ast, Environment, Package};
@@ -16,22 +17,28 @@ use std::io::Write;
 use std::cmp;
 use std::env;
 use std::fs;
+use std::time::Duration;
+
+/// Time budget of the evaluation of a statement.
+const TIME_BUDGET: Duration = Duration::from_secs(30);
 
 fn main() -> io::Result<()> {
   let mut env = Environment::default();
 
   env.register_package(Prelude).expect("failed to load cycle prelude");
-  if let Some(filename) = env::args().nth(1) {
-    env.file(filename)
+  // `--verify` checks every simplification against an interval enclosure of the input
+  let (verify, args): (Vec<_>, Vec<_>) = env::args().skip(1).partition(|arg| arg == "--verify");
+  if let Some(filename) = args.into_iter().next() {
+    env.file(filename, !verify.is_empty())
   } else {
     println!("Cycle 0.4.1 :: omega");
-    env.repl()
+    env.repl(!verify.is_empty())
   }
 }
 
 trait Interact {
-  fn repl(&mut self) -> io::Result<()>;
-  fn file(&mut self, filename: String) -> io::Result<()>;
+  fn repl(&mut self, verify: bool) -> io::Result<()>;
+  fn file(&mut self, filename: String, verify: bool) -> io::Result<()>;
 
   fn interpret(
     // run interpreter
@@ -41,11 +48,12 @@ trait Interact {
     &mut self,
     line: usize,
     stmt: &str,
+    verify: bool,
   );
 }
 
 impl Interact for Environment {
-  fn repl(&mut self) -> io::Result<()> {
+  fn repl(&mut self, verify: bool) -> io::Result<()> {
     let mut stmt = String::new();
 
     loop {
@@ -60,31 +68,50 @@ impl Interact for Environment {
 
       self.interpret(
         1, //.
-        &stmt,
+        &stmt, verify,
       )
     }
   }
 
-  fn file(&mut self, filename: String) -> io::Result<()> {
+  fn file(&mut self, filename: String, verify: bool) -> io::Result<()> {
     let script = fs::read_to_string(&filename)?;
-    script.lines().enumerate().for_each(|(line, stmt)| self.interpret(line + 1, stmt));
+    script.lines().enumerate().for_each(|(line, stmt)| self.interpret(line + 1, stmt, verify));
 
     Ok(())
   }
 
-  fn interpret(&mut self, line: usize, stmt: &str) {
-    match self.run(stmt.trim_end()) {
-      // variable and function definition
-      Ok(None) => (),
+  fn interpret(&mut self, line: usize, stmt: &str, verify: bool) {
+    // evaluation, simplification and verification within the time budget
+    let eval = Budget::new().time(TIME_BUDGET).run(|| {
+      self.run(stmt.trim_end()).map(|expr| {
+        expr.map(|expr| {
+          let simplified = expr.clone().trivial();
+          let bounds = verify.then(|| Some((expr.interval(128).ok()?, simplified.as_ref().ok()?.interval(128).ok()?))).flatten();
+          (expr, simplified, bounds)
+        })
+      })
+    });
 
-      Ok(Some(expr)) => {
-        let expr = expr.trivial();
-        expr.map_or_else(|err| eprintln!("{err}"), |expr| println!("{expr}"))
+    match eval {
+      // variable and function definition
+      Ok(Ok(None)) => (),
+
+      Ok(Ok(Some((expr, simplified, bounds)))) => {
+        if let Some((lhs, rhs)) = bounds {
+          if lhs.intersect(&rhs).is_none() {
+            eprintln!("[verify: {line}] {expr} in {lhs}, simplified in {rhs}")
+          }
+        }
+        simplified.map_or_else(|err| eprintln!("{err}"), |expr| println!("{expr}"))
       }
 
-      Err(err) => {
+      Ok(Err(err)) => {
         eprintln!("[error: {line}] {err}")
       }
+
+      Err(halt) => {
+        eprintln!("[error: {line}] {halt}")
+      }
     }
   }
 }
@@ -94,6 +121,7 @@ struct Prelude;
 impl Package for Prelude {
   fn build(&self, env: &mut Environment) -> Result<(), lang::Error> {
     self.load_elementary(env);
+    self.load_combinatorics(env);
     self.load_calculus(env);
     self.load_sequence(env);
     self.load_manipulation(env);
@@ -163,6 +191,11 @@ impl Prelude {
       Prelude::map_fixed(|[x]| Ok(x.artanh()), arg) //.
     });
 
+    // ```sqrt(x)```
+    env.register_builtin(Symbol::new("sqrt", Number::C).expect("failed to declare symbol `sqrt`"), |arg| {
+      Prelude::map_fixed(|[x]| Ok(x.sqrt()), arg) //.
+    });
+
     // ```exp(x)```
     // ```log(x)```
     env.register_builtin(Symbol::new("exp", Number::C).expect("failed to declare symbol `exp`"), |arg| {
@@ -171,6 +204,32 @@ impl Prelude {
     env.register_builtin(Symbol::new("log", Number::C).expect("failed to declare symbol `log`"), |arg| {
       Prelude::map_fixed(|[x]| Ok(x.log()), arg) //.
     });
+
+    // ```gamma(x)```
+    env.register_builtin(Symbol::new("gamma", Number::C).expect("failed to declare symbol `gamma`"), |arg| {
+      Prelude::map_fixed(|[x]| Ok(x.gamma()), arg) //.
+    });
+
+    // ```floor(x)```
+    // ```ceil(x)```
+    // ```round(x)```
+    // ```frac(x)```
+    // ```mod(x, y)```
+    env.register_builtin(Symbol::new("floor", Number::R).expect("failed to declare symbol `floor`"), |arg| {
+      Prelude::map_fixed(|[x]| Ok(x.floor()), arg) //.
+    });
+    env.register_builtin(Symbol::new("ceil", Number::R).expect("failed to declare symbol `ceil`"), |arg| {
+      Prelude::map_fixed(|[x]| Ok(x.ceil()), arg) //.
+    });
+    env.register_builtin(Symbol::new("round", Number::R).expect("failed to declare symbol `round`"), |arg| {
+      Prelude::map_fixed(|[x]| Ok(x.round()), arg) //.
+    });
+    env.register_builtin(Symbol::new("frac", Number::R).expect("failed to declare symbol `frac`"), |arg| {
+      Prelude::map_fixed(|[x]| Ok(x.frac()), arg) //.
+    });
+    env.register_builtin(Symbol::new("mod", Number::R).expect("failed to declare symbol `mod`"), |arg| {
+      Prelude::map_fixed(|[x, y]| Ok(x.modulo(y)), arg) //.
+    });
   }
 
   /// Load calculus operators.
@@ -191,6 +250,71 @@ impl Prelude {
     });
   }
 
+  /// Load combinatorial and number-theoretic functions.
+  fn load_combinatorics(
+    //.
+    &self,
+    env: &mut Environment,
+  ) {
+    // ```binomial(n, k)```
+    // ```multinomial(k_1, ..., k_m)```
+    env.register_builtin(Symbol::new("binomial", Number::R).expect("failed to declare symbol `binomial`"), |arg| {
+      Prelude::map_fixed(|[n, k]| Ok(n.binomial(k)), arg) //.
+    });
+    env.register_builtin(Symbol::new("multinomial", Number::N).expect("failed to declare symbol `multinomial`"), |arg| {
+      Ok(Tree::multinomial(arg)) //.
+    });
+
+    // ```fibonacci(n)```
+    // ```lucas(n)```
+    env.register_builtin(Symbol::new("fibonacci", Number::R).expect("failed to declare symbol `fibonacci`"), |arg| {
+      Prelude::map_fixed(|[n]| Ok(n.fibonacci()), arg) //.
+    });
+    env.register_builtin(Symbol::new("lucas", Number::R).expect("failed to declare symbol `lucas`"), |arg| {
+      Prelude::map_fixed(|[n]| Ok(n.lucas()), arg) //.
+    });
+
+    // ```bernoulli(n)```
+    // ```euler(n)```
+    env.register_builtin(Symbol::new("bernoulli", Number::R).expect("failed to declare symbol `bernoulli`"), |arg| {
+      Prelude::map_fixed(|[n]| Ok(n.bernoulli()), arg) //.
+    });
+    env.register_builtin(Symbol::new("euler", Number::Z).expect("failed to declare symbol `euler`"), |arg| {
+      Prelude::map_fixed(|[n]| Ok(n.euler()), arg) //.
+    });
+
+    // ```stirling1(n, k)```
+    // ```stirling2(n, k)```
+    // ```bell(n)```
+    env.register_builtin(Symbol::new("stirling1", Number::Z).expect("failed to declare symbol `stirling1`"), |arg| {
+      Prelude::map_fixed(|[n, k]| Ok(n.stirling1(k)), arg) //.
+    });
+    env.register_builtin(Symbol::new("stirling2", Number::N).expect("failed to declare symbol `stirling2`"), |arg| {
+      Prelude::map_fixed(|[n, k]| Ok(n.stirling2(k)), arg) //.
+    });
+    env.register_builtin(Symbol::new("bell", Number::N).expect("failed to declare symbol `bell`"), |arg| {
+      Prelude::map_fixed(|[n]| Ok(n.bell()), arg) //.
+    });
+
+    // ```catalan(n)```
+    // ```partition(n)```
+    env.register_builtin(Symbol::new("catalan", Number::N).expect("failed to declare symbol `catalan`"), |arg| {
+      Prelude::map_fixed(|[n]| Ok(n.catalan()), arg) //.
+    });
+    env.register_builtin(Symbol::new("partition", Number::N).expect("failed to declare symbol `partition`"), |arg| {
+      Prelude::map_fixed(|[n]| Ok(n.partition()), arg) //.
+    });
+
+    // ```divisor_sigma(k, n)```
+    // ```mobius(n)```
+    env.register_builtin(Symbol::new("divisor_sigma", Number::R).expect("failed to declare symbol `divisor_sigma`"), |arg| {
+      Prelude::map_fixed(|[k, n]| Ok(k.divisor_sigma(n)), arg) //.
+    });
+    env.register_builtin(Symbol::new("mobius", Number::Z).expect("failed to declare symbol `mobius`"), |arg| {
+      Prelude::map_fixed(|[n]| Ok(n.mobius()), arg) //.
+    });
+  }
+
   /// Load sequential operators.
   fn load_sequence(
     //.
@@ -216,6 +340,58 @@ impl Prelude {
     env.register_builtin(Symbol::new("Expand", Number::AS).expect("failed to declare symbol `Expand`"), |arg| {
       Prelude::map_fixed(|[arg]| Ok(Tree::expand(arg).trivial().unwrap_or(Tree::Form)), arg)
     });
+
+    // ```Simplify(x)``` shortest form of `x` found
+    env.register_builtin(Symbol::new("Simplify", Number::AS).expect("failed to declare symbol `Simplify`"), |arg| {
+      Prelude::map_fixed(|[arg]| Ok(arg.simplify().unwrap_or(Tree::Form)), arg)
+    });
+
+    // ```IsZero(x)``` 1 if `x` is zero, 0 if not, with a randomized test
+    env.register_builtin(Symbol::new("IsZero", Number::AS).expect("failed to declare symbol `IsZero`"), |arg| {
+      Prelude::map_fixed(
+        |[arg]| match arg.is_zero() {
+          Zero::Yes => Ok(Tree::ONE),
+          Zero::No => Ok(Tree::ZERO),
+          Zero::Unknown => Ok(Tree::Form),
+        },
+        arg,
+      )
+    });
+
+    // ```Radsimp(x)``` rationalize the denominators of square roots
+    env.register_builtin(Symbol::new("Radsimp", Number::AS).expect("failed to declare symbol `Radsimp`"), |arg| {
+      Prelude::map_fixed(|[arg]| Ok(arg.radsimp().unwrap_or(Tree::Form)), arg)
+    });
+
+    // ```N(x, d)``` numerical value of `x` with `d` significant digits
+    env.register_builtin(Symbol::new("N", Number::AS).expect("failed to declare symbol `N`"), |arg| {
+      Prelude::map_fixed(
+        |[arg, digits]| match digits {
+          Tree::Num(Number::Int(d)) => {
+            let d = Natural::try_from(d).and_then(u64::try_from).map_err(|_| None)?;
+            Ok(arg.evalf(d as usize).map_or(Tree::Form, |x| Tree::Num(Number::Flt(x))))
+          }
+          _ => Err(None),
+        },
+        arg,
+      )
+    });
+
+    // ```RootOf(p, k)``` k-th real root of the univariate polynomial `p`
+    env.register_builtin(Symbol::new("RootOf", Number::R).expect("failed to declare symbol `RootOf`"), |arg| {
+      Prelude::map_fixed(
+        |[poly, k]| match k {
+          Tree::Num(Number::Int(k)) => {
+            let k = Natural::try_from(k).and_then(u64::try_from).map_err(|_| None)?;
+            let x = poly.iter().fold_rec(None, &|acc, e| acc.or_else(|| matches!(e, Tree::Sym(_)).then(|| e.clone())));
+            let root = x.and_then(|x| Poly::from_tree(&poly, &x)).and_then(|p| Algebraic::root_of(&p, k as usize));
+            Ok(root.map_or(Tree::Form, |a| Tree::Num(Number::Alg(a))))
+          }
+          _ => Err(None),
+        },
+        arg,
+      )
+    });
   }
 
   /// Load mathematical constants.
@@ -2,7 +2,8 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::ops::{Add, Mul};
 
-use crate::{Constant, Form, Integer, Natural, Number, Rational, SymbolicResult};
+use crate::base::radical;
+use crate::{Algebraic, Complex, Constant, Form, Integer, Natural, Number, Rational, SymbolicResult};
 use crate::{Edge, Expr, Tree};
 
 /// A list of unary operations.
@@ -66,7 +67,7 @@ impl Algebra {
         map: UOp::Fact,
         arg,
       } => match arg.trivial()? {
-        Tree::Num(Number::Int(z)) => Natural::try_from(z).map(|n| Tree::from(Integer::from(Natural::factorial(n)))).map_err(|_| Form {}),
+        Tree::Num(Number::Int(z)) => Natural::try_from(z).map(|n| Tree::from(Integer::from(Natural::factorial(n)))).map_err(|_| Form::default()),
         expr => Ok(expr.fact()),
       },
 
@@ -81,72 +82,96 @@ impl Algebra {
           // ```z∞^~∞ -> ?```
           (Tree::Cte(Constant::Infinity(_)), Tree::Cte(Constant::Infinity(Ordering::Greater))) => Ok(Tree::Cte(Constant::Infinity(Ordering::Equal))),
           (Tree::Cte(Constant::Infinity(_)), Tree::Cte(Constant::Infinity(Ordering::Less))) => Ok(Tree::from(0)),
-          (Tree::Cte(Constant::Infinity(_)), Tree::Cte(Constant::Infinity(Ordering::Equal))) => Err(Form {}),
+          (Tree::Cte(Constant::Infinity(_)), Tree::Cte(Constant::Infinity(Ordering::Equal))) => Err(Form::default()),
 
           // ```z∞^0 = +-1^z∞ -> ?```
-          (Tree::Cte(Constant::Infinity(_)), Tree::ZERO) | (Tree::ONE | Tree::NEG_ONE, Tree::Cte(Constant::Infinity(_))) => Err(Form {}),
+          (Tree::Cte(Constant::Infinity(_)), Tree::ZERO) | (Tree::ONE | Tree::NEG_ONE, Tree::Cte(Constant::Infinity(_))) => Err(Form::default()),
+
+          // ```z∞^y = x^z∞ -> ?, x, y ∈ ℚ(i)∖ℚ```
+          (Tree::Cte(Constant::Infinity(_)), Tree::Num(Number::Cpx(_))) | (Tree::Num(Number::Cpx(_)), Tree::Cte(Constant::Infinity(_))) => Err(Form::default()),
 
           // ``` z∞^y, y ∈ ℚ```
           // ``` z∞^y ->   0, y < 0```
           // ```+~∞^y -> +~∞, y > 0```
           // ``` -∞^y ->   ∞, y mod 2 = 0```
           // ``` -∞^y ->  -∞, y mod 2 = 1```
-          (Tree::Cte(Constant::Infinity(_)), Tree::Num(rhs)) if rhs.num().is_negative() => Ok(Tree::from(0)),
+          (Tree::Cte(Constant::Infinity(_)), Tree::Num(rhs)) if rhs.sgn().is_lt() => Ok(Tree::from(0)),
           (Tree::Cte(Constant::Infinity(z)), Tree::Num(_)) if z.is_ge() => Ok(Tree::Cte(Constant::Infinity(z))),
-          (Tree::Cte(Constant::Infinity(Ordering::Less)), Tree::Num(rhs)) => Ok(Tree::Cte(Constant::Infinity(match (rhs.num().clone() / rhs.den()).rem_euclid(Integer::from(2)) {
-            Integer::ZERO => Ordering::Greater,
-            _ => Ordering::Less,
-          }))),
+          (Tree::Cte(Constant::Infinity(Ordering::Less)), Tree::Num(rhs)) => {
+            let q = rhs.to_rational().ok_or_else(Form::default)?;
+            Ok(Tree::Cte(Constant::Infinity(match (q.num / q.den).rem_euclid(Integer::from(2)) {
+              Integer::ZERO => Ordering::Greater,
+              _ => Ordering::Less,
+            })))
+          }
 
           // ```x^+-∞, x ∈ ℚ```
           // ```x^+-∞ ->  0, |x|+-∞ < 0```
           // ```x^+-∞ ->  ∞, |x|+-∞ = 0, x > 0```
           // ```x^+-∞ -> ~∞, |x|+-∞ = 0, x < 0```
-          (Tree::Num(lhs), Tree::Cte(Constant::Infinity(z))) if z.is_ne() => Ok(match (Integer::from(lhs.num().clone().abs()).cmp(&lhs.den()).cmp(&z), lhs.num().is_positive()) {
-            (Ordering::Greater | Ordering::Less, _) => Tree::from(0),
-            (Ordering::Equal, true) => Tree::Cte(Constant::Infinity(Ordering::Greater)),
-            (Ordering::Equal, false) => Tree::Cte(Constant::Infinity(Ordering::Equal)),
-          }),
-
-          // ```i^y, y ∈ ℤ```
-          // ```i^y =  1, y mod 4 = 0```
-          // ```i^y =  i, y mod 4 = 1```
-          // ```i^y = -1, y mod 4 = 2```
-          // ```i^y = -i, y mod 4 = 3```
-          (Tree::Cte(Constant::i), Tree::Num(Number::Int(rhs))) => Ok(match rhs.rem_euclid(Integer::from(4)) {
-            Integer::ZERO => Tree::from(1),
-            Integer::ONE => Tree::Cte(Constant::i),
-            Integer::TWO => Tree::from(-1),
-            _ => Tree::Cte(Constant::i).neg(),
-          }),
+          (Tree::Num(lhs @ (Number::Int(_) | Number::Rat(_))), Tree::Cte(Constant::Infinity(z))) if z.is_ne() => {
+            let q = lhs.to_rational().ok_or_else(Form::default)?;
+            Ok(match (Integer::from(q.num.clone().abs()).cmp(&q.den).cmp(&z), q.num.is_positive()) {
+              (Ordering::Greater | Ordering::Less, _) => Tree::from(0),
+              (Ordering::Equal, true) => Tree::Cte(Constant::Infinity(Ordering::Greater)),
+              (Ordering::Equal, false) => Tree::Cte(Constant::Infinity(Ordering::Equal)),
+            })
+          }
+          // ```c^+-∞```, `c` a real constant compared with `±1` by an interval enclosure
+          (lhs, rhs @ Tree::Cte(Constant::Infinity(z))) if z.is_ne() => {
+            let mag = match (lhs.clone().sub(Tree::from(1)).sign(), lhs.clone().add(Tree::from(1)).sign()) {
+              (Some(Ordering::Greater), _) | (_, Some(Ordering::Less)) => Some(Ordering::Greater),
+              (Some(Ordering::Less), Some(Ordering::Greater)) => Some(Ordering::Less),
+              _ => None,
+            };
+            match (mag, lhs.sign()) {
+              (Some(mag), _) if mag != z => Ok(Tree::from(0)),
+              (Some(_), Some(Ordering::Greater)) => Ok(Tree::Cte(Constant::Infinity(Ordering::Greater))),
+              (Some(_), Some(Ordering::Less)) => Ok(Tree::Cte(Constant::Infinity(Ordering::Equal))),
+              _ => Ok(lhs.pow(rhs)),
+            }
+          }
 
           // ```0^y, y ∈ ℚ```
           // ```0^y =  0, y > 0```
           // ```0^y = ~∞, y < 0```
           // ```0^0 =  ?```
-          (Tree::ZERO, Tree::Num(rhs)) => match rhs.num().ord() {
+          (Tree::ZERO, Tree::Num(rhs)) => match rhs.sgn() {
             Ordering::Greater => Ok(Tree::from(0)),
             Ordering::Less => Ok(Tree::Cte(Constant::Infinity(Ordering::Equal))),
-            Ordering::Equal => Err(Form {}),
+            Ordering::Equal => Err(Form::default()),
           },
 
           // ```sqrt(-1) = (-1)^(1/2) = i```
-          (Tree::NEG_ONE, Tree::Num(Number::Rat(Rational { num: Integer::ONE, den: Integer::TWO }))) => Ok(Tree::Cte(Constant::i)),
+          (Tree::NEG_ONE, Tree::Num(Number::Rat(Rational { num: Integer::ONE, den: Integer::TWO }))) => Ok(Tree::from(Complex::I)),
           // ```1^x = x^0 = 1```
           (Tree::ONE, _) | (_, Tree::ZERO) => Ok(Tree::from(1)),
           // ```x^1 = x```
           (lhs, Tree::ONE) => Ok(lhs),
 
-          // ```x ∈ ℚ, y ∈ ℤ```
+          // ```x^y, x ∈ F or y ∈ F```
+          (Tree::Num(lhs @ Number::Flt(_)), Tree::Num(rhs)) | (Tree::Num(lhs), Tree::Num(rhs @ Number::Flt(_))) => Ok(Tree::Num(lhs.powf(rhs)?)),
+          // ```x ∈ ℚ(i), y ∈ ℤ```
           (Tree::Num(lhs), Tree::Num(Number::Int(rhs))) => Ok(Tree::Num(lhs.powi(rhs)?)),
           // ```x ∈ ℤ, y ∈ ℚ```
-          (Tree::Num(Number::Int(lhs)), Tree::Num(rhs)) => Algebra::trivial_root(lhs, rhs),
+          (Tree::Num(Number::Int(lhs)), Tree::Num(rhs @ Number::Rat(_))) => Algebra::trivial_root(lhs, rhs),
+          // ```(n/d)^y = n^y*d^-y, d > 0```
+          (Tree::Num(Number::Rat(q)), Tree::Num(rhs @ Number::Rat(_))) => Tree::from(q.num).pow(Tree::Num(rhs.clone())).mul(Tree::from(q.den).pow(Tree::Num(rhs).neg())).trivial(),
+          // ```(a + b*r^(1/2))^(p/2)```
+          (lhs @ Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Add, .. })), Tree::Num(Number::Rat(q))) if q.den == Integer::TWO => match radical::denest(&lhs) {
+            Some(root) => root.pow(Tree::from(q.num)).trivial(),
+            None => Ok(lhs.pow(Tree::Num(Number::Rat(q)))),
+          },
 
-          // ```(b^e)^y = b^(e*y), y ∈ ℤ```
-          (Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, e) }), rhs) if rhs.dom().le(&Number::Z) => b.pow(e.mul(rhs)).trivial(),
+          // ```(b^e)^y = b^(e*y), y ∈ ℤ or b ≥ 0, e, y ∈ ℝ```
+          (Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, e) }), rhs) if rhs.dom().le(&Number::Z) || (b.facts().is_nonnegative() && e.facts().is_real() && rhs.facts().is_real()) => {
+            b.pow(e.mul(rhs)).trivial()
+          }
 
-          // ```(x_1*x_2*...*x_n)^y = x_1^y*x_2^y*...*x_n^y, y ∈ ℤ```
-          (Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Mul, arg })), Tree::Num(rhs)) if rhs.dom().le(&Number::Z) => {
+          // ```(x_1*x_2*...*x_n)^y = x_1^y*x_2^y*...*x_n^y, y ∈ ℤ or x_i ≥ 0, y ∈ ℝ```
+          (Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Mul, arg })), Tree::Num(rhs))
+            if rhs.dom().le(&Number::Z) || (Tree::Num(rhs.clone()).facts().is_real() && arg.iter().all(|e| e.facts().is_nonnegative())) =>
+          {
             let prod = arg.into_iter().map(|sub| sub.pow(Tree::Num(rhs.clone())).edge()).collect();
             Tree::assoc(AOp::Mul, prod).trivial()
           }
@@ -163,20 +188,9 @@ impl Algebra {
 
   /// Apply root simplifications.
   pub fn trivial_root(lhs: Integer, rhs: Number) -> SymbolicResult<Tree> {
-    match rhs.clone().try_root(&lhs.mag) {
-      Some(Number::Int(Integer::ONE)) | None => Ok(Tree::from(lhs).pow(Tree::Num(rhs))),
-      Some(root) => {
-        let root = Tree::Num(root);
-        if lhs.is_negative() {
-          // ```(-x)^(n/d) = x^(n/d)*(-1)^div(n, d)*(-1)^(mod(n, d)/d)```
-          let n = rhs.num();
-          let d = rhs.den();
-          let (q, r) = (n.clone().div_euclid(d.clone()), n.clone().rem_euclid(d));
-          root.mul(Tree::from(-1).pow(Tree::from(q)).mul(Tree::from(-1).pow(Tree::from(Rational::new(r, rhs.den()))))).trivial()
-        } else {
-          Ok(root)
-        }
-      }
+    match rhs {
+      Number::Rat(e) => radical::trivial_root(lhs, &e),
+      rhs => Ok(Tree::from(lhs).pow(Tree::Num(rhs))),
     }
   }
 
@@ -316,9 +330,9 @@ impl Assoc {
 
         (Some(lhs), Some(rhs)) => match (map, lhs, rhs) {
           // ```z1∞ + z2∞ = ?, z1 != z2```
-          (AOp::Add, Tree::Cte(Constant::Infinity(lhs)), Tree::Cte(Constant::Infinity(rhs))) if lhs != rhs => return Err(Form {}),
+          (AOp::Add, Tree::Cte(Constant::Infinity(lhs)), Tree::Cte(Constant::Infinity(rhs))) if lhs != rhs => return Err(Form::default()),
           // ```0*z∞ = ?```
-          (AOp::Mul, Tree::ZERO, Tree::Cte(Constant::Infinity(_))) => return Err(Form {}),
+          (AOp::Mul, Tree::ZERO, Tree::Cte(Constant::Infinity(_))) => return Err(Form::default()),
 
           // ```0*x = 0```
           (AOp::Mul, Tree::ZERO, _) => {
@@ -333,17 +347,52 @@ impl Assoc {
           // ```z1∞*z2∞ = sgn(z1*z2)∞```
           (AOp::Mul, Tree::Cte(Constant::Infinity(lhs)), Tree::Cte(Constant::Infinity(rhs))) => flat.push(Tree::Cte(Constant::Infinity(Constant::sgn_cmp(lhs, rhs)))),
 
+          // ```x*z∞ = ~∞, x ∈ ℚ(i)∖ℚ```
+          (AOp::Mul, Tree::Num(Number::Cpx(_)), Tree::Cte(Constant::Infinity(_))) => flat.push(Tree::Cte(Constant::Infinity(Ordering::Equal))),
           // ```x*z∞ = sgn(x*z)∞, x ∈ ℚ```
-          (AOp::Mul, Tree::Num(lhs), Tree::Cte(Constant::Infinity(z))) => flat.push(Tree::Cte(Constant::Infinity(Constant::sgn_cmp(lhs.num().ord(), z)))),
+          (AOp::Mul, Tree::Num(lhs), Tree::Cte(Constant::Infinity(z))) => flat.push(Tree::Cte(Constant::Infinity(Constant::sgn_cmp(lhs.sgn(), z)))),
+          // ```c*z∞ = sgn(c*z)∞```, `c` a real constant whose sign an interval enclosure decides
+          (AOp::Mul, lhs, rhs @ Tree::Cte(Constant::Infinity(z))) | (AOp::Mul, lhs @ Tree::Cte(Constant::Infinity(z)), rhs) => {
+            let c = if matches!(lhs, Tree::Cte(Constant::Infinity(_))) { &rhs } else { &lhs };
+            match c.sign() {
+              Some(Ordering::Equal) => return Err(Form::default()),
+              Some(sgn) => flat.push(Tree::Cte(Constant::Infinity(Constant::sgn_cmp(sgn, z)))),
+              None => {
+                arg.push(lhs.edge());
+                flat.push(rhs);
+              }
+            }
+          }
+
+          // ```α + (a + b*i)``` and ```α*(a + b*i)``` stay unevaluated
+          (_, lhs @ Tree::Num(Number::Alg(_)), rhs @ Tree::Num(Number::Cpx(_))) | (_, lhs @ Tree::Num(Number::Cpx(_)), rhs @ Tree::Num(Number::Alg(_))) => {
+            arg.push(lhs.edge());
+            flat.push(rhs);
+          }
 
           // ```x, y ∈ ℚ```
           (AOp::Add, Tree::Num(lhs), Tree::Num(rhs)) => flat.push(Tree::Num(lhs.add(rhs)?)),
           (AOp::Mul, Tree::Num(lhs), Tree::Num(rhs)) => flat.push(Tree::Num(lhs.mul(rhs)?)),
 
+          // ```a^y*b^y = (a*b)^y, a, b > 0```
+          (AOp::Mul, Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (a, e) }), Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, f) }))
+            if e == f && matches!((a.as_ref(), b.as_ref(), e.as_ref()), (Tree::Num(Number::Int(a)), Tree::Num(Number::Int(b)), Tree::Num(Number::Rat(_))) if a.is_positive() && b.is_positive()) =>
+          {
+            match a.as_ref().clone().mul(b.as_ref().clone()).pow(e.as_ref().clone()).trivial()? {
+              Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Mul, arg })) => flat.extend(arg.iter().rev().map(|sub| sub.as_ref().clone())),
+              root => flat.push(root),
+            }
+          }
+
           // ```x + 0 = x```
           (AOp::Add, lhs, Tree::ZERO) => flat.push(lhs),
           // ```1*x = x```
           (AOp::Mul, Tree::ONE, rhs) => flat.push(rhs),
+          // ```c*c^y``` stays a coefficient times a radical
+          (AOp::Mul, lhs @ Tree::Num(_), rhs) => {
+            arg.push(lhs.edge());
+            flat.push(rhs);
+          }
 
           (_, lhs, rhs) => {
             let (lhs_base, lhs_coeff) = Self::split(map, &lhs)?;
@@ -377,6 +426,16 @@ impl Assoc {
         arg,
       ),
     };
+
+    // ```sqrt(2)*sqrt(3) - sqrt(6) = 0```, by the minimal polynomial
+    let (radical, algebraic) = tree.helper_algebraic();
+    if radical || algebraic {
+      match tree.to_algebraic() {
+        Some(a) if a.deg() == 1 => return Ok(Tree::Num(Number::Alg(a).trivial()?)),
+        Some(a) if algebraic => return Ok(Tree::Num(Number::Alg(a))),
+        _ => {}
+      }
+    }
     Ok(tree)
   }
 
@@ -469,7 +528,7 @@ impl AOp {
   /// Return the associative identity element.
   /// (S, ∘), ∃e, e ∘ a = a ∘ e = a ∀a ∈ S
   /// [Semigroup](https://en.wikipedia.org/wiki/Semigroup)
-  const fn id(&self) -> Tree {
+  pub(crate) const fn id(&self) -> Tree {
     match self {
       // ```Id(+) = 0```
       AOp::Add => Tree::ZERO,
@@ -489,6 +548,66 @@ impl AOp {
 }
 
 impl Tree {
+  const ALGEBRAIC_DEG: usize = 32;
+
+  /// Return the exact value of a real expression of rationals, algebraic numbers and radicals of positive rationals.
+  /// Give up (`None`) once the degree of the minimal polynomial would exceed 32.
+  pub fn to_algebraic(&self) -> Option<Algebraic> {
+    let bounded = |a: Algebraic| (a.deg() <= Tree::ALGEBRAIC_DEG).then_some(a);
+    match self {
+      Tree::Num(Number::Int(z)) => Some(Algebraic::from(Rational::from(z.clone()))),
+      Tree::Num(Number::Rat(q)) => Some(Algebraic::from(q.clone())),
+      Tree::Num(Number::Alg(a)) => Some(a.clone()),
+
+      Tree::Alg(Algebra::UExpr { map: UOp::Id, arg }) => arg.to_algebraic(),
+      Tree::Alg(Algebra::AssocExpr(Assoc { map, arg })) => {
+        let mut arg = arg.iter();
+        let init = arg.next()?.to_algebraic()?;
+        arg.try_fold(init, |acc, sub| {
+          let sub = sub.to_algebraic()?;
+          if acc.deg() * sub.deg() > Tree::ALGEBRAIC_DEG {
+            return None;
+          }
+          bounded(match map {
+            AOp::Add => acc + sub,
+            AOp::Mul => acc * sub,
+          })
+        })
+      }
+
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, e) }) => match e.as_ref() {
+        Tree::Num(Number::Int(n)) => b.to_algebraic()?.pow(n.clone()).ok(),
+        // ```b^(n/d) = (b^(1/d))^n, b > 0```
+        Tree::Num(Number::Rat(q)) => {
+          let b = b.to_algebraic()?.to_rational()?;
+          let d = u64::try_from(Natural::try_from(q.den.clone()).ok()?).ok()?;
+          if !b.is_positive() || d > Tree::ALGEBRAIC_DEG as u64 {
+            return None;
+          }
+          bounded(Algebraic::nth_root(b, d)?.pow(q.num.clone()).ok()?)
+        }
+        _ => None,
+      },
+
+      _ => None,
+    }
+  }
+
+  // Whether a numeric expression holds radicals and algebraic numbers
+  fn helper_algebraic(&self) -> (bool, bool) {
+    let (mut radical, mut algebraic) = (false, false);
+    for e in self.pre_order() {
+      match e {
+        Tree::Num(Number::Alg(_)) => algebraic = true,
+        Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (_, e) }) if matches!(e.as_ref(), Tree::Num(Number::Rat(_))) => radical = true,
+        Tree::Num(_) | Tree::Alg(Algebra::UExpr { map: UOp::Id, .. } | Algebra::BExpr { .. } | Algebra::AssocExpr(_)) => {}
+        // stops at the first subexpression which is not algebraic
+        _ => return (false, false),
+      }
+    }
+    (radical, algebraic)
+  }
+
   pub(crate) fn assoc(
     //.
     map: AOp,
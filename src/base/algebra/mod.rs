@@ -1,7 +1,12 @@
 //! Algebraic structures.
 
+mod num_algebraic;
+mod num_complex;
+mod num_finite;
+mod num_float;
 mod num_integer;
 mod num_natural;
+mod num_padic;
 mod num_rational;
 
 pub mod poly;
@@ -11,21 +16,34 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::ops::{Add, Mul};
 
+use crate::Halt;
+
+pub use num_algebraic::Algebraic;
+pub use num_complex::Complex;
+pub use num_finite::{FiniteField, Gf};
+pub use num_float::{Float, Round};
 pub use num_integer::*;
 pub use num_natural::*;
+pub use num_padic::Padic;
 pub use num_rational::Rational;
 pub use repr::*;
 
 /// Type alias for a mathematical resulting form.
 pub type SymbolicResult<T> = Result<T, Form>;
 
-/// A countable number (excluding irrational and complex numbers).
+/// A countable number (excluding transcendental numbers).
 #[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord)]
 pub enum Number {
   /// The ring of integers.
   Int(Integer),
   /// The field of rationals.
   Rat(Rational),
+  /// A binary floating point approximation.
+  Flt(Float),
+  /// The field of Gaussian rationals.
+  Cpx(Complex),
+  /// A real algebraic number.
+  Alg(Algebraic),
 }
 
 impl Number {
@@ -40,49 +58,107 @@ impl Number {
   /// The field of complex numbers.
   pub const C: NumberSystem = NumberSystem::C;
 
-  /// Return the numerator.
-  pub fn num(&self) -> &Integer {
+  /// Return the numerator of an integer or rational number.
+  pub fn num(&self) -> Option<&Integer> {
     match self {
       Number::Int(
         z, // ```num(z) ∈ ℤ = num(z/1) ∈ ℚ = z,```
-      ) => z,
+      ) => Some(z),
       Number::Rat(
         q, // ```num(n/d) ∈ ℚ = n,```
-      ) => &q.num,
+      ) => Some(&q.num),
+      Number::Flt(_) | Number::Cpx(_) | Number::Alg(_) => None,
     }
   }
 
-  /// Return the denominator.
-  pub fn den(&self) -> Integer {
+  /// Return the denominator of an integer or rational number.
+  pub fn den(&self) -> Option<Integer> {
     match self {
       Number::Int(
         _, // ```den(z) ∈ ℤ = den(z/1) ∈ ℚ = 1,```
-      ) => Integer::from(1),
+      ) => Some(Integer::from(1)),
       Number::Rat(
         q, // ```den(n/d) ∈ ℚ = d,```
-      ) => q.den.clone(),
+      ) => Some(q.den.clone()),
+      Number::Flt(_) | Number::Cpx(_) | Number::Alg(_) => None,
+    }
+  }
+
+  /// Return the sign (of the real part, for complex numbers).
+  pub fn sgn(&self) -> Ordering {
+    match self {
+      Number::Int(z) => z.ord(),
+      Number::Rat(q) => q.num.ord(),
+      Number::Flt(x) if x.is_zero() => Ordering::Equal,
+      Number::Flt(x) => {
+        if x.is_negative() {
+          Ordering::Less
+        } else {
+          Ordering::Greater
+        }
+      }
+      Number::Cpx(z) => z.re.num.ord(),
+      Number::Alg(a) => a.sgn(),
     }
   }
 
   /// Determine the number set.
   pub fn dom(&self) -> NumberSystem {
-    if self.den() != Integer::ONE {
+    if let Number::Cpx(_) = self {
+      NumberSystem::C
+    } else if let Number::Flt(_) | Number::Alg(_) = self {
+      NumberSystem::R
+    } else if self.den() != Some(Integer::ONE) {
       NumberSystem::Q
-    } else if self.num().is_negative() {
+    } else if self.sgn() == Ordering::Less {
       NumberSystem::Z
     } else {
       NumberSystem::N
     }
   }
 
+  /// Return `true` if the number is zero.
+  pub fn is_zero(&self) -> bool {
+    match self {
+      Number::Flt(x) => x.is_zero(),
+      Number::Cpx(z) => z.is_zero(),
+      Number::Alg(a) => a.sgn() == Ordering::Equal,
+      n => n.sgn() == Ordering::Equal,
+    }
+  }
+
   /// Return the inverse (reciprocal).
   pub fn inv(self) -> SymbolicResult<Number> {
-    Number::Rat(Rational::new(self.den(), self.num().clone())).trivial()
+    if let Number::Cpx(z) = self {
+      return Number::Cpx(z.inv()?).trivial();
+    }
+    if let Number::Alg(a) = self {
+      return Number::Alg(a.inv()?).trivial();
+    }
+
+    if let Number::Flt(x) = self {
+      let one = Float::one(x.prec(), x.rnd());
+      return if x.is_zero() {
+        Err(
+          Form::default(), // ```1/0.0```
+        )
+      } else {
+        Ok(Number::Flt(one / x))
+      };
+    }
+
+    let q = self.to_rational().ok_or_else(Form::default)?;
+    Number::Rat(Rational::new(q.den, q.num)).trivial()
   }
 
   /// Raise the number to an integer power.
   pub fn powi(self, n: Integer) -> SymbolicResult<Number> {
-    if self.num() != &Integer::ZERO {
+    if let Number::Alg(a) = self {
+      // ```α^n``` by its minimal polynomial
+      return Number::Alg(a.pow(n)?).trivial();
+    }
+
+    if !self.is_zero() {
       match n.ord() {
         // ```l^n = 1^(n - 1)*l```
         Ordering::Greater => self.clone().powi(n - Integer::from(1))?.mul(self),
@@ -95,16 +171,17 @@ impl Number {
       Ok(Number::Int(Integer::from(0)))
     } else {
       Err(
-        Form {}, // ```0^-n```
+        Form::default(), // ```0^-n```
       )
     }
   }
 
   /// Try to compute the ith root.
   pub fn try_root(self, x: &Natural) -> Option<Number> {
-    if let Ok(n) = u64::try_from(self.den().abs()) {
-      let m = self.num();
-
+    let (Some(m), Some(den)) = (self.num().cloned(), self.den()) else {
+      return None;
+    };
+    if let Ok(n) = u64::try_from(den.abs()) {
       let mut l = Natural::from(0u64);
       let mut u = Natural::from(1u64);
       while &u.clone().pow(n) <= x {
@@ -118,7 +195,7 @@ impl Number {
         let test = root.clone().pow(n);
 
         match test.cmp(x) {
-          Ordering::Equal => return Number::Int(Integer::from(root)).powi(m.clone()).ok(),
+          Ordering::Equal => return Number::Int(Integer::from(root)).powi(m).ok(),
           Ordering::Less => l = root,
           Ordering::Greater => u = root,
         }
@@ -137,7 +214,7 @@ impl Number {
     if let Number::Rat(q) = self {
       if q.den == Integer::ZERO {
         return Err(
-          Form {}, // ```n/0```
+          Form::default(), // ```n/0```
         );
       }
 
@@ -155,19 +232,81 @@ impl Number {
       } else {
         Ok(Number::Int(num))
       }
+    } else if let Number::Cpx(z) = self {
+      if z.is_real() {
+        Number::Rat(z.re).trivial()
+      } else {
+        Ok(Number::Cpx(z))
+      }
+    } else if let Number::Alg(a) = self {
+      match a.to_rational() {
+        Some(q) => Number::Rat(q).trivial(),
+        None => Ok(Number::Alg(a)),
+      }
     } else {
       Ok(self)
     }
   }
 
+  /// Round the (real) number to a [`Float`] with precision `prec`.
+  pub fn to_float(self, prec: usize, rnd: Round) -> SymbolicResult<Float> {
+    match self {
+      Number::Int(z) => Ok(Float::from_integer(z, prec, rnd)),
+      Number::Rat(q) => Ok(Float::from_rational(q, prec, rnd)),
+      Number::Flt(x) => Ok(x.with_rnd(rnd).with_prec(prec)),
+      Number::Cpx(_) => Err(
+        Form::default(), // ```a + b*i ∉ ℝ```
+      ),
+      Number::Alg(a) => Ok(a.to_float(prec, rnd)),
+    }
+  }
+
+  /// Return the exact value of an integer, rational or floating point number.
+  pub(crate) fn to_rational(&self) -> Option<Rational> {
+    match self {
+      Number::Int(z) => Some(Rational::from(z.clone())),
+      Number::Rat(q) => Some(q.clone()),
+      Number::Flt(x) => Some(x.to_rational()),
+      Number::Cpx(_) | Number::Alg(_) => None,
+    }
+  }
+
+  /// Raise the number to a floating point power (real valued).
+  pub fn powf(self, n: Number) -> SymbolicResult<Number> {
+    let (prec, rnd) = match (&self, &n) {
+      (Number::Flt(x), _) | (_, Number::Flt(x)) => (x.prec(), x.rnd()),
+      _ => (Float::DEFAULT_PREC, Round::Nearest),
+    };
+
+    self.to_float(prec, rnd)?.pow(n.to_float(prec, rnd)?).map(Number::Flt)
+  }
+
   // Helpers
   pub(crate) fn helper_len(&self) -> u64 {
+    match self {
+      Number::Flt(x) => return 1 + x.is_negative() as u64,
+      // a + b*i
+      Number::Cpx(_) => return 3,
+      Number::Alg(_) => return 1,
+      _ => {}
+    }
+
     1 + self
       .dom()
       // ℤ -> -
       // ℚ -> /
       .ge(&NumberSystem::Z) as u64
   }
+
+  pub(crate) fn helper_prec(&self) -> u64 {
+    match self {
+      // a + b*i -> +
+      Number::Cpx(z) if z.re.num != Integer::ZERO => 1,
+      // b*i -> *
+      Number::Cpx(_) => 2,
+      n => n.helper_len(),
+    }
+  }
 }
 
 impl Add for Number {
@@ -192,6 +331,32 @@ impl Add for Number {
           q + Rational::from(z),
         )
       }
+
+      (Number::Alg(lhs), Number::Alg(rhs)) => Number::Alg(lhs + rhs),
+      (Number::Alg(_), Number::Cpx(_)) | (Number::Cpx(_), Number::Alg(_)) => {
+        return Err(
+          Form::default(), // ```α + (a + b*i)``` is not a Gaussian rational
+        );
+      }
+      (Number::Alg(a), n @ (Number::Int(_) | Number::Rat(_))) | (n @ (Number::Int(_) | Number::Rat(_)), Number::Alg(a)) => {
+        Number::Alg(a + Algebraic::from(n.to_rational().ok_or_else(Form::default)?))
+      }
+
+      (Number::Cpx(lhs), Number::Cpx(rhs)) => Number::Cpx(lhs + rhs),
+      (Number::Flt(_), Number::Cpx(_)) | (Number::Cpx(_), Number::Flt(_)) => {
+        return Err(
+          Form::default(), // ```x + (a + b*i)``` has no floating point representation
+        );
+      }
+      (Number::Cpx(z), n @ (Number::Int(_) | Number::Rat(_))) | (n @ (Number::Int(_) | Number::Rat(_)), Number::Cpx(z)) => {
+        // ```(a + b*i) + c = (a + c) + b*i```
+        Number::Cpx(z + Complex::from(n.to_rational().ok_or_else(Form::default)?))
+      }
+
+      (Number::Flt(x), n) | (n, Number::Flt(x)) => {
+        let n = n.to_float(x.prec(), x.rnd())?;
+        Number::Flt(x + n)
+      }
     }
     .trivial()
   }
@@ -219,6 +384,32 @@ impl Mul for Number {
           q * Rational::from(z),
         )
       }
+
+      (Number::Alg(lhs), Number::Alg(rhs)) => Number::Alg(lhs * rhs),
+      (Number::Alg(_), Number::Cpx(_)) | (Number::Cpx(_), Number::Alg(_)) => {
+        return Err(
+          Form::default(), // ```α*(a + b*i)``` is not a Gaussian rational
+        );
+      }
+      (Number::Alg(a), n @ (Number::Int(_) | Number::Rat(_))) | (n @ (Number::Int(_) | Number::Rat(_)), Number::Alg(a)) => {
+        Number::Alg(a * Algebraic::from(n.to_rational().ok_or_else(Form::default)?))
+      }
+
+      (Number::Cpx(lhs), Number::Cpx(rhs)) => Number::Cpx(lhs * rhs),
+      (Number::Flt(_), Number::Cpx(_)) | (Number::Cpx(_), Number::Flt(_)) => {
+        return Err(
+          Form::default(), // ```x*(a + b*i)``` has no floating point representation
+        );
+      }
+      (Number::Cpx(z), n @ (Number::Int(_) | Number::Rat(_))) | (n @ (Number::Int(_) | Number::Rat(_)), Number::Cpx(z)) => {
+        // ```(a + b*i) * c = a*c + b*c*i```
+        Number::Cpx(z * Complex::from(n.to_rational().ok_or_else(Form::default)?))
+      }
+
+      (Number::Flt(x), n) | (n, Number::Flt(x)) => {
+        let n = n.to_float(x.prec(), x.rnd())?;
+        Number::Flt(x * n)
+      }
     }
     .trivial()
   }
@@ -238,17 +429,45 @@ impl fmt::Display for Number {
           q.num, q.den
         )
       }
+
+      Number::Flt(x) => {
+        write!(f, "{x}")
+      }
+
+      Number::Cpx(z) => {
+        write!(f, "{z}")
+      }
+
+      Number::Alg(a) => {
+        write!(f, "{a}")
+      }
     }
   }
 }
 
-/// An indeterminate form.
-#[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord, Copy)]
-pub struct Form {}
+/// An indeterminate form, or a computation stopped by its [`Budget`](crate::Budget).
+#[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord, Copy, Default)]
+pub struct Form {
+  halt: Option<Halt>,
+}
+
+impl Form {
+  /// Return the reason the computation was stopped, or `None` for an indeterminate form.
+  pub fn halt(&self) -> Option<Halt> {
+    self.halt
+  }
+
+  pub(crate) fn halted(halt: Halt) -> Form {
+    Form { halt: Some(halt) }
+  }
+}
 
 impl fmt::Display for Form {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    write!(f, "?")
+    match self.halt {
+      Some(halt) => write!(f, "{halt}"),
+      None => write!(f, "?"),
+    }
   }
 }
 
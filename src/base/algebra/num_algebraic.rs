@@ -0,0 +1,530 @@
+use crate::base::algebra::num_float::{Float, Round};
+use crate::base::algebra::num_integer::Integer;
+use crate::base::algebra::num_rational::Rational;
+use crate::base::algebra::poly::Poly;
+use crate::{Form, SymbolicResult};
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A real algebraic number, the unique root of its minimal polynomial in an isolating interval.
+///
+/// The minimal polynomial is monic and irreducible over ℚ, and `[lo, hi]` contains exactly one of its real roots
+/// (`lo = hi` for rationals). Arithmetic is exact: the result of `α + β` or `α*β` is a root of an annihilating
+/// polynomial found by linear algebra in ℚ\[α, β], and is told apart from the other roots of its irreducible
+/// factors by interval arithmetic on refined operands.
+#[derive(Debug, Clone)]
+pub struct Algebraic {
+  poly: Poly,
+  lo: Rational,
+  hi: Rational,
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+  Add,
+  Mul,
+}
+
+impl Algebraic {
+  /// Create the rational `q` as an algebraic number of degree 1.
+  pub fn from_rational(q: Rational) -> Algebraic {
+    let q = q.reduce();
+    Algebraic {
+      poly: Poly::new(vec![-q.clone(), one()]),
+      lo: q.clone(),
+      hi: q,
+    }
+  }
+
+  /// Return the `k`-th real root of `p` (in increasing order, from 0), or `None` if there is none.
+  pub fn root_of(p: &Poly, k: usize) -> Option<Algebraic> {
+    let (lo, hi) = p.real_roots().into_iter().nth(k)?;
+    let (_, factors) = p.factor();
+    let (f, _) = factors.into_iter().find(|(f, _)| f.count_roots(&lo, &hi) == 1)?;
+    Some(Algebraic::isolated(f, lo, hi))
+  }
+
+  /// Return the real `n`-th root of `q`, positive if `q > 0`, or `None` if there is none.
+  pub fn nth_root(q: Rational, n: u64) -> Option<Algebraic> {
+    if n == 0 || (q.is_negative() && n.is_multiple_of(2)) {
+      return None;
+    }
+
+    // ```x^n - q```
+    let p = Poly::monomial(one(), n as usize) - Poly::constant(q);
+    let k = p.real_roots().len().checked_sub(1)?;
+    Algebraic::root_of(&p, k)
+  }
+
+  /// Return the minimal polynomial.
+  pub fn minpoly(&self) -> &Poly {
+    &self.poly
+  }
+
+  /// Return the isolating interval `[lo, hi]`.
+  pub fn interval(&self) -> (&Rational, &Rational) {
+    (&self.lo, &self.hi)
+  }
+
+  /// Return the degree of the minimal polynomial.
+  pub fn deg(&self) -> usize {
+    self.poly.deg().unwrap_or(0)
+  }
+
+  /// Return the value if `self` is rational.
+  pub fn to_rational(&self) -> Option<Rational> {
+    (self.deg() == 1).then(|| self.lo.clone())
+  }
+
+  /// Return the index of `self` among the real roots of its minimal polynomial (in increasing order, from 0).
+  pub fn index(&self) -> usize {
+    if self.deg() == 1 {
+      return 0;
+    }
+
+    let b = self.poly.root_bound();
+    Poly::sturm_count(&self.poly.sturm(), &-b, &self.lo)
+  }
+
+  /// Compare `self` with zero.
+  pub fn sgn(&self) -> Ordering {
+    let mut a = self.clone();
+    loop {
+      match (a.lo.num.ord(), a.hi.num.ord()) {
+        (Ordering::Equal, Ordering::Equal) => return Ordering::Equal,
+        (Ordering::Greater | Ordering::Equal, _) => return Ordering::Greater,
+        (_, Ordering::Less | Ordering::Equal) => return Ordering::Less,
+        _ => a.refine(),
+      }
+    }
+  }
+
+  /// Halve the isolating interval.
+  pub fn refine(&mut self) {
+    if self.deg() <= 1 {
+      return;
+    }
+
+    // The root is irrational: p(mid) != 0 and p changes sign on exactly one half.
+    let mid = ((self.lo.clone() + self.hi.clone()) / Rational::from(Integer::TWO)).reduce();
+    if self.poly.eval(&self.lo).num.ord() == self.poly.eval(&mid).num.ord() {
+      self.lo = mid;
+    } else {
+      self.hi = mid;
+    }
+  }
+
+  /// Return the inverse (reciprocal).
+  pub fn inv(&self) -> SymbolicResult<Algebraic> {
+    if let Some(q) = self.to_rational() {
+      return if q.num == Integer::ZERO {
+        Err(
+          Form::default(), // ```1/0```
+        )
+      } else {
+        Ok(Algebraic::from_rational(Rational::new(q.den, q.num)))
+      };
+    }
+
+    // ```x^n*p(1/x)```, on an interval excluding 0
+    let mut a = self.clone();
+    while a.lo.num.ord() != a.hi.num.ord() {
+      a.refine();
+    }
+    let poly = Poly::new(a.poly.coef().iter().rev().cloned().collect()).monic();
+    let (lo, hi) = ((one() / a.hi).reduce(), (one() / a.lo).reduce());
+    Ok(Algebraic { poly, lo, hi })
+  }
+
+  /// Raise `self` to an integer power.
+  pub fn pow(&self, n: Integer) -> SymbolicResult<Algebraic> {
+    if n.is_negative() {
+      return self.inv()?.pow(-n);
+    }
+    let n = n.abs();
+    if let Some(q) = self.to_rational() {
+      let n = u64::try_from(n).map_err(|_| Form::default())?;
+      return Ok(Algebraic::from_rational(q_pow(&q, n)));
+    }
+
+    // ```β = x^n (mod p)``` then the dependency between the powers of β in ℚ[x]/(p)
+    let m = self.deg();
+    let modulo = |a: Poly| a.div_rem(&self.poly).map_or_else(Poly::zero, |(_, r)| r);
+    let beta = (0..n.bits()).rev().fold(Poly::constant(one()), |acc, i| {
+      let acc = modulo(acc.clone() * acc);
+      if n.bit(i) {
+        modulo(acc * Poly::monomial(one(), 1))
+      } else {
+        acc
+      }
+    });
+    let dense = |a: Poly| (0..m).map(|k| a.coef().get(k).cloned().unwrap_or_else(zero)).collect::<Vec<_>>();
+    let ann = dependency(m, dense(Poly::constant(one())), |v| dense(modulo(Poly::new(v.to_vec()) * beta.clone())));
+
+    let mut a = self.clone();
+    while a.lo.num.ord() != a.hi.num.ord() {
+      a.refine();
+    }
+    let n = u64::try_from(n).map_err(|_| Form::default())?;
+    Ok(Algebraic::identify(ann, || {
+      let (lo, hi) = (q_pow(&a.lo, n), q_pow(&a.hi, n));
+      a.refine();
+      if lo <= hi {
+        (lo, hi)
+      } else {
+        (hi, lo)
+      }
+    }))
+  }
+
+  /// Round to a [`Float`] with precision `prec`.
+  pub fn to_float(&self, prec: usize, rnd: Round) -> Float {
+    let mut a = self.clone();
+    loop {
+      // rounding is monotonic: when both bounds round to the same float, so does the root
+      let lo = Float::from_rational(a.lo.clone(), prec, rnd);
+      let hi = Float::from_rational(a.hi.clone(), prec, rnd);
+      if lo == hi {
+        return lo;
+      }
+      a.refine();
+    }
+  }
+
+  // Helpers
+  fn isolated(poly: Poly, lo: Rational, hi: Rational) -> Algebraic {
+    if poly.deg() == Some(1) {
+      // ```x + c```
+      let c = poly.coef()[0].clone();
+      return Algebraic::from_rational(-c);
+    }
+    Algebraic { poly, lo, hi }
+  }
+
+  // Find the root of `ann` enclosed by the shrinking intervals of `next`.
+  fn identify<F>(ann: Poly, mut next: F) -> Algebraic
+  where
+    F: FnMut() -> (Rational, Rational),
+  {
+    let (_, factors) = ann.factor();
+    let factors: Vec<_> = factors.into_iter().map(|(f, _)| (f.sturm(), f)).collect();
+    loop {
+      let (lo, hi) = next();
+      // roots in the closed interval ```[lo, hi]```
+      let count = |(seq, f): &(Vec<Poly>, Poly)| Poly::sturm_count(seq, &lo, &hi) + (f.eval(&lo).num == Integer::ZERO) as usize;
+      let mut hits = factors.iter().filter(|f| count(f) > 0);
+      if let (Some(f), None) = (hits.next(), hits.next()) {
+        if count(f) == 1 {
+          return Algebraic::isolated(f.1.clone(), lo, hi);
+        }
+      }
+    }
+  }
+
+  fn binop(self, o: Algebraic, op: Op) -> Algebraic {
+    match (self.to_rational(), o.to_rational(), op) {
+      (Some(x), Some(y), Op::Add) => return Algebraic::from_rational(x + y),
+      (Some(x), Some(y), Op::Mul) => return Algebraic::from_rational(x * y),
+      (Some(x), None, _) => return o.rational_op(x, op),
+      (None, Some(y), _) => return self.rational_op(y, op),
+      _ => {}
+    }
+
+    // ```γ = x + y``` or ```γ = x*y``` in ℚ[x, y]/(f(x), g(y)), coefficients of x^i*y^j at i*n + j
+    let (f, g) = (self.poly.coef(), o.poly.coef());
+    let (m, n) = (self.deg(), o.deg());
+    let mul_x = |v: &[Rational]| {
+      let mut w = vec![zero(); m * n];
+      for (idx, c) in v.iter().enumerate().filter(|(_, c)| c.num != Integer::ZERO) {
+        let (i, j) = (idx / n, idx % n);
+        if i + 1 < m {
+          w[idx + n] = (w[idx + n].clone() + c.clone()).reduce();
+        } else {
+          // ```x^m = -f_0 - f_1*x - ... - f_(m-1)*x^(m-1)```
+          for (k, fk) in f.iter().take(m).enumerate() {
+            w[k * n + j] = (w[k * n + j].clone() - c.clone() * fk.clone()).reduce();
+          }
+        }
+      }
+      w
+    };
+    let mul_y = |v: &[Rational]| {
+      let mut w = vec![zero(); m * n];
+      for (idx, c) in v.iter().enumerate().filter(|(_, c)| c.num != Integer::ZERO) {
+        let (i, j) = (idx / n, idx % n);
+        if j + 1 < n {
+          w[idx + 1] = (w[idx + 1].clone() + c.clone()).reduce();
+        } else {
+          for (k, gk) in g.iter().take(n).enumerate() {
+            w[i * n + k] = (w[i * n + k].clone() - c.clone() * gk.clone()).reduce();
+          }
+        }
+      }
+      w
+    };
+
+    let mut start = vec![zero(); m * n];
+    start[0] = one();
+    let ann = dependency(m * n, start, |v| match op {
+      Op::Add => mul_x(v).into_iter().zip(mul_y(v)).map(|(a, b)| (a + b).reduce()).collect(),
+      Op::Mul => mul_x(&mul_y(v)),
+    });
+
+    let (mut a, mut b) = (self.clone(), o.clone());
+    Algebraic::identify(ann, || {
+      let bounds = match op {
+        Op::Add => ((a.lo.clone() + b.lo.clone()).reduce(), (a.hi.clone() + b.hi.clone()).reduce()),
+        Op::Mul => {
+          let p = [a.lo.clone() * b.lo.clone(), a.lo.clone() * b.hi.clone(), a.hi.clone() * b.lo.clone(), a.hi.clone() * b.hi.clone()].map(Rational::reduce);
+          (p.iter().min().cloned().unwrap_or_else(zero), p.iter().max().cloned().unwrap_or_else(zero))
+        }
+      };
+      a.refine();
+      b.refine();
+      bounds
+    })
+  }
+
+  // ```α + q```, ```α*q``` with q rational, by substitution in the minimal polynomial.
+  fn rational_op(self, q: Rational, op: Op) -> Algebraic {
+    let x = Poly::monomial(one(), 1);
+    match op {
+      // ```p(x - q)```
+      Op::Add => Algebraic {
+        poly: self.poly.compose(&(x - Poly::constant(q.clone()))),
+        lo: (self.lo + q.clone()).reduce(),
+        hi: (self.hi + q).reduce(),
+      },
+      Op::Mul if q.num == Integer::ZERO => Algebraic::from_rational(q),
+      // ```p(x/q)```
+      Op::Mul => {
+        let poly = self.poly.compose(&x.scale(&(one() / q.clone()))).monic();
+        let (lo, hi) = ((self.lo * q.clone()).reduce(), (self.hi * q.clone()).reduce());
+        let (lo, hi) = if q.is_negative() { (hi, lo) } else { (lo, hi) };
+        Algebraic { poly, lo, hi }
+      }
+    }
+  }
+}
+
+// First linear dependency between the iterates `v, step(v), step(step(v)), ...` in ℚ^dim, as a monic polynomial.
+fn dependency<F>(dim: usize, mut v: Vec<Rational>, step: F) -> Poly
+where
+  F: Fn(&[Rational]) -> Vec<Rational>,
+{
+  // rows reduced against the previous ones, with the combination of iterates they stand for
+  let mut rows: Vec<(usize, Vec<Rational>, Vec<Rational>)> = vec![];
+  for k in 0..=dim {
+    let mut w = v.clone();
+    let mut comb = vec![zero(); dim + 1];
+    comb[k] = one();
+
+    for (p, row, rc) in &rows {
+      if w[*p].num != Integer::ZERO {
+        let t = (w[*p].clone() / row[*p].clone()).reduce();
+        w.iter_mut().zip(row).for_each(|(a, b)| *a = (a.clone() - t.clone() * b.clone()).reduce());
+        comb.iter_mut().zip(rc).for_each(|(a, b)| *a = (a.clone() - t.clone() * b.clone()).reduce());
+      }
+    }
+
+    match w.iter().position(|c| c.num != Integer::ZERO) {
+      None => return Poly::new(comb).monic(),
+      Some(p) => rows.push((p, w, comb)),
+    }
+    v = step(&v);
+  }
+
+  // dim + 1 vectors in ℚ^dim are dependent
+  unreachable!()
+}
+
+fn zero() -> Rational {
+  Rational::from(Integer::ZERO)
+}
+
+fn one() -> Rational {
+  Rational::from(Integer::ONE)
+}
+
+fn q_pow(q: &Rational, n: u64) -> Rational {
+  Rational::new(q.num.clone().pow(n), q.den.clone().pow(n)).reduce()
+}
+
+impl From<Rational> for Algebraic {
+  fn from(q: Rational) -> Self {
+    Algebraic::from_rational(q)
+  }
+}
+
+impl Add for Algebraic {
+  type Output = Algebraic;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    self.binop(rhs, Op::Add)
+  }
+}
+
+impl Sub for Algebraic {
+  type Output = Algebraic;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    self.binop(-rhs, Op::Add)
+  }
+}
+
+impl Mul for Algebraic {
+  type Output = Algebraic;
+
+  fn mul(self, rhs: Self) -> Self::Output {
+    self.binop(rhs, Op::Mul)
+  }
+}
+
+impl Div for Algebraic {
+  type Output = Algebraic;
+
+  /// Panics if `rhs` is zero.
+  fn div(self, rhs: Self) -> Self::Output {
+    self.binop(rhs.inv().expect("attempt to divide by zero"), Op::Mul)
+  }
+}
+
+impl Neg for Algebraic {
+  type Output = Algebraic;
+
+  /// ```p(-x)```
+  fn neg(self) -> Self::Output {
+    let coef = self.poly.coef().iter().enumerate().map(|(k, c)| if k % 2 == 1 { -c.clone() } else { c.clone() }).collect();
+    Algebraic {
+      poly: Poly::new(coef).monic(),
+      lo: -self.hi,
+      hi: -self.lo,
+    }
+  }
+}
+
+impl Eq for Algebraic {}
+impl PartialEq for Algebraic {
+  fn eq(&self, o: &Algebraic) -> bool {
+    if self.poly != o.poly {
+      return false;
+    }
+
+    // same root iff the isolating intervals share it
+    let lo = (&self.lo).max(&o.lo);
+    let hi = (&self.hi).min(&o.hi);
+    lo <= hi && (Poly::sturm_count(&self.poly.sturm(), lo, hi) > 0 || self.poly.eval(lo).num == Integer::ZERO)
+  }
+}
+
+impl PartialOrd for Algebraic {
+  fn partial_cmp(&self, o: &Algebraic) -> Option<Ordering> {
+    Some(self.cmp(o))
+  }
+}
+
+impl Ord for Algebraic {
+  fn cmp(&self, o: &Algebraic) -> Ordering {
+    if self == o {
+      return Ordering::Equal;
+    }
+
+    let (mut a, mut b) = (self.clone(), o.clone());
+    loop {
+      if a.hi < b.lo {
+        return Ordering::Less;
+      }
+      if b.hi < a.lo {
+        return Ordering::Greater;
+      }
+      a.refine();
+      b.refine();
+    }
+  }
+}
+
+impl Hash for Algebraic {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.poly.hash(state);
+  }
+}
+
+impl fmt::Display for Algebraic {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.to_rational() {
+      Some(q) if q.den == Integer::ONE => write!(f, "{}", q.num),
+      Some(q) => write!(f, "{}/{}", q.num, q.den),
+      None => write!(f, "RootOf({}, {})", self.poly, self.index()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn poly(coef: &[i64]) -> Poly {
+    Poly::from_integers(coef.iter().map(|&c| Integer::from(c)).collect())
+  }
+
+  fn q(n: i64, d: i64) -> Rational {
+    Rational::new(Integer::from(n), Integer::from(d))
+  }
+
+  fn sqrt(n: i64) -> Algebraic {
+    Algebraic::nth_root(q(n, 1), 2).unwrap()
+  }
+
+  #[test]
+  fn arithmetic() {
+    let (s2, s3, s6) = (sqrt(2), sqrt(3), sqrt(6));
+
+    // ```sqrt(2)^2 - 2 = 0```
+    assert_eq!((s2.clone() * s2.clone() - Algebraic::from(q(2, 1))).to_rational(), Some(q(0, 1)));
+    // ```sqrt(2)*sqrt(3) = sqrt(6)```
+    assert_eq!(s2.clone() * s3.clone(), s6);
+    // ```sqrt(2) + sqrt(3)``` is a root of ```x^4 - 10*x^2 + 1```
+    let s = s2.clone() + s3.clone();
+    assert_eq!(s.minpoly(), &poly(&[1, 0, -10, 0, 1]));
+    assert_eq!(s.index(), 3);
+    // ```(sqrt(2) + sqrt(3))^2 = 5 + 2*sqrt(6)```
+    assert_eq!(s.pow(Integer::from(2)), Ok(Algebraic::from(q(5, 1)) + Algebraic::from(q(2, 1)) * s6.clone()));
+    // ```1/(sqrt(3) - sqrt(2)) = sqrt(3) + sqrt(2)```
+    assert_eq!((s3.clone() - s2.clone()).inv(), Ok(s.clone()));
+    assert_eq!(s6.clone() / s3.clone(), s2);
+    assert_eq!(Algebraic::from(q(0, 1)).inv(), Err(Form::default()));
+
+    // ```2^(1/3)^3 = 2```
+    let c2 = Algebraic::nth_root(q(2, 1), 3).unwrap();
+    assert_eq!(c2.pow(Integer::from(3)).unwrap().to_rational(), Some(q(2, 1)));
+    assert_eq!(c2.pow(Integer::from(-6)).unwrap().to_rational(), Some(q(1, 4)));
+    assert_eq!(Algebraic::nth_root(q(-8, 1), 3).unwrap().to_rational(), Some(q(-2, 1)));
+    assert_eq!(Algebraic::nth_root(q(-2, 1), 2), None);
+  }
+
+  #[test]
+  fn order() {
+    let (s2, s3) = (sqrt(2), sqrt(3));
+    assert!(s2 < s3);
+    assert!(-s3.clone() < -s2.clone());
+    assert!(s2.clone() - s3.clone() < Algebraic::from(q(0, 1)));
+    assert_eq!((s2.clone() - s3.clone()).sgn(), Ordering::Less);
+    assert_eq!((s2.clone() * s3.clone()).sgn(), Ordering::Greater);
+    assert_eq!(s2.to_float(53, Round::Nearest), Float::from_integer(Integer::TWO, 53, Round::Nearest).sqrt().unwrap());
+
+    // ```x^5 - x - 1``` has a single real root, ~1.1673
+    let r = Algebraic::root_of(&poly(&[-1, -1, 0, 0, 0, 1]), 0).unwrap();
+    assert!(Algebraic::from(q(11673, 10000)) < r && r < Algebraic::from(q(11674, 10000)));
+    assert_eq!(Algebraic::root_of(&poly(&[-1, -1, 0, 0, 0, 1]), 1), None);
+    assert_eq!(format!("{r}"), "RootOf(x^5 + -1*x + -1, 0)");
+
+    // ```(x^2 - 2)*(x - 1)```
+    let p = poly(&[-2, 0, 1]) * poly(&[-1, 1]);
+    assert_eq!(Algebraic::root_of(&p, 0), Some(-sqrt(2)));
+    assert_eq!(Algebraic::root_of(&p, 1), Some(Algebraic::from(q(1, 1))));
+    assert_eq!(Algebraic::root_of(&p, 2), Some(sqrt(2)));
+  }
+}
@@ -0,0 +1,373 @@
+use crate::base::algebra::num_integer::Integer;
+use crate::base::algebra::num_natural::Natural;
+use crate::base::algebra::num_rational::Rational;
+use crate::{Form, SymbolicResult};
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A Gaussian rational `a + b*i` ∈ ℚ(i).
+///
+/// Both parts are kept reduced (coprime numerator and denominator, positive denominator), so that structural
+/// equality coincides with numerical equality. When both parts are integers, the number is a Gaussian integer
+/// ℤ\[i], for which [`Complex::gcd`] and [`Complex::factor`] are defined.
+#[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord)]
+pub struct Complex {
+  /// Real part.
+  pub(crate) re: Rational,
+  /// Imaginary part.
+  pub(crate) im: Rational,
+}
+
+impl Complex {
+  /// The additive identity `0`.
+  pub const ZERO: Complex = Complex {
+    re: Rational::new(Integer::ZERO, Integer::ONE),
+    im: Rational::new(Integer::ZERO, Integer::ONE),
+  };
+  /// The multiplicative identity `1`.
+  pub const ONE: Complex = Complex {
+    re: Rational::new(Integer::ONE, Integer::ONE),
+    im: Rational::new(Integer::ZERO, Integer::ONE),
+  };
+  /// The imaginary unit `i`.
+  pub const I: Complex = Complex {
+    re: Rational::new(Integer::ZERO, Integer::ONE),
+    im: Rational::new(Integer::ONE, Integer::ONE),
+  };
+
+  /// Create a new [`Complex`] `re + im*i`.
+  ///
+  /// Panics if one of the denominators is zero.
+  pub fn new(re: Rational, im: Rational) -> Complex {
+    Complex {
+      re: re.reduce(), //.
+      im: im.reduce(),
+    }
+  }
+
+  /// Return the real part.
+  pub fn re(&self) -> &Rational {
+    &self.re
+  }
+
+  /// Return the imaginary part.
+  pub fn im(&self) -> &Rational {
+    &self.im
+  }
+
+  /// Return `true` if `self` is `0`.
+  pub fn is_zero(&self) -> bool {
+    self.re.num == Integer::ZERO && self.im.num == Integer::ZERO
+  }
+
+  /// Return `true` if the imaginary part is `0`.
+  pub fn is_real(&self) -> bool {
+    self.im.num == Integer::ZERO
+  }
+
+  /// Return `true` if `self` is a Gaussian integer ℤ\[i].
+  pub fn is_integral(&self) -> bool {
+    self.re.den == Integer::ONE && self.im.den == Integer::ONE
+  }
+
+  /// Return the complex conjugate `a - b*i`.
+  pub fn conj(self) -> Complex {
+    Complex {
+      re: self.re, //.
+      im: -self.im,
+    }
+  }
+
+  /// Return the (field) norm `a^2 + b^2`.
+  pub fn norm(&self) -> Rational {
+    let (a, b) = (self.re.clone(), self.im.clone());
+    (a.clone() * a + b.clone() * b).reduce()
+  }
+
+  /// Return the inverse (reciprocal).
+  pub fn inv(self) -> SymbolicResult<Complex> {
+    if self.is_zero() {
+      return Err(
+        Form::default(), // ```1/0```
+      );
+    }
+
+    // ```1/z = conj(z)/|z|^2```
+    let n = self.norm();
+    let z = self.conj();
+    Ok(Complex::new(z.re / n.clone(), z.im / n))
+  }
+
+  /// Compute the Greatest Common Divisor (GCD) of two Gaussian integers `u` and `v`.
+  ///
+  /// The result is normalized to its associate in the first quadrant (`re > 0`, `im >= 0`), or `0`.
+  /// Returns `None` when one of the arguments is not a Gaussian integer.
+  pub fn gcd(u: Self, v: Self) -> Option<Self> {
+    if !(u.is_integral() && v.is_integral()) {
+      return None;
+    }
+
+    let (mut u, mut v) = (u, v);
+    while !v.is_zero() {
+      // ```u = q*v + r, N(r) <= N(v)/2```
+      let q = Complex::rdiv(&u, &v);
+      let r = u - q * v.clone();
+      (u, v) = (v, r);
+    }
+
+    Some(u.normal().1)
+  }
+
+  /// Factor a nonzero Gaussian integer into `(unit, [(prime, multiplicity)])`.
+  ///
+  /// Primes are normalized to the first quadrant and sorted by norm. Returns `None` when `self` is zero or not a
+  /// Gaussian integer.
+  pub fn factor(self) -> Option<(Complex, Vec<(Complex, u64)>)> {
+    if self.is_zero() || !self.is_integral() {
+      return None;
+    }
+
+    let mut z = self;
+    let mut primes = vec![];
+    for (p, _) in z.norm().num.abs().factor() {
+      let four = Natural::from(4u64);
+      let candidates = if p == Natural::TWO {
+        // ```2 = -i*(1 + i)^2```
+        vec![Complex::from(Integer::ONE) + Complex::I]
+      } else if p.clone() % four == Natural::from(3u64) {
+        // p inert
+        vec![Complex::from(Integer::from(p))]
+      } else {
+        // ```p = π*conj(π), π = gcd(p, x + i) where x^2 = -1 (mod p)```
+        let x = sqrt_neg_one(&p);
+        let pi = Complex::gcd(Complex::from(Integer::from(p)), Complex::from(Integer::from(x)) + Complex::I)?;
+        let conj = pi.clone().conj().normal().1;
+        vec![pi, conj]
+      };
+
+      for pi in candidates {
+        let mut k = 0;
+        while let Some(q) = z.div_exact(&pi) {
+          z = q;
+          k += 1;
+        }
+        if k > 0 {
+          primes.push((pi, k));
+        }
+      }
+    }
+
+    Some((z, primes))
+  }
+
+  // Helpers
+  fn div_exact(&self, d: &Complex) -> Option<Complex> {
+    let q = self.clone() / d.clone();
+    q.is_integral().then_some(q)
+  }
+
+  // ```round(u/v)``` componentwise, for Gaussian integers
+  fn rdiv(u: &Complex, v: &Complex) -> Complex {
+    let n = v.norm().num;
+    let w = u.clone() * v.clone().conj();
+    let round = |x: Integer| (x * Integer::TWO + n.clone()).div_euclid(n.clone() * Integer::TWO);
+    Complex::new(Rational::from(round(w.re.num)), Rational::from(round(w.im.num)))
+  }
+
+  // ```z = u*w``` with u a unit and w in the first quadrant
+  fn normal(self) -> (Complex, Complex) {
+    let mut unit = Complex::ONE;
+    let mut z = self;
+    if z.is_zero() {
+      return (unit, z);
+    }
+
+    while !(z.re.is_positive() && z.re.num != Integer::ZERO && z.im.is_positive()) {
+      // ```z = i*(-i*z)```
+      z = z * -Complex::I;
+      unit = unit * Complex::I;
+    }
+
+    (unit, z)
+  }
+}
+
+// ```x^2 = -1 (mod p)``` for a prime p = 1 (mod 4)
+fn sqrt_neg_one(p: &Natural) -> Natural {
+  let e = (p.clone() - Natural::ONE) / Natural::from(4u64);
+  let mut c = Natural::TWO;
+  loop {
+    // ```x = c^((p - 1)/4)``` is a square root of -1 iff c is a non-residue
+    let x = c.clone().pow_mod(e.clone(), p);
+    if x.clone() * x.clone() % p.clone() == p.clone() - Natural::ONE {
+      return x;
+    }
+    c += Natural::ONE;
+  }
+}
+
+impl From<Integer> for Complex {
+  fn from(z: Integer) -> Self {
+    Complex::from(Rational::from(z))
+  }
+}
+
+impl From<Rational> for Complex {
+  fn from(q: Rational) -> Self {
+    Complex::new(q, Rational::from(Integer::ZERO))
+  }
+}
+
+impl Add for Complex {
+  type Output = Complex;
+
+  /// ```(a + b*i) + (c + d*i) = (a + c) + (b + d)*i```
+  fn add(self, rhs: Self) -> Self::Output {
+    Complex::new(self.re + rhs.re, self.im + rhs.im)
+  }
+}
+
+impl Sub for Complex {
+  type Output = Complex;
+
+  /// ```(a + b*i) - (c + d*i) = (a - c) + (b - d)*i```
+  fn sub(self, rhs: Self) -> Self::Output {
+    Complex::new(self.re - rhs.re, self.im - rhs.im)
+  }
+}
+
+impl Mul for Complex {
+  type Output = Complex;
+
+  /// ```(a + b*i) * (c + d*i) = (a*c - b*d) + (a*d + b*c)*i```
+  fn mul(self, rhs: Self) -> Self::Output {
+    let (a, b) = (self.re, self.im);
+    let (c, d) = (rhs.re, rhs.im);
+
+    Complex::new(
+      a.clone() * c.clone() - b.clone() * d.clone(), //.
+      a * d + b * c,
+    )
+  }
+}
+
+impl Div for Complex {
+  type Output = Complex;
+
+  /// ```(a + b*i) / (c + d*i) = ((a*c + b*d) + (b*c - a*d)*i)/(c^2 + d^2)```
+  ///
+  /// Panics if `rhs` is zero.
+  fn div(self, rhs: Self) -> Self::Output {
+    assert!(!rhs.is_zero(), "attempt to divide by zero");
+
+    let n = rhs.norm();
+    let (a, b) = (self.re, self.im);
+    let (c, d) = (rhs.re, rhs.im);
+
+    Complex::new(
+      (a.clone() * c.clone() + b.clone() * d.clone()) / n.clone(), //.
+      (b * c - a * d) / n,
+    )
+  }
+}
+
+impl Neg for Complex {
+  type Output = Complex;
+
+  fn neg(self) -> Self::Output {
+    Complex {
+      re: -self.re, //.
+      im: -self.im,
+    }
+  }
+}
+
+impl fmt::Display for Complex {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let part = |f: &mut fmt::Formatter, q: &Rational| {
+      if q.den == Integer::ONE {
+        write!(f, "{}", q.num)
+      } else {
+        write!(f, "{}/{}", q.num, q.den)
+      }
+    };
+
+    if self.is_real() {
+      return part(f, &self.re);
+    }
+
+    if self.re.num != Integer::ZERO {
+      part(f, &self.re)?;
+      write!(f, " + ")?;
+    }
+
+    if self.im == Rational::from(Integer::ONE) {
+      write!(f, "i")
+    } else if self.im == Rational::from(Integer::NEG_ONE) {
+      write!(f, "-i")
+    } else {
+      part(f, &self.im)?;
+      write!(f, "*i")
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn z(re: i64, im: i64) -> Complex {
+    Complex::new(Rational::from(Integer::from(re)), Rational::from(Integer::from(im)))
+  }
+
+  fn q(n: i64, d: i64) -> Rational {
+    Rational::new(Integer::from(n), Integer::from(d))
+  }
+
+  #[test]
+  fn arithmetic() {
+    assert_eq!(z(1, 2) * z(3, -1), z(5, 5));
+    assert_eq!(z(1, 2) + z(3, -1), z(4, 1));
+    assert_eq!(Complex::I * Complex::I, z(-1, 0));
+    assert_eq!(z(1, 1).norm(), q(2, 1));
+    assert_eq!(z(3, 4).conj(), z(3, -4));
+    assert_eq!(z(1, 1).inv(), Ok(Complex::new(q(1, 2), q(-1, 2))));
+    assert_eq!(z(5, 5) / z(3, -1), z(1, 2));
+    assert_eq!(Complex::new(q(2, 4), q(-3, -6)), Complex::new(q(1, 2), q(1, 2)));
+    assert_eq!(Complex::ZERO.inv(), Err(Form::default()));
+
+    assert_eq!(format!("{}", z(1, -2)), "1 + -2*i");
+    assert_eq!(format!("{}", z(0, -1)), "-i");
+    assert_eq!(format!("{}", z(0, 2)), "2*i");
+    assert_eq!(format!("{}", Complex::new(q(1, 2), q(3, 4))), "1/2 + 3/4*i");
+  }
+
+  #[test]
+  fn gaussian() {
+    assert_eq!(Complex::gcd(z(5, 0), z(3, 1)), Some(z(1, 2)));
+    assert_eq!(Complex::gcd(z(4, 2), z(-6, -3)), Some(z(2, 1)));
+    assert_eq!(Complex::gcd(z(3, 0), z(0, 0)), Some(z(3, 0)));
+    assert_eq!(Complex::gcd(Complex::new(q(1, 2), q(0, 1)), z(1, 0)), None);
+
+    assert_eq!(z(2, 0).factor(), Some((z(0, -1), vec![(z(1, 1), 2)])));
+    assert_eq!(z(15, 0).factor(), Some((z(0, -1), vec![(z(3, 0), 1), (z(2, 1), 1), (z(1, 2), 1)])));
+    assert_eq!(z(0, 0).factor(), None);
+
+    for w in [z(360, 0), z(-7, 13), z(12, -34), z(0, 65)] {
+      let (u, primes) = w.clone().factor().unwrap();
+      let p = primes.into_iter().fold(u, |acc, (p, k)| (0..k).fold(acc, |acc, _| acc * p.clone()));
+      assert_eq!(p, w);
+    }
+  }
+  #[test]
+  fn inexact() {
+    use crate::base::algebra::{Float, Number, Round};
+
+    // a float never becomes an exact real or imaginary part
+    let x = || Number::Flt(Float::one(53, Round::Nearest));
+    assert_eq!(Number::Cpx(z(1, 2)) + x(), Err(Form::default()));
+    assert_eq!(x() * Number::Cpx(z(1, 2)), Err(Form::default()));
+    assert_eq!(Number::Cpx(z(1, 2)) + Number::Int(Integer::from(1)), Ok(Number::Cpx(z(2, 2))));
+  }
+}
@@ -0,0 +1,358 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::sync::Arc;
+
+use super::poly::GfPoly;
+use crate::{Integer, Natural, Random, Rational};
+
+/// A finite field `GF(p^n) = 𝔽_p[x]/(m(x))`, with `m` a monic irreducible polynomial of degree `n` over `𝔽_p`.
+///
+/// Prime fields (`n = 1`) use the modulus `m(x) = x`. A field is a cheap shared handle; elements of different fields
+/// cannot be mixed.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FiniteField(Arc<Spec>);
+
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+struct Spec {
+  /// The characteristic.
+  p: Natural,
+  /// The monic modulus, from the constant term up.
+  modulus: Vec<Natural>,
+}
+
+/// An element of a [`FiniteField`], a polynomial of degree below `n` over `𝔽_p`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Gf {
+  field: FiniteField,
+  /// Coefficients modulo `p`, from the constant term up, without trailing zeros.
+  coef: Vec<Natural>,
+}
+
+impl FiniteField {
+  /// Create the prime field `𝔽_p`, or `None` if `p` is not prime.
+  pub fn prime(p: Natural) -> Option<FiniteField> {
+    p.is_prime().then(|| {
+      FiniteField(Arc::new(Spec {
+        p, //.
+        modulus: vec![Natural::ZERO, Natural::ONE],
+      }))
+    })
+  }
+
+  /// Create the field `𝔽_p[x]/(m(x))`, or `None` if `m` is not an irreducible polynomial over a prime field.
+  pub fn extension(m: &GfPoly) -> Option<FiniteField> {
+    let base = m.field();
+    if base.degree() != 1 || !m.is_irreducible() {
+      return None;
+    }
+
+    let modulus = m.clone().monic().coef().iter().map(|c| c.coef.first().cloned().unwrap_or(Natural::ZERO)).collect();
+    Some(FiniteField(Arc::new(Spec { p: base.char().clone(), modulus })))
+  }
+
+  /// Create `GF(p^n)` from a primitive polynomial, so that the class of `x` generates the multiplicative group,
+  /// or `None` if `p` is not prime or `n = 0`.
+  pub fn galois(p: Natural, n: usize) -> Option<FiniteField> {
+    let base = FiniteField::prime(p)?;
+    match n {
+      0 => None,
+      1 => Some(base),
+      n => FiniteField::extension(&GfPoly::primitive(&base, n)),
+    }
+  }
+
+  /// Return the characteristic `p`.
+  pub fn char(&self) -> &Natural {
+    &self.0.p
+  }
+
+  /// Return the degree `n` over the prime field.
+  pub fn degree(&self) -> usize {
+    self.0.modulus.len() - 1
+  }
+
+  /// Return the number of elements `p^n`.
+  pub fn order(&self) -> Natural {
+    self.char().clone().pow(self.degree() as u64)
+  }
+
+  /// Return the modulus, as a polynomial over the prime field.
+  pub fn modulus(&self) -> GfPoly {
+    let base = self.base();
+    GfPoly::new(&base, self.0.modulus.iter().map(|c| base.element(vec![c.clone()])).collect())
+  }
+
+  /// Return the prime subfield `𝔽_p`.
+  pub fn base(&self) -> FiniteField {
+    match self.degree() {
+      1 => self.clone(),
+      _ => FiniteField::prime(self.char().clone()).expect("the characteristic is prime"),
+    }
+  }
+
+  /// Return `0`.
+  pub fn zero(&self) -> Gf {
+    Gf { field: self.clone(), coef: vec![] }
+  }
+
+  /// Return `1`.
+  pub fn one(&self) -> Gf {
+    self.element(vec![Natural::ONE])
+  }
+
+  /// Return the class of `x`, a root of the modulus (`0` in a prime field).
+  pub fn root(&self) -> Gf {
+    self.element(vec![Natural::ZERO, Natural::ONE])
+  }
+
+  /// Return the element `c_0 + c_1*x + ... + c_k*x^k`, reduced modulo `p` and the modulus.
+  pub fn element(&self, coef: Vec<Natural>) -> Gf {
+    let p = self.char();
+    let coef = fp_rem(coef.into_iter().map(|c| c % p.clone()).collect(), &self.0.modulus, p);
+    Gf { field: self.clone(), coef }
+  }
+
+  /// Return the image of an integer.
+  pub fn from_integer(&self, z: &Integer) -> Gf {
+    let p = Integer::from(self.char().clone());
+    self.element(vec![z.clone().rem_euclid(p).abs()])
+  }
+
+  /// Return the image of a rational, or `None` if its denominator is divisible by `p`.
+  pub fn from_rational(&self, q: &Rational) -> Option<Gf> {
+    Some(self.from_integer(&q.num) * self.from_integer(&q.den).inv()?)
+  }
+
+  /// Return the `i`-th element, in the order of the base `p` digits `c_0 + c_1*p + ...` of `i < p^n`.
+  pub fn element_at(&self, mut i: Natural) -> Gf {
+    let mut coef = vec![];
+    for _ in 0..self.degree() {
+      let (q, r) = i.div_rem(self.char().clone());
+      coef.push(r);
+      i = q;
+    }
+    self.element(coef)
+  }
+
+  /// Return a uniform random element.
+  pub fn random(&self, rng: &mut Random) -> Gf {
+    let coef = (0..self.degree()).map(|_| rng.natural_below(self.char())).collect();
+    self.element(coef)
+  }
+
+  /// Return the first generator of the multiplicative group, in the order of [`FiniteField::element_at`].
+  pub fn primitive_element(&self) -> Gf {
+    let mut i = Natural::ONE;
+    loop {
+      let g = self.element_at(i.clone());
+      if g.is_primitive() {
+        return g;
+      }
+      i.incr();
+    }
+  }
+}
+
+impl Gf {
+  /// Return the field of the element.
+  pub fn field(&self) -> &FiniteField {
+    &self.field
+  }
+
+  /// Return the coefficients `[c_0, c_1, ..., c_k]` over the prime field.
+  pub fn coef(&self) -> &[Natural] {
+    &self.coef
+  }
+
+  /// Return `true` if the element is `0`.
+  pub fn is_zero(&self) -> bool {
+    self.coef.is_empty()
+  }
+
+  /// Return `true` if the element is `1`.
+  pub fn is_one(&self) -> bool {
+    self.coef == [Natural::ONE]
+  }
+
+  /// Raise `self` to the power of `e`.
+  pub fn pow(&self, e: &Natural) -> Gf {
+    let mut r = self.field.one();
+    for i in (0..e.bits()).rev() {
+      r = r.clone() * r;
+      if e.bit(i) {
+        r = r * self.clone();
+      }
+    }
+    r
+  }
+
+  /// Return the multiplicative inverse, or `None` for `0`.
+  pub fn inv(&self) -> Option<Gf> {
+    // ```a^-1 = a^(q - 2)```
+    (!self.is_zero()).then(|| self.pow(&(self.field.order() - Natural::TWO)))
+  }
+
+  /// Apply the Frobenius automorphism `a -> a^p`.
+  pub fn frobenius(&self) -> Gf {
+    self.pow(self.field.char())
+  }
+
+  /// Return `true` if the element generates the multiplicative group.
+  pub fn is_primitive(&self) -> bool {
+    // ```a^((q - 1)/r) != 1``` for every prime ```r | q - 1```
+    let q1 = self.field.order() - Natural::ONE;
+    !self.is_zero() && q1.clone().factor().into_iter().all(|(r, _)| !self.pow(&(q1.clone() / r)).is_one())
+  }
+
+  fn same_field(&self, o: &Gf) {
+    assert!(self.field == o.field, "elements of different fields");
+  }
+}
+
+impl Add for Gf {
+  type Output = Gf;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    self.same_field(&rhs);
+    let p = self.field.char();
+    let len = self.coef.len().max(rhs.coef.len());
+    let at = |v: &[Natural], i: usize| v.get(i).cloned().unwrap_or(Natural::ZERO);
+    let coef = fp_trim((0..len).map(|i| (at(&self.coef, i) + at(&rhs.coef, i)) % p.clone()).collect());
+    Gf { field: self.field, coef }
+  }
+}
+
+impl Sub for Gf {
+  type Output = Gf;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    self + -rhs
+  }
+}
+
+impl Neg for Gf {
+  type Output = Gf;
+
+  fn neg(self) -> Self::Output {
+    let p = self.field.char().clone();
+    let coef = self.coef.into_iter().map(|c| if c == Natural::ZERO { c } else { p.clone() - c }).collect();
+    Gf { field: self.field, coef }
+  }
+}
+
+impl Mul for Gf {
+  type Output = Gf;
+
+  fn mul(self, rhs: Self) -> Self::Output {
+    self.same_field(&rhs);
+    let p = self.field.char();
+    let mut coef = vec![Natural::ZERO; (self.coef.len() + rhs.coef.len()).saturating_sub(1)];
+    for (i, x) in self.coef.iter().enumerate() {
+      for (j, y) in rhs.coef.iter().enumerate() {
+        coef[i + j] = (coef[i + j].clone() + x.clone() * y.clone()) % p.clone();
+      }
+    }
+    let coef = fp_rem(coef, &self.field.0.modulus, p);
+    Gf { field: self.field, coef }
+  }
+}
+
+impl Div for Gf {
+  type Output = Gf;
+
+  // division is the multiplication by the inverse
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn div(self, rhs: Self) -> Self::Output {
+    self * rhs.inv().expect("attempt to divide by zero")
+  }
+}
+
+impl fmt::Display for FiniteField {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self.degree() {
+      1 => write!(f, "GF({})", self.char()),
+      n => write!(f, "GF({}^{n})", self.char()),
+    }
+  }
+}
+
+impl fmt::Display for Gf {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.is_zero() {
+      return write!(f, "0");
+    }
+
+    let terms: Vec<_> = (self.coef.iter().enumerate().rev())
+      .filter(|(_, c)| **c != Natural::ZERO)
+      .map(|(k, c)| match (k, c == &Natural::ONE) {
+        (0, _) => format!("{c}"),
+        (1, true) => "x".to_string(),
+        (1, false) => format!("{c}*x"),
+        (k, true) => format!("x^{k}"),
+        (k, false) => format!("{c}*x^{k}"),
+      })
+      .collect();
+    write!(f, "{}", terms.join(" + "))
+  }
+}
+
+fn fp_trim(mut a: Vec<Natural>) -> Vec<Natural> {
+  while a.last() == Some(&Natural::ZERO) {
+    a.pop();
+  }
+  a
+}
+
+// Remainder of `a` by the monic `m` over ```𝔽_p```.
+fn fp_rem(a: Vec<Natural>, m: &[Natural], p: &Natural) -> Vec<Natural> {
+  let dm = m.len() - 1;
+  let mut r = fp_trim(a);
+  while r.len() > dm {
+    // ```r = r - c*x^k*m```
+    let k = r.len() - 1 - dm;
+    let c = r[r.len() - 1].clone();
+    for (i, y) in m.iter().enumerate() {
+      let cy = c.clone() * y.clone() % p.clone();
+      r[i + k] = (r[i + k].clone() + p.clone() - cy) % p.clone();
+    }
+    r = fp_trim(r);
+  }
+  r
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn arithmetic() {
+    assert_eq!(FiniteField::prime(Natural::from(9u64)), None);
+    let f7 = FiniteField::prime(Natural::from(7u64)).expect("7 is prime");
+    let three = f7.from_integer(&Integer::from(3));
+    assert_eq!(three.clone() * three.inv().expect("3 != 0"), f7.one());
+    assert_eq!(f7.from_integer(&Integer::from(-4)), three);
+    assert_eq!(f7.from_rational(&Rational::new(Integer::ONE, Integer::TWO)), Some(f7.from_integer(&Integer::from(4))));
+    assert_eq!(f7.from_rational(&Rational::new(Integer::ONE, Integer::from(14))), None);
+    assert_eq!(f7.primitive_element(), three);
+
+    // ```GF(3^4)```: every nonzero element is invertible, and Frobenius is additive
+    let f81 = FiniteField::galois(Natural::from(3u64), 4).expect("3 is prime");
+    assert_eq!((f81.order(), f81.to_string()), (Natural::from(81u64), "GF(3^4)".to_string()));
+    assert!(f81.root().is_primitive());
+    let elements: Vec<_> = (0..81u64).map(|i| f81.element_at(Natural::from(i))).collect();
+    for a in &elements[1..] {
+      assert!((a.clone() * a.inv().expect("a != 0")).is_one());
+      let b = a.clone() * f81.root() + f81.one();
+      assert_eq!((a.clone() + b.clone()).frobenius(), a.frobenius() + b.frobenius());
+      assert_eq!(a.pow(&Natural::from(81u64)), a.clone());
+    }
+    assert_eq!(elements.iter().filter(|a| a.is_primitive()).count(), 32);
+
+    // ```x^2 = -x - 1``` in ```GF(4) = 𝔽_2[x]/(x^2 + x + 1)```
+    let f4 = FiniteField::galois(Natural::TWO, 2).expect("2 is prime");
+    let x = f4.root();
+    assert_eq!(x.clone() * x.clone(), x.clone() + f4.one());
+    assert_eq!((x.clone() * x.clone()).to_string(), "x + 1");
+    assert_eq!(x.frobenius().frobenius(), x);
+  }
+}
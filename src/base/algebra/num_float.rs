@@ -0,0 +1,1169 @@
+use crate::base::algebra::num_integer::Integer;
+use crate::base::algebra::num_natural::Natural;
+use crate::base::algebra::num_rational::Rational;
+use crate::base::algebra::repr::*;
+use crate::{Form, SymbolicResult};
+use Sign::*;
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// IEEE 754 double: precision, magnitudes ```e``` (```2^(e - 1) <= |x| < 2^e```) of normal numbers and exponent bias.
+const F64_PREC: i64 = 53;
+const F64_EMIN: i64 = -1021;
+const F64_EMAX: i64 = 1024;
+const F64_BIAS: i64 = 1023;
+
+/// A rounding mode for floating point operations.
+#[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord, Copy)]
+pub enum Round {
+  /// Round to nearest, ties to even.
+  Nearest,
+  /// Round towards zero.
+  Zero,
+  /// Round towards negative infinity.
+  Floor,
+  /// Round towards positive infinity.
+  Ceil,
+}
+
+/// An arbitrary precision binary floating point number `(-1)^s*m*2^e`.
+///
+/// Arithmetic operations (`+`, `-`, `*`, `/`) and [`Float::sqrt`] are correctly rounded to the precision
+/// (in bits) and rounding mode of the result, which are taken from the left operand (the precision being the
+/// largest of both operands). Elementary functions and constants are evaluated with guard bits and rounded once
+/// to the target precision, i.e. their error is below one unit in the last place.
+#[derive(Debug, Clone)]
+pub struct Float {
+  /// Sign.
+  pub(crate) sgn: Sign,
+  /// Mantissa (odd or zero).
+  pub(crate) man: Natural,
+  /// Binary exponent.
+  pub(crate) exp: i64,
+  /// Precision in bits.
+  pub(crate) prec: usize,
+  /// Rounding mode.
+  pub(crate) rnd: Round,
+}
+
+impl Float {
+  /// The default precision, matching IEEE 754 double precision.
+  pub const DEFAULT_PREC: usize = 53;
+
+  /// Create a zero with precision `prec`.
+  pub fn zero(prec: usize, rnd: Round) -> Float {
+    Float::from_parts(Positive, Natural::ZERO, 0, prec, rnd)
+  }
+
+  /// Create a one with precision `prec`.
+  pub fn one(prec: usize, rnd: Round) -> Float {
+    Float::from_parts(Positive, Natural::ONE, 0, prec, rnd)
+  }
+
+  /// Round an integer to precision `prec`.
+  pub fn from_integer(z: Integer, prec: usize, rnd: Round) -> Float {
+    Float::from_parts(z.sgn, z.mag, 0, prec, rnd)
+  }
+
+  /// Round a rational to precision `prec`.
+  pub fn from_rational(q: Rational, prec: usize, rnd: Round) -> Float {
+    let (num_bits, den_bits) = (q.num.mag.bits().max(1), q.den.mag.bits().max(1));
+    let num = Float::from_parts(q.num.sgn, q.num.mag, 0, num_bits, rnd);
+    let den = Float::from_parts(q.den.sgn, q.den.mag, 0, den_bits, rnd);
+    num.div_round(den, prec, rnd).expect("rational with a zero denominator")
+  }
+
+  /// Return the precision (in bits).
+  pub fn prec(&self) -> usize {
+    self.prec
+  }
+
+  /// Return the rounding mode.
+  pub fn rnd(&self) -> Round {
+    self.rnd
+  }
+
+  /// Round `self` to a new precision `prec`.
+  pub fn with_prec(self, prec: usize) -> Float {
+    let rnd = self.rnd;
+    Float::from_parts(self.sgn, self.man, self.exp, prec, rnd)
+  }
+
+  /// Change the rounding mode of `self`.
+  pub fn with_rnd(self, rnd: Round) -> Float {
+    Float { rnd, ..self }
+  }
+
+  /// Return `true` if `self` is zero.
+  pub fn is_zero(&self) -> bool {
+    self.man == Natural::ZERO
+  }
+
+  /// Return `true` if `self` is strictly negative.
+  pub fn is_negative(&self) -> bool {
+    self.sgn == Negative
+  }
+
+  /// Compute the absolute value of `self`.
+  pub fn abs(self) -> Float {
+    Float { sgn: Positive, ..self }
+  }
+
+  /// Compute `self*2^k` (exact).
+  pub fn ldexp(self, k: i64) -> Float {
+    if self.is_zero() {
+      self
+    } else {
+      Float { exp: self.exp + k, ..self }
+    }
+  }
+
+  /// Return the binary order of magnitude `e` such that `2^(e - 1) <= |self| < 2^e`.
+  pub fn magnitude(&self) -> Option<i64> {
+    if self.is_zero() {
+      None
+    } else {
+      Some(self.top())
+    }
+  }
+
+  /// Convert to the exact [`Rational`] value.
+  pub fn to_rational(&self) -> Rational {
+    let num = Integer::from_sgn(self.sgn, self.man.clone());
+    if self.exp >= 0 {
+      Rational::from(num << self.exp as usize)
+    } else {
+      Rational::new(
+        num, //.
+        Integer::from(Natural::ONE << self.exp.unsigned_abs() as usize),
+      )
+    }
+  }
+
+  /// Convert to the nearest [`f64`] (ties to even), overflowing to infinity and underflowing to zero.
+  pub fn to_f64(&self) -> f64 {
+    let Some(e) = self.magnitude() else {
+      return 0.0;
+    };
+
+    let abs = match e + F64_PREC - F64_EMIN {
+      _ if e > F64_EMAX => f64::INFINITY,
+      // ```2^-1074 <= |x|```, with less precision for subnormals
+      prec if prec >= 1 => self.clone().round(prec.min(F64_PREC) as usize, Round::Nearest).compose_f64(),
+      // ```2^-1075 < |x| < 2^-1074``` rounds to the smallest subnormal, the tie to zero
+      0 if self.man != Natural::ONE => f64::from_bits(1),
+      _ => 0.0,
+    };
+    if matches!(self.sgn, Negative) {
+      -abs
+    } else {
+      abs
+    }
+  }
+
+  /// Round to an integer with rounding mode `rnd`.
+  pub fn to_integer(&self, rnd: Round) -> Integer {
+    if self.exp >= 0 {
+      return Integer::from_sgn(self.sgn, self.man.clone() << self.exp as usize);
+    }
+
+    let shift = self.exp.unsigned_abs() as usize;
+    let kept = self.man.clone() >> shift;
+    let half = self.man.bit(shift - 1);
+    let sticky = self.man.trailing_zeros() < shift - 1;
+    let kept = if Float::round_up(self.sgn, rnd, kept.bit(0), half, sticky) { kept + Natural::ONE } else { kept };
+    Integer::from_sgn(self.sgn, kept)
+  }
+
+  /// Find the closest rational to the exact value of `self` with a denominator at most `max_den`.
+  pub fn limit_denominator(&self, max_den: Natural) -> Rational {
+    self.to_rational().limit_denominator(max_den)
+  }
+
+  /// Compute the correctly rounded square root.
+  pub fn sqrt(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    self.sqrt_round(prec, rnd)
+  }
+
+  /// Compute the exponential `exp(self)`, or fail if it is out of the exponent range.
+  pub fn exp(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    if self.is_zero() {
+      return Ok(Float::one(prec, rnd));
+    }
+
+    let w = Float::guard(prec);
+    Float::exp_work(self, w).map(|exp| exp.round(prec, rnd))
+  }
+
+  /// Compute the natural logarithm `log(self)`, for `self > 0`.
+  pub fn log(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    if self.is_zero() || self.is_negative() {
+      return Err(
+        Form::default(), // ```log(x), x <= 0```
+      );
+    }
+
+    let w = Float::guard(prec);
+    Ok(Float::log_work(self, w).round(prec, rnd))
+  }
+
+  /// Compute the power `self^o` (real valued).
+  pub fn pow(self, o: Float) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec.max(o.prec), self.rnd);
+    let w = Float::guard(prec);
+
+    if o.is_zero() {
+      return Ok(Float::one(prec, rnd));
+    }
+    if self.is_zero() {
+      return if o.is_negative() {
+        Err(
+          Form::default(), // ```0^-y```
+        )
+      } else {
+        Ok(Float::zero(prec, rnd))
+      };
+    }
+
+    let int = o.exp >= 0 || o.man.trailing_zeros() as i64 >= -o.exp;
+    if int && o.top() <= 32 {
+      // ```x^n, n ∈ ℤ``` by squaring
+      let n = o.to_integer(Round::Zero);
+      let neg = n.is_negative();
+      let mut n = n.abs();
+      let w = w + 2 * o.top().max(0) as usize;
+      let mut base = self.round(w, Round::Nearest);
+      let mut acc = Float::one(w, Round::Nearest);
+      while n != Natural::ZERO {
+        if n.bit(0) {
+          acc = acc * base.clone();
+        }
+        base = base.clone() * base;
+        n >>= 1;
+      }
+      return if neg { Float::one(w, Round::Nearest).div_round(acc, prec, rnd) } else { Ok(acc.round(prec, rnd)) };
+    }
+
+    if self.is_negative() {
+      return Err(
+        Form::default(), // ```(-x)^y, y ∉ ℤ```
+      );
+    }
+
+    // ```x^y = exp(y*log(x))```
+    let extra = (o.top() + self.top().unsigned_abs().max(1).ilog2() as i64).max(0) as usize;
+    let log = Float::log_work(self, w + extra);
+    Float::exp_work(log * o.round(w + extra, Round::Nearest), w).map(|pow| pow.round(prec, rnd))
+  }
+
+  /// Compute the sine `sin(self)`.
+  pub fn sin(self) -> Float {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let (sin, _) = Float::sin_cos_work(self, Float::guard(prec));
+    sin.round(prec, rnd)
+  }
+
+  /// Compute the cosine `cos(self)`.
+  pub fn cos(self) -> Float {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let (_, cos) = Float::sin_cos_work(self, Float::guard(prec));
+    cos.round(prec, rnd)
+  }
+
+  /// Compute the tangent `tan(self)`.
+  pub fn tan(self) -> Float {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let (sin, cos) = Float::sin_cos_work(self, Float::guard(prec));
+    sin.div_round(cos, prec, rnd).expect("cos(x) != 0 for any binary float x")
+  }
+
+  /// Compute the inverse tangent `arctan(self)`.
+  pub fn arctan(self) -> Float {
+    let (prec, rnd) = (self.prec, self.rnd);
+    Float::atan_work(self, Float::guard(prec)).round(prec, rnd)
+  }
+
+  /// Compute the inverse sine `arcsin(self)`, for `|self| <= 1`.
+  pub fn arcsin(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let w = Float::guard(prec);
+    let one = Float::one(w, Round::Nearest);
+
+    match self.clone().abs().cmp(&one) {
+      Ordering::Greater => Err(
+        Form::default(), // ```arcsin(x), |x| > 1```
+      ),
+      // ```arcsin(±1) = ±π/2```
+      Ordering::Equal => Ok(Float::pi_work(w).ldexp(-1).with_sgn(self.sgn).round(prec, rnd)),
+      // ```arcsin(x) = arctan(x/sqrt((1 - x)*(1 + x)))```
+      Ordering::Less => {
+        let x = self.round(w, Round::Nearest);
+        let den = ((one.clone() - x.clone()) * (one + x.clone())).sqrt_work();
+        Ok(Float::atan_work(x / den, w).round(prec, rnd))
+      }
+    }
+  }
+
+  /// Compute the inverse cosine `arccos(self)`, for `|self| <= 1`.
+  pub fn arccos(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let w = Float::guard(prec);
+    let one = Float::one(w, Round::Nearest);
+
+    if self.clone().abs() > one {
+      return Err(
+        Form::default(), // ```arccos(x), |x| > 1```
+      );
+    }
+
+    let x = self.round(w, Round::Nearest);
+    if x == -one.clone() {
+      // ```arccos(-1) = π```
+      return Ok(Float::pi_work(w).round(prec, rnd));
+    }
+
+    // ```arccos(x) = 2*arctan(sqrt((1 - x)/(1 + x)))```
+    let arg = ((one.clone() - x.clone()) / (one + x)).sqrt_work();
+    Ok(Float::atan_work(arg, w).ldexp(1).round(prec, rnd))
+  }
+
+  /// Compute the hyperbolic sine `sinh(self)`, or fail if it is out of the exponent range.
+  pub fn sinh(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let w = Float::guard(prec);
+    Float::sinh_work(self, w).map(|sinh| sinh.round(prec, rnd))
+  }
+
+  /// Compute the hyperbolic cosine `cosh(self)`, or fail if it is out of the exponent range.
+  pub fn cosh(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let w = Float::guard(prec);
+
+    // ```cosh(x) = (exp(x) + exp(-x))/2```
+    let exp = Float::exp_work(self.abs(), w)?;
+    let inv = Float::one(w, Round::Nearest) / exp.clone();
+    Ok((exp + inv).ldexp(-1).round(prec, rnd))
+  }
+
+  /// Compute the hyperbolic tangent `tanh(self)`.
+  pub fn tanh(self) -> Float {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let w = Float::guard(prec);
+    let sgn = self.sgn;
+    let one = Float::one(w, Round::Nearest);
+
+    if self.top() <= 0 {
+      // ```tanh(x) = sinh(x)/sqrt(1 + sinh(x)^2)```
+      let sinh = Float::sinh_work(self, w).expect("sinh(x), |x| <= 1");
+      let cosh = (one + sinh.clone() * sinh.clone()).sqrt_work();
+      sinh.div_round(cosh, prec, rnd).expect("cosh(x) >= 1")
+    } else {
+      // ```tanh(x) = sgn(x)*(1 - exp(-2|x|))/(1 + exp(-2|x|))```
+      // ```exp(-2|x|) < 2^-w``` for large ```|x|```, only its sign matters for rounding
+      let tiny = Float::one(w, Round::Nearest).ldexp(-2 * w as i64);
+      let exp = if self.top() > (usize::BITS - w.leading_zeros()) as i64 {
+        tiny
+      } else {
+        Float::exp_work(self.abs().ldexp(1).neg(), w).unwrap_or(tiny)
+      };
+      ((one.clone() - exp.clone()) / (one + exp)).with_sgn(sgn).round(prec, rnd)
+    }
+  }
+
+  /// Compute the inverse hyperbolic sine `arsinh(self)`.
+  pub fn arsinh(self) -> Float {
+    let (prec, rnd) = (self.prec, self.rnd);
+    if self.is_zero() {
+      return self;
+    }
+
+    // ```arsinh(x) = sgn(x)*log(|x| + sqrt(x^2 + 1))```
+    let w = Float::guard(prec) + self.top().min(0).unsigned_abs() as usize;
+    let sgn = self.sgn;
+    let x = self.abs().round(w, Round::Nearest);
+    let arg = x.clone() + (x.clone() * x + Float::one(w, Round::Nearest)).sqrt_work();
+    Float::log_work(arg, w).with_sgn(sgn).round(prec, rnd)
+  }
+
+  /// Compute the inverse hyperbolic cosine `arcosh(self)`, for `self >= 1`.
+  pub fn arcosh(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    let w = Float::guard(prec);
+    let one = Float::one(w, Round::Nearest);
+
+    if self < one {
+      return Err(
+        Form::default(), // ```arcosh(x), x < 1```
+      );
+    }
+
+    // ```arcosh(x) = log(x + sqrt((x - 1)*(x + 1)))```
+    let dx = self.clone().round(w, Round::Nearest) - one.clone();
+    let w = w + dx.magnitude().map_or(0, |m| m.min(0).unsigned_abs() as usize);
+    let x = self.round(w, Round::Nearest);
+    let arg = x.clone() + (dx * (x + one)).round(w, Round::Nearest).sqrt_work();
+    Ok(Float::log_work(arg, w).round(prec, rnd))
+  }
+
+  /// Compute the inverse hyperbolic tangent `artanh(self)`, for `|self| < 1`.
+  pub fn artanh(self) -> SymbolicResult<Float> {
+    let (prec, rnd) = (self.prec, self.rnd);
+    if self.is_zero() {
+      return Ok(self);
+    }
+
+    let w = Float::guard(prec) + self.top().min(0).unsigned_abs() as usize;
+    let one = Float::one(w, Round::Nearest);
+    if self.clone().abs() >= one {
+      return Err(
+        Form::default(), // ```artanh(x), |x| >= 1```
+      );
+    }
+
+    // ```artanh(x) = log((1 + x)/(1 - x))/2```
+    let x = self.round(w, Round::Nearest);
+    let arg = (one.clone() + x.clone()) / (one - x);
+    Ok(Float::log_work(arg, w).ldexp(-1).round(prec, rnd))
+  }
+
+  /// Compute Archimede's constant π.
+  pub fn pi(prec: usize, rnd: Round) -> Float {
+    Float::pi_work(Float::guard(prec)).round(prec, rnd)
+  }
+
+  /// Compute Euler's number e.
+  pub fn e(prec: usize, rnd: Round) -> Float {
+    Float::exp_work(Float::one(prec, rnd), Float::guard(prec)).expect("exp(1)").round(prec, rnd)
+  }
+
+  /// Compute the natural logarithm of 2.
+  pub fn ln2(prec: usize, rnd: Round) -> Float {
+    Float::ln2_work(Float::guard(prec)).round(prec, rnd)
+  }
+
+  /// Format the value in decimal with `digits` significant digits (rounded to nearest).
+  pub fn to_decimal(&self, digits: usize) -> String {
+    let sgn = if self.is_negative() { "-" } else { "" };
+    if self.is_zero() {
+      return "0.0".to_string();
+    }
+
+    let (n, d) = self.decimal(digits);
+    let mut s = format!("{n}");
+    while s.len() > 1 && s.ends_with('0') {
+      s.pop();
+    }
+
+    if (-5..21).contains(&d) {
+      if d >= 0 {
+        let int = d as usize + 1;
+        if s.len() <= int {
+          format!("{sgn}{s}{}.0", "0".repeat(int - s.len()))
+        } else {
+          format!("{sgn}{}.{}", &s[..int], &s[int..])
+        }
+      } else {
+        format!("{sgn}0.{}{s}", "0".repeat(d.unsigned_abs() as usize - 1))
+      }
+    } else {
+      let frac = if s.len() > 1 { &s[1..] } else { "0" };
+      format!("{sgn}{}.{frac}e{d}", &s[..1])
+    }
+  }
+
+  /// Return `(N, d)` such that `|self| ≈ N*10^(d - digits + 1)` with `10^(digits - 1) <= N < 10^digits` (rounded to nearest).
+  pub(crate) fn decimal(&self, digits: usize) -> (Natural, i64) {
+    let digits = digits.max(1);
+    let ten = |k: usize| Natural::from(10u64).pow(k as u64);
+    let lo = ten(digits - 1);
+    let hi = ten(digits);
+    let mut d = (self.top() - 1) * 30103 / 100000 - 1;
+
+    loop {
+      let scale = digits as i64 - 1 - d;
+      let (mut num, mut den) = (self.man.clone(), Natural::ONE);
+      if self.exp >= 0 {
+        num <<= self.exp as usize;
+      } else {
+        den <<= self.exp.unsigned_abs() as usize;
+      }
+      if scale >= 0 {
+        num *= ten(scale as usize);
+      } else {
+        den *= ten(scale.unsigned_abs() as usize);
+      }
+
+      let (q, r) = num.div_rem(den.clone());
+      let q = match (r << 1).cmp(&den) {
+        Ordering::Greater => q + Natural::ONE,
+        Ordering::Equal if q.bit(0) => q + Natural::ONE,
+        _ => q,
+      };
+
+      if q == hi {
+        return (lo, d + 1);
+      } else if q > hi {
+        d += 1;
+      } else if q < lo {
+        d -= 1;
+      } else {
+        return (q, d);
+      }
+    }
+  }
+
+  // Rounding
+
+  /// The working precision used for elementary functions.
+  fn guard(prec: usize) -> usize {
+    prec + 2 * (usize::BITS - prec.leading_zeros()) as usize + 16
+  }
+
+  fn top(&self) -> i64 {
+    self.exp + self.man.bits() as i64
+  }
+
+  // `|self|`, which fits in the precision of a double at its magnitude
+  fn compose_f64(&self) -> f64 {
+    let e = self.top();
+    if e > F64_EMAX {
+      return f64::INFINITY;
+    }
+
+    let man = u64::try_from(self.man.clone()).expect("at most 53 bits");
+    let bits = if e >= F64_EMIN {
+      // ```1.f*2^(e - 1)```
+      let frac = (man << (F64_PREC as usize - self.man.bits())) & ((1 << (F64_PREC - 1)) - 1);
+      ((e - 1 + F64_BIAS) as u64) << (F64_PREC - 1) | frac
+    } else {
+      // ```f*2^-1074```
+      man << (self.exp - F64_EMIN + F64_PREC) as u64
+    };
+    f64::from_bits(bits)
+  }
+
+  fn with_sgn(self, sgn: Sign) -> Float {
+    Float::from_parts(sgn, self.man, self.exp, self.prec, self.rnd)
+  }
+
+  fn round(self, prec: usize, rnd: Round) -> Float {
+    Float::from_parts(self.sgn, self.man, self.exp, prec, rnd)
+  }
+
+  const fn round_up(sgn: Sign, rnd: Round, odd: bool, half: bool, sticky: bool) -> bool {
+    match rnd {
+      Round::Nearest => half && (sticky || odd),
+      Round::Zero => false,
+      Round::Floor => matches!(sgn, Negative) && (half || sticky),
+      Round::Ceil => matches!(sgn, Positive) && (half || sticky),
+    }
+  }
+
+  /// Round `(-1)^sgn*man*2^exp` to `prec` bits and normalize the mantissa.
+  pub(crate) fn from_parts(sgn: Sign, man: Natural, mut exp: i64, prec: usize, rnd: Round) -> Float {
+    assert!(prec > 0, "precision must be positive");
+    if man == Natural::ZERO {
+      return Float {
+        sgn: Positive,
+        man,
+        exp: 0,
+        prec,
+        rnd,
+      };
+    }
+
+    let bits = man.bits();
+    let mut man = if bits > prec {
+      let shift = bits - prec;
+      let half = man.bit(shift - 1);
+      let sticky = man.trailing_zeros() < shift - 1;
+      let kept = man >> shift;
+      exp += shift as i64;
+
+      if Float::round_up(sgn, rnd, kept.bit(0), half, sticky) {
+        kept + Natural::ONE
+      } else {
+        kept
+      }
+    } else {
+      man
+    };
+
+    let tz = man.trailing_zeros();
+    man >>= tz;
+    Float {
+      sgn,
+      man,
+      exp: exp + tz as i64,
+      prec,
+      rnd,
+    }
+  }
+
+  fn add_round(self, o: Float, prec: usize, rnd: Round) -> Float {
+    if o.is_zero() {
+      return self.round(prec, rnd);
+    }
+    if self.is_zero() {
+      return o.round(prec, rnd);
+    }
+
+    let (a, mut b) = if self.top() >= o.top() { (self, o) } else { (o, self) };
+
+    // Replace a negligible operand by a sticky bit below the rounding position.
+    let low = a.exp.min(a.top() - prec as i64 - 3);
+    if b.top() < low {
+      b = Float { man: Natural::ONE, exp: low - 2, ..b };
+    }
+
+    let exp = a.exp.min(b.exp);
+    let lhs = a.man << (a.exp - exp) as usize;
+    let rhs = b.man << (b.exp - exp) as usize;
+
+    let (sgn, man) = if a.sgn == b.sgn {
+      (a.sgn, lhs + rhs)
+    } else {
+      match lhs.cmp(&rhs) {
+        Ordering::Greater => (a.sgn, lhs - rhs),
+        Ordering::Less => (b.sgn, rhs - lhs),
+        Ordering::Equal => (Positive, Natural::ZERO),
+      }
+    };
+
+    Float::from_parts(sgn, man, exp, prec, rnd)
+  }
+
+  fn mul_round(self, o: Float, prec: usize, rnd: Round) -> Float {
+    Float::from_parts(self.sgn * o.sgn, self.man * o.man, self.exp + o.exp, prec, rnd)
+  }
+
+  fn div_round(self, o: Float, prec: usize, rnd: Round) -> SymbolicResult<Float> {
+    if o.is_zero() {
+      return Err(
+        Form::default(), // ```x/0```
+      );
+    }
+
+    // at least `prec + 2` bits of quotient and a sticky remainder
+    let shift = (prec + 2 + o.man.bits()).saturating_sub(self.man.bits()) + 1;
+    let (q, r) = (self.man << shift).div_rem(o.man);
+    let exp = self.exp - shift as i64 - o.exp;
+    let (q, exp) = if r != Natural::ZERO { ((q << 1) | Natural::ONE, exp - 1) } else { (q, exp) };
+
+    Ok(Float::from_parts(self.sgn * o.sgn, q, exp, prec, rnd))
+  }
+
+  fn sqrt_round(self, prec: usize, rnd: Round) -> SymbolicResult<Float> {
+    if self.is_zero() {
+      return Ok(self.round(prec, rnd));
+    }
+    if self.is_negative() {
+      return Err(
+        Form::default(), // ```sqrt(x), x < 0```
+      );
+    }
+
+    // at least `prec + 2` bits of root and an even exponent
+    let mut shift = (2 * (prec + 2)).saturating_sub(self.man.bits()) + 2;
+    if (self.exp - shift as i64) % 2 != 0 {
+      shift += 1;
+    }
+
+    let m = self.man << shift;
+    let s = m.clone().sqrt();
+    let exact = s.clone() * s.clone() == m;
+    let exp = (self.exp - shift as i64) / 2;
+    let (s, exp) = if exact { (s, exp) } else { ((s << 1) | Natural::ONE, exp - 1) };
+
+    Ok(Float::from_parts(Positive, s, exp, prec, rnd))
+  }
+
+  // Elementary functions at working precision
+
+  fn sqrt_work(self) -> Float {
+    let prec = self.prec;
+    self.sqrt_round(prec, Round::Nearest).expect("sqrt of a nonnegative value")
+  }
+
+  fn ln2_work(w: usize) -> Float {
+    // ```log(2) = 2*artanh(1/3) = 2*∑{k=0->∞} 1/((2k + 1)*3^(2k + 1))```
+    let w = w + 8;
+    let nine = Float::from_integer(Integer::from(9), w, Round::Nearest);
+    let mut term = Float::one(w, Round::Nearest) / Float::from_integer(Integer::from(3), w, Round::Nearest);
+    let mut sum = Float::zero(w, Round::Nearest);
+    let mut k = 0u64;
+
+    while !term.is_zero() && term.top() > -(w as i64) - 2 {
+      sum = sum + term.clone() / Float::from_integer(Integer::from(2 * k + 1), w, Round::Nearest);
+      term = term / nine.clone();
+      k += 1;
+    }
+    sum.ldexp(1)
+  }
+
+  fn atan_inv_work(n: u64, w: usize) -> Float {
+    // ```arctan(1/n) = ∑{k=0->∞} (-1)^k/((2k + 1)*n^(2k + 1))```
+    let n2 = Float::from_integer(Integer::from(n * n), w, Round::Nearest);
+    let mut term = Float::one(w, Round::Nearest) / Float::from_integer(Integer::from(n), w, Round::Nearest);
+    let mut sum = Float::zero(w, Round::Nearest);
+    let mut k = 0u64;
+
+    while !term.is_zero() && term.top() > -(w as i64) - 2 {
+      let t = term.clone() / Float::from_integer(Integer::from(2 * k + 1), w, Round::Nearest);
+      sum = if k.is_multiple_of(2) { sum + t } else { sum - t };
+      term = term / n2.clone();
+      k += 1;
+    }
+    sum
+  }
+
+  fn pi_work(w: usize) -> Float {
+    // ```π = 16*arctan(1/5) - 4*arctan(1/239)``` (Machin)
+    let w = w + 8;
+    Float::atan_inv_work(5, w).ldexp(4) - Float::atan_inv_work(239, w).ldexp(2)
+  }
+
+  fn exp_work(x: Float, w: usize) -> SymbolicResult<Float> {
+    if x.is_zero() {
+      return Ok(Float::one(w, Round::Nearest));
+    }
+
+    let top = x.top().max(0) as usize;
+    if top >= 62 {
+      return Err(
+        Form::default(), // ```exp(x)``` out of the exponent range
+      );
+    }
+
+    // ```exp(x) = 2^n*exp(r), r = x - n*log(2), |r| <= log(2)/2```
+    let wx = w + top + 8;
+    let ln2 = Float::ln2_work(wx);
+    let x = x.round(wx, Round::Nearest);
+    let n = (x.clone() / ln2.clone()).to_integer(Round::Nearest);
+    let r = x - ln2 * Float::from_integer(n.clone(), wx, Round::Nearest);
+
+    // ```exp(r) = exp(r/2^s)^(2^s)```
+    let s = Float::isqrt(w) / 2;
+    let ws = w + s + 8;
+    let r = r.ldexp(-(s as i64)).round(ws, Round::Nearest);
+
+    let mut sum = Float::one(ws, Round::Nearest);
+    let mut term = Float::one(ws, Round::Nearest);
+    let mut k = 1u64;
+    loop {
+      term = term * r.clone() / Float::from_integer(Integer::from(k), ws, Round::Nearest);
+      if term.is_zero() || term.top() < -(ws as i64) - 2 {
+        break;
+      }
+      sum = sum + term.clone();
+      k += 1;
+    }
+
+    for _ in 0..s {
+      sum = sum.clone() * sum;
+    }
+
+    // ```|n| < 2^62/log(2) < 2^63```
+    let neg = n.is_negative();
+    let n = u64::try_from(n.abs()).ok().and_then(|n| i64::try_from(n).ok()).ok_or_else(Form::default)?;
+    Ok(sum.ldexp(if neg { -n } else { n }).round(w, Round::Nearest))
+  }
+
+  fn log_work(x: Float, w: usize) -> Float {
+    let one = Float::one(w, Round::Nearest);
+    if x == one {
+      return Float::zero(w, Round::Nearest);
+    }
+
+    // ```log(x) = e*log(2) + log(m), 3/4 <= m < 3/2```
+    let mut e = x.top() - 1;
+    let mut m = x.ldexp(-e);
+    if m >= Float::from_parts(Positive, Natural::from(3u64), -1, 2, Round::Nearest) {
+      m = m.ldexp(-1);
+      e += 1;
+    }
+
+    // ```log(m) = 2^r*log(m^(1/2^r))```
+    let wr = w + 8 + if e != 0 { (64 - e.unsigned_abs().leading_zeros()) as usize } else { 0 };
+    let mut m = m.round(wr + Float::isqrt(wr), Round::Nearest);
+    let Some(near) = (m.clone() - Float::one(m.prec, Round::Nearest)).magnitude() else {
+      // ```log(2^e) = e*log(2)```
+      return (Float::ln2_work(wr) * Float::from_integer(Integer::from(e), wr, Round::Nearest)).round(w, Round::Nearest);
+    };
+    let r = if near > -(Float::isqrt(wr) as i64) { Float::isqrt(wr) / 2 } else { 0 };
+    let wr = wr + r + near.min(0).unsigned_abs() as usize;
+    m = m.round(wr, Round::Nearest);
+    for _ in 0..r {
+      m = m.sqrt_work();
+    }
+
+    // ```log(m) = 2*artanh(z) = 2*∑{k=0->∞} z^(2k + 1)/(2k + 1), z = (m - 1)/(m + 1)```
+    let one = Float::one(wr, Round::Nearest);
+    let z = (m.clone() - one.clone()) / (m + one);
+    let z2 = z.clone() * z.clone();
+    let stop = z.magnitude().unwrap_or(0) - wr as i64 - 2;
+    let mut term = z;
+    let mut sum = Float::zero(wr, Round::Nearest);
+    let mut k = 0u64;
+    while !term.is_zero() && term.top() > stop {
+      sum = sum + term.clone() / Float::from_integer(Integer::from(2 * k + 1), wr, Round::Nearest);
+      term = term * z2.clone();
+      k += 1;
+    }
+    let log = sum.ldexp(1 + r as i64);
+
+    if e != 0 {
+      (log + Float::ln2_work(wr) * Float::from_integer(Integer::from(e), wr, Round::Nearest)).round(w, Round::Nearest)
+    } else {
+      log.round(w, Round::Nearest)
+    }
+  }
+
+  fn sin_cos_work(x: Float, w: usize) -> (Float, Float) {
+    if x.is_zero() {
+      return (Float::zero(w, Round::Nearest), Float::one(w, Round::Nearest));
+    }
+
+    // ```x = k*π/2 + r, |r| <= π/4```
+    let mut extra = x.top().max(0) as usize + 8;
+    let (k, r) = loop {
+      let wx = w + extra;
+      let half_pi = Float::pi_work(wx).ldexp(-1);
+      let xw = x.clone().round(wx, Round::Nearest);
+      let k = (xw.clone() / half_pi.clone()).to_integer(Round::Nearest);
+      let r = xw - half_pi * Float::from_integer(k.clone(), wx, Round::Nearest);
+
+      // cancellation when x is close to a multiple of π/2
+      let loss = r.magnitude().map_or(w, |m| m.min(0).unsigned_abs() as usize);
+      if loss + 8 <= extra.saturating_sub(x.top().max(0) as usize) {
+        break (k, r);
+      }
+      extra += loss + 8;
+    };
+
+    let ws = w + 8;
+    let r = r.round(ws, Round::Nearest);
+    let r2 = r.clone() * r.clone();
+    let stop = -(ws as i64) - 2;
+
+    // ```sin(r) = ∑{k=0->∞} (-1)^k*r^(2k + 1)/(2k + 1)!```
+    // ```cos(r) = ∑{k=0->∞} (-1)^k*r^(2k)/(2k)!```
+    let series = |mut term: Float, mut n: u64, scale: i64| {
+      let mut sum = Float::zero(ws, Round::Nearest);
+      let mut k = 0u64;
+      while !term.is_zero() && term.top() > stop + scale {
+        sum = if k.is_multiple_of(2) { sum + term.clone() } else { sum - term.clone() };
+        term = term * r2.clone() / Float::from_integer(Integer::from((n + 1) * (n + 2)), ws, Round::Nearest);
+        n += 2;
+        k += 1;
+      }
+      sum
+    };
+
+    let sin = series(r.clone(), 1, r.top().min(0));
+    let cos = series(Float::one(ws, Round::Nearest), 0, 0);
+
+    match k.rem_euclid(Integer::from(4)) {
+      Integer::ZERO => (sin, cos),
+      Integer::ONE => (cos, -sin),
+      Integer::TWO => (-sin, -cos),
+      _ => (-cos, sin),
+    }
+  }
+
+  fn atan_work(x: Float, w: usize) -> Float {
+    if x.is_zero() {
+      return x.round(w, Round::Nearest);
+    }
+
+    let sgn = x.sgn;
+    let ws = w + Float::isqrt(w) + 8;
+    let one = Float::one(ws, Round::Nearest);
+    let mut a = x.abs().round(ws, Round::Nearest);
+
+    // ```arctan(x) = π/2 - arctan(1/x), x > 1```
+    let inv = a > one;
+    if inv {
+      a = one.clone() / a;
+    }
+
+    // ```arctan(x) = 2*arctan(x/(1 + sqrt(1 + x^2)))```
+    let r = Float::isqrt(w) / 2;
+    let r = if a.top() > -(r as i64) { r } else { 0 };
+    for _ in 0..r {
+      a = a.clone() / (one.clone() + (one.clone() + a.clone() * a).sqrt_work());
+    }
+
+    // ```arctan(a) = ∑{k=0->∞} (-1)^k*a^(2k + 1)/(2k + 1)```
+    let a2 = a.clone() * a.clone();
+    let stop = a.top() - ws as i64 - 2;
+    let mut term = a;
+    let mut sum = Float::zero(ws, Round::Nearest);
+    let mut k = 0u64;
+    while !term.is_zero() && term.top() > stop {
+      let t = term.clone() / Float::from_integer(Integer::from(2 * k + 1), ws, Round::Nearest);
+      sum = if k.is_multiple_of(2) { sum + t } else { sum - t };
+      term = term * a2.clone();
+      k += 1;
+    }
+    let mut atan = sum.ldexp(r as i64);
+
+    if inv {
+      atan = Float::pi_work(ws).ldexp(-1) - atan;
+    }
+    atan.with_sgn(sgn).round(w, Round::Nearest)
+  }
+
+  fn sinh_work(x: Float, w: usize) -> SymbolicResult<Float> {
+    if x.top() <= 0 {
+      // ```sinh(x) = ∑{k=0->∞} x^(2k + 1)/(2k + 1)!```
+      let ws = w + 8;
+      let x = x.round(ws, Round::Nearest);
+      let x2 = x.clone() * x.clone();
+      let stop = x.magnitude().unwrap_or(0) - ws as i64 - 2;
+      let mut term = x;
+      let mut sum = Float::zero(ws, Round::Nearest);
+      let mut n = 1u64;
+      while !term.is_zero() && term.top() > stop {
+        sum = sum + term.clone();
+        term = term * x2.clone() / Float::from_integer(Integer::from((n + 1) * (n + 2)), ws, Round::Nearest);
+        n += 2;
+      }
+      Ok(sum)
+    } else {
+      // ```sinh(x) = (exp(x) - exp(-x))/2```
+      let exp = Float::exp_work(x, w)?;
+      let inv = Float::one(w, Round::Nearest) / exp.clone();
+      Ok((exp - inv).ldexp(-1))
+    }
+  }
+
+  fn isqrt(n: usize) -> usize {
+    u64::try_from(Natural::from(n as u64).sqrt()).unwrap_or(0) as usize
+  }
+}
+
+impl TryFrom<f64> for Float {
+  type Error = ();
+
+  /// Convert a finite [`f64`] exactly, with the precision of a double.
+  fn try_from(x: f64) -> Result<Self, Self::Error> {
+    if !x.is_finite() {
+      return Err(
+        (), //.
+      );
+    }
+
+    let bits = x.to_bits();
+    let sgn = if x.is_sign_negative() { Negative } else { Positive };
+    let exp = ((bits >> (F64_PREC - 1)) & 0x7ff) as i64;
+    let frac = bits & ((1 << (F64_PREC - 1)) - 1);
+    let (man, exp) = match exp {
+      0 => (frac, F64_EMIN - F64_PREC),
+      _ => (frac | 1 << (F64_PREC - 1), exp - F64_BIAS - (F64_PREC - 1)),
+    };
+    Ok(Float::from_parts(sgn, Natural::from(man), exp, Float::DEFAULT_PREC, Round::Nearest))
+  }
+}
+
+impl Neg for Float {
+  type Output = Float;
+
+  fn neg(self) -> Self::Output {
+    let sgn = if self.is_zero() { Positive } else { -self.sgn };
+    Float { sgn, ..self }
+  }
+}
+
+impl Add for Float {
+  type Output = Float;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    let (prec, rnd) = (self.prec.max(rhs.prec), self.rnd);
+    self.add_round(rhs, prec, rnd)
+  }
+}
+
+impl Sub for Float {
+  type Output = Float;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    let (prec, rnd) = (self.prec.max(rhs.prec), self.rnd);
+    self.add_round(-rhs, prec, rnd)
+  }
+}
+
+impl Mul for Float {
+  type Output = Float;
+
+  fn mul(self, rhs: Self) -> Self::Output {
+    let (prec, rnd) = (self.prec.max(rhs.prec), self.rnd);
+    self.mul_round(rhs, prec, rnd)
+  }
+}
+
+impl Div for Float {
+  type Output = Float;
+
+  fn div(self, rhs: Self) -> Self::Output {
+    let (prec, rnd) = (self.prec.max(rhs.prec), self.rnd);
+    self.div_round(rhs, prec, rnd).expect("attempt to divide by zero")
+  }
+}
+
+impl Eq for Float {}
+impl PartialEq for Float {
+  fn eq(&self, o: &Float) -> bool {
+    // normalized mantissas
+    self.sgn == o.sgn && self.man == o.man && self.exp == o.exp
+  }
+}
+
+impl PartialOrd for Float {
+  fn partial_cmp(&self, o: &Float) -> Option<Ordering> {
+    Some(self.cmp(o))
+  }
+}
+
+impl Ord for Float {
+  fn cmp(&self, o: &Float) -> Ordering {
+    match (self.is_zero(), o.is_zero()) {
+      (true, true) => return Ordering::Equal,
+      (true, false) => return Positive.cmp(&o.sgn).then(Ordering::Less),
+      (false, true) => return self.sgn.cmp(&Positive).then(Ordering::Greater),
+      (false, false) => {}
+    }
+
+    if self.sgn != o.sgn {
+      return self.sgn.cmp(&o.sgn);
+    }
+
+    let mag = self.top().cmp(&o.top()).then_with(|| {
+      let exp = self.exp.min(o.exp);
+      let lhs = self.man.clone() << (self.exp - exp) as usize;
+      let rhs = o.man.clone() << (o.exp - exp) as usize;
+      lhs.cmp(&rhs)
+    });
+
+    match self.sgn {
+      Positive => mag,
+      Negative => mag.reverse(),
+    }
+  }
+}
+
+impl Hash for Float {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.sgn.hash(state);
+    self.man.hash(state);
+    self.exp.hash(state);
+  }
+}
+
+impl fmt::Display for Float {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    // ```digits = ⌊prec*log10(2)⌋```
+    let digits = (self.prec * 30103 / 100000).max(1);
+    write!(f, "{}", self.to_decimal(digits))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PI: &str = "3.14159265358979323846264338327950288419716939937510582097494459";
+  const E: &str = "2.71828182845904523536028747135266249775724709369995957496696763";
+
+  fn float(n: i64, prec: usize) -> Float {
+    Float::from_integer(Integer::from(n), prec, Round::Nearest)
+  }
+
+  #[test]
+  fn rounding() {
+    // ```1/3 = 0.0101...```
+    let third = |rnd| Float::from_rational(Rational::new(Integer::from(1), Integer::from(3)), 4, rnd);
+    assert_eq!(third(Round::Nearest).to_rational(), Rational::new(Integer::from(11), Integer::from(32)));
+    assert_eq!(third(Round::Zero).to_rational(), Rational::new(Integer::from(5), Integer::from(16)));
+    assert_eq!(third(Round::Floor).to_rational(), Rational::new(Integer::from(5), Integer::from(16)));
+    assert_eq!(third(Round::Ceil).to_rational(), Rational::new(Integer::from(11), Integer::from(32)));
+    assert_eq!((-third(Round::Floor)).to_rational(), Rational::new(Integer::from(-5), Integer::from(16)));
+
+    // ties to even
+    assert_eq!(float(9, 3), float(8, 3));
+    assert_eq!(float(11, 3), float(12, 3));
+
+    // ```1 + 2^-100 = 1``` with 53 bits
+    assert_eq!(float(1, 53) + float(1, 53).ldexp(-100), float(1, 53));
+    assert_eq!(
+      (float(1, 53).with_rnd(Round::Ceil) + float(1, 53).ldexp(-100)).to_rational(),
+      Rational::new(Integer::from((1i64 << 52) + 1), Integer::from(1i64 << 52))
+    );
+    assert_eq!((float(1, 53) - float(1, 53).ldexp(-100)).with_rnd(Round::Floor), float(1, 53));
+
+    // ```sqrt(2)^2 != 2``` but ```sqrt(4) = 2```
+    assert_eq!(float(4, 10).sqrt(), Ok(float(2, 10)));
+    assert!(float(-4, 10).sqrt().is_err());
+    assert_eq!(float(2, 200).sqrt().map(|s| s.to_decimal(50)), Ok("1.4142135623730950488016887242096980785696718753769".to_string()));
+  }
+
+  #[test]
+  fn elementary() {
+    let prec = 200;
+    let digits = 60;
+    assert_eq!(Float::pi(prec, Round::Nearest).to_decimal(digits), PI[..digits + 1]);
+    assert_eq!(Float::e(prec, Round::Nearest).to_decimal(40), E[..41]);
+    assert_eq!(Float::ln2(prec, Round::Nearest).to_decimal(30), "0.693147180559945309417232121458");
+
+    // ```4*arctan(1) = π```
+    assert_eq!(float(1, prec).arctan().ldexp(2).to_decimal(digits), PI[..digits + 1]);
+    // ```sin(1)```
+    assert_eq!(float(1, prec).sin().to_decimal(30), "0.84147098480789650665250232163");
+    // ```log(10)```
+    assert_eq!(float(10, prec).log().map(|l| l.to_decimal(30)), Ok("2.30258509299404568401799145468".to_string()));
+
+    let x = Float::from_rational(Rational::new(Integer::from(-7), Integer::from(3)), prec, Round::Nearest);
+    let eps = |y: Float| y.abs() < float(1, prec).ldexp(-(prec as i64) + 8);
+
+    // ```log(exp(x)) = x```
+    assert!(eps(x.clone().exp().and_then(Float::log).unwrap() - x.clone()));
+    // ```sin(x)^2 + cos(x)^2 = 1```
+    assert!(eps(x.clone().sin() * x.clone().sin() + x.clone().cos() * x.clone().cos() - float(1, prec)));
+    // ```cosh(x)^2 - sinh(x)^2 = 1```
+    let (cosh, sinh) = (x.clone().cosh().unwrap(), x.clone().sinh().unwrap());
+    assert!(eps(cosh.clone() * cosh - sinh.clone() * sinh.clone() - float(1, prec)));
+    // ```arsinh(sinh(x)) = x```
+    assert!(eps(sinh.arsinh() - x.clone()));
+    // ```tan(arctan(x)) = x```
+    assert!(eps(x.clone().arctan().tan() - x.clone()));
+    // ```2^10 = 1024```
+    assert_eq!(float(2, prec).pow(float(10, prec)), Ok(float(1024, prec)));
+
+    // results out of the exponent range fail, ```tanh(x)``` does not overflow
+    let huge = float(1, prec).ldexp(62);
+    assert!(huge.clone().exp().is_err() && huge.clone().neg().exp().is_err());
+    assert!(huge.clone().sinh().is_err() && huge.clone().cosh().is_err());
+    assert!(float(2, prec).pow(huge.clone()).is_err());
+    assert_eq!(huge.tanh(), float(1, prec));
+  }
+
+  #[test]
+  fn decimal() {
+    assert_eq!(float(0, 53).to_decimal(10), "0.0");
+    assert_eq!(float(-12, 53).to_decimal(10), "-12.0");
+    assert_eq!(float(1, 53).ldexp(-2).to_decimal(10), "0.25");
+    assert_eq!(float(1, 53).ldexp(100).to_decimal(10), "1.2676506e30");
+    assert_eq!(float(1, 53).ldexp(-30).to_decimal(5), "9.3132e-10");
+    assert_eq!(float(999, 53).to_decimal(2), "1000.0");
+
+    let pi = Float::pi(53, Round::Nearest);
+    assert_eq!(pi.limit_denominator(Natural::from(1000u64)), Rational::new(Integer::from(355), Integer::from(113)));
+    assert_eq!(pi.limit_denominator(Natural::from(1000000u64)), Rational::new(Integer::from(3126535), Integer::from(995207)));
+  }
+}
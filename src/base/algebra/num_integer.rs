@@ -1,15 +1,21 @@
 use crate::base::algebra::num_natural::*;
+use crate::base::algebra::num_rational::Rational;
 use crate::base::algebra::repr::*;
 use Sign::*;
 
 use std::cmp::Ordering;
 use std::fmt;
 use std::mem;
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+use std::ops::{
+  Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign, Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
 use std::str::FromStr;
 
 /// An integer ℤ.
-#[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord)]
+///
+/// Bitwise operations follow the two's complement semantics of primitive integers, where a negative
+/// integer behaves as if it had an infinite number of leading ones.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Integer {
   pub(crate) sgn: Sign,
   pub(crate) mag: Natural,
@@ -29,12 +35,43 @@ impl Integer {
     sgn: Sign, //.
     mag: Natural,
   ) -> Self {
+    // ```-0 = 0```
+    let sgn = if matches!(mag, Natural::ZERO) { Positive } else { sgn };
     Integer {
       sgn, //.
       mag,
     }
   }
 
+  /// Parse a signed integer written in base `radix`, with single underscores allowed between digits.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `radix` is not in `[2, 36]`.
+  pub fn from_str_radix(src: &str, radix: u32) -> Result<Integer, ParseError> {
+    let (sgn, num, pos) = Integer::split_sign(src);
+    Ok(Integer::from_sgn(sgn, Natural::parse(num, radix, pos)?))
+  }
+
+  // Error positions are offset by `pos`, the position of `src` in the whole input.
+  pub(crate) fn parse(src: &str, pos: usize) -> Result<Integer, ParseError> {
+    let (sgn, num, skip) = Integer::split_sign(src);
+    Ok(Integer::from_sgn(sgn, Natural::parse_prefixed(num, pos + skip)?))
+  }
+
+  pub(crate) fn split_sign(src: &str) -> (Sign, &str, usize) {
+    match src.as_bytes().first() {
+      Some(b'-') => (Negative, &src[1..], 1),
+      Some(b'+') => (Positive, &src[1..], 1),
+      _ => (Positive, src, 0),
+    }
+  }
+
+  /// Convert to the nearest [`f64`] (ties to even), overflowing to infinity.
+  pub fn to_f64(&self) -> f64 {
+    Rational::from(self.clone()).to_f64()
+  }
+
   pub(crate) fn incr(&mut self) {
     *self = mem::take(self) + Self::ONE;
   }
@@ -73,6 +110,127 @@ impl Integer {
   pub fn lcm(u: Self, v: Self) -> Self {
     Integer::from(Natural::lcm(u.abs(), v.abs()))
   }
+
+  /// Compute the p-adic valuation `ν_p(self)`, the exponent of `p` in `self`, or `None` if `self = 0` or `p < 2`.
+  pub fn valuation(&self, p: &Natural) -> Option<u64> {
+    if self.mag == Natural::ZERO || p < &Natural::TWO {
+      return None;
+    }
+
+    let (mut n, mut k) = (self.mag.clone(), 0);
+    loop {
+      let (q, r) = n.div_rem(p.clone());
+      if r != Natural::ZERO {
+        return Some(k);
+      }
+      (n, k) = (q, k + 1);
+    }
+  }
+
+  /// Compute the (signed) Stirling number of the first kind `s(n, k)`.
+  pub fn stirling1(n: u64, k: u64) -> Self {
+    if k > n {
+      return Integer::ZERO;
+    }
+
+    // ```s(m + 1, j) = s(m, j - 1) - m*s(m, j)```
+    let mut row = vec![Integer::ZERO; k as usize + 1];
+    row[0] = Integer::ONE;
+    for m in 0..n {
+      for j in (1..=k as usize).rev() {
+        row[j] = row[j - 1].clone() - Integer::from(m) * row[j].clone();
+      }
+      row[0] = Integer::ZERO;
+    }
+    row.swap_remove(k as usize)
+  }
+
+  /// Compute the Euler number `E_n` (`E_0 = 1`, `E_2 = -1`, `E_4 = 5`, ...).
+  pub fn euler(n: u64) -> Self {
+    if n % 2 == 1 {
+      return Integer::ZERO;
+    }
+
+    // ```E_2m = -∑{j=0->m-1} (2m 2j)*E_2j```
+    let mut e = vec![Integer::ONE];
+    for m in 1..=n / 2 {
+      let mut sum = Integer::ZERO;
+      let mut binom = Integer::ONE;
+      for (j, ej) in e.iter().enumerate() {
+        sum += binom.clone() * ej.clone();
+        let j = j as u64;
+        binom = binom * Integer::from((2 * m - 2 * j) * (2 * m - 2 * j - 1)) / Integer::from((2 * j + 1) * (2 * j + 2));
+      }
+      e.push(-sum);
+    }
+    e.swap_remove((n / 2) as usize)
+  }
+
+  /// Compute the Möbius function `μ(n)`: `0` if `n` has a square factor, `(-1)^k` for `k` distinct prime factors.
+  pub fn mobius(n: Natural) -> Self {
+    let factors = n.factor();
+    if factors.iter().any(|(_, e)| *e > 1) {
+      Integer::ZERO
+    } else if factors.len() % 2 == 1 {
+      Integer::NEG_ONE
+    } else {
+      Integer::ONE
+    }
+  }
+
+  /// Return the bit at position `idx` in the two's complement representation of `self`.
+  pub fn bit(&self, idx: usize) -> bool {
+    match self.sgn {
+      Positive => self.mag.bit(idx),
+      // ```-x = !(x - 1)```
+      Negative => !(self.mag.clone() - Natural::ONE).bit(idx),
+    }
+  }
+
+  /// Set the bit at position `idx` in the two's complement representation of `self`.
+  pub fn set_bit(&mut self, idx: usize, bit: bool) {
+    let (mut mag, neg) = mem::take(self).complement();
+    mag.set_bit(idx, bit ^ neg);
+    *self = Integer::from_complement(mag, neg);
+  }
+
+  /// Return the number of ones in the binary representation of `self`.
+  ///
+  /// A negative integer has infinitely many ones in two's complement, in which case `None` is returned.
+  pub fn count_ones(&self) -> Option<usize> {
+    match self.sgn {
+      Positive => Some(self.mag.count_ones()),
+      Negative => None,
+    }
+  }
+
+  /// Return the number of significant bits of `self`, excluding the sign bit.
+  ///
+  /// For a negative integer, this is the minimal two's complement width without its sign bit, e.g. `bits(-1) = 0`.
+  pub fn bits(&self) -> usize {
+    match self.sgn {
+      Positive => self.mag.bits(),
+      Negative => (self.mag.clone() - Natural::ONE).bits(),
+    }
+  }
+
+  /// Split into `(l, neg)` such that `self = l` or `self = !l` (two's complement) if `neg`.
+  fn complement(self) -> (Natural, bool) {
+    match self.sgn {
+      Positive => (self.mag, false),
+      // ```-x = !(x - 1)```
+      Negative => (self.mag - Natural::ONE, true),
+    }
+  }
+
+  fn from_complement(l: Natural, neg: bool) -> Integer {
+    if neg {
+      // ```!l = -(l + 1)```
+      Integer::from_sgn(Negative, l + Natural::ONE)
+    } else {
+      Integer::from(l)
+    }
+  }
 }
 
 impl Neg for Integer {
@@ -80,7 +238,7 @@ impl Neg for Integer {
 
   #[inline]
   fn neg(self) -> Self::Output {
-    Integer { sgn: self.sgn.neg(), mag: self.mag }
+    Integer::from_sgn(self.sgn.neg(), self.mag)
   }
 }
 
@@ -147,10 +305,10 @@ impl Mul for Integer {
     self, //.
     rhs: Self,
   ) -> Self::Output {
-    Integer {
-      sgn: self.sgn * rhs.sgn,
-      mag: self.mag * rhs.mag,
-    }
+    Integer::from_sgn(
+      self.sgn * rhs.sgn, //.
+      self.mag * rhs.mag,
+    )
   }
 }
 
@@ -172,10 +330,10 @@ impl Div for Integer {
     self, //.
     rhs: Self,
   ) -> Self::Output {
-    Integer {
-      sgn: self.sgn * rhs.sgn,
-      mag: self.mag / rhs.mag,
-    }
+    Integer::from_sgn(
+      self.sgn * rhs.sgn, //.
+      self.mag / rhs.mag,
+    )
   }
 }
 
@@ -197,10 +355,10 @@ impl Rem for Integer {
     self, //.
     rhs: Self,
   ) -> Self::Output {
-    Integer {
-      sgn: self.sgn,
-      mag: self.mag % rhs.mag,
-    }
+    Integer::from_sgn(
+      self.sgn, //.
+      self.mag % rhs.mag,
+    )
   }
 }
 
@@ -242,16 +400,170 @@ impl Integer {
   }
 }
 
+impl PartialOrd for Integer {
+  #[inline]
+  fn partial_cmp(&self, o: &Integer) -> Option<Ordering> {
+    Some(self.cmp(o))
+  }
+}
+
+impl Ord for Integer {
+  fn cmp(&self, o: &Integer) -> Ordering {
+    match (self.sgn, o.sgn) {
+      (Positive, Positive) => self.mag.cmp(&o.mag),
+      // ```-a < -b <=> a > b```
+      (Negative, Negative) => o.mag.cmp(&self.mag),
+      (lhs, rhs) => lhs.cmp(&rhs),
+    }
+  }
+}
+
+impl Not for Integer {
+  type Output = Integer;
+
+  #[inline]
+  fn not(self) -> Self::Output {
+    // ```!x = -x - 1```
+    let (l, neg) = self.complement();
+    Integer::from_complement(l, !neg)
+  }
+}
+
+impl BitAnd for Integer {
+  type Output = Integer;
+
+  #[inline]
+  fn bitand(
+    self, //.
+    rhs: Self,
+  ) -> Self::Output {
+    match (self.complement(), rhs.complement()) {
+      ((lhs, false), (rhs, false)) => Integer::from(lhs & rhs),
+      // ```!a & b = b ^ (a & b)```
+      ((a, true), (b, false)) | ((b, false), (a, true)) => Integer::from(b.clone() ^ (a & b)),
+      // ```!a & !b = !(a | b)```
+      ((lhs, true), (rhs, true)) => Integer::from_complement(lhs | rhs, true),
+    }
+  }
+}
+
+impl BitAndAssign for Integer {
+  #[inline]
+  fn bitand_assign(
+    &mut self,
+    rhs: Self, //.
+  ) {
+    *self = mem::take(self) & rhs;
+  }
+}
+
+impl BitOr for Integer {
+  type Output = Integer;
+
+  #[inline]
+  fn bitor(
+    self, //.
+    rhs: Self,
+  ) -> Self::Output {
+    match (self.complement(), rhs.complement()) {
+      ((lhs, false), (rhs, false)) => Integer::from(lhs | rhs),
+      // ```!a | b = !(a ^ (a & b))```
+      ((a, true), (b, false)) | ((b, false), (a, true)) => Integer::from_complement(a.clone() ^ (a & b), true),
+      // ```!a | !b = !(a & b)```
+      ((lhs, true), (rhs, true)) => Integer::from_complement(lhs & rhs, true),
+    }
+  }
+}
+
+impl BitOrAssign for Integer {
+  #[inline]
+  fn bitor_assign(
+    &mut self,
+    rhs: Self, //.
+  ) {
+    *self = mem::take(self) | rhs;
+  }
+}
+
+impl BitXor for Integer {
+  type Output = Integer;
+
+  #[inline]
+  fn bitxor(
+    self, //.
+    rhs: Self,
+  ) -> Self::Output {
+    let (lhs, lhs_neg) = self.complement();
+    let (rhs, rhs_neg) = rhs.complement();
+    // ```!a ^ b = !(a ^ b)```
+    // ```!a ^ !b = a ^ b```
+    Integer::from_complement(lhs ^ rhs, lhs_neg ^ rhs_neg)
+  }
+}
+
+impl BitXorAssign for Integer {
+  #[inline]
+  fn bitxor_assign(
+    &mut self,
+    rhs: Self, //.
+  ) {
+    *self = mem::take(self) ^ rhs;
+  }
+}
+
+impl Shl<usize> for Integer {
+  type Output = Integer;
+
+  #[inline]
+  fn shl(
+    self, //.
+    rhs: usize,
+  ) -> Integer {
+    Integer::from_sgn(self.sgn, self.mag << rhs)
+  }
+}
+
+impl ShlAssign<usize> for Integer {
+  #[inline]
+  fn shl_assign(
+    &mut self,
+    rhs: usize, //.
+  ) {
+    *self = mem::take(self) << rhs;
+  }
+}
+
+impl Shr<usize> for Integer {
+  type Output = Integer;
+
+  /// Arithmetic shift, rounding towards negative infinity.
+  #[inline]
+  fn shr(
+    self, //.
+    rhs: usize,
+  ) -> Integer {
+    // ```!l >> n = !(l >> n)```
+    let (l, neg) = self.complement();
+    Integer::from_complement(l >> rhs, neg)
+  }
+}
+
+impl ShrAssign<usize> for Integer {
+  #[inline]
+  fn shr_assign(
+    &mut self,
+    rhs: usize, //.
+  ) {
+    *self = mem::take(self) >> rhs;
+  }
+}
+
 impl FromStr for Integer {
   type Err = ParseError;
 
+  /// Parse a signed decimal integer, or a binary, octal or hexadecimal one with prefix `0b`, `0o` or `0x`.
   fn from_str(s: &str) -> Result<Integer, ParseError> {
-    let (sgn, num) = s.strip_prefix('-').map(|num| (Negative, num)).unwrap_or((Positive, s));
-    let mag = Natural::from_str(num)?;
-    Ok(Integer {
-      sgn, //.
-      mag,
-    })
+    Integer::parse(s, 0)
   }
 }
 
@@ -296,6 +608,79 @@ impl From<Natural> for Integer {
   }
 }
 
+impl From<u128> for Integer {
+  /// Produce a positive integer from a [`u128`].
+  fn from(n: u128) -> Integer {
+    Integer::from(Natural::from(n))
+  }
+}
+
+impl From<i128> for Integer {
+  /// Produce an integer from a [`i128`].
+  fn from(i: i128) -> Integer {
+    Integer::from_sgn(if i < 0 { Negative } else { Positive }, Natural::from(i.unsigned_abs()))
+  }
+}
+
+impl TryFrom<Integer> for u128 {
+  type Error = ();
+
+  fn try_from(z: Integer) -> Result<Self, Self::Error> {
+    Natural::try_from(z).and_then(u128::try_from)
+  }
+}
+
+impl TryFrom<Integer> for i128 {
+  type Error = ();
+
+  fn try_from(z: Integer) -> Result<Self, Self::Error> {
+    let neg = z.is_negative();
+    let mag = u128::try_from(z.mag)?;
+    match (neg, i128::try_from(mag)) {
+      (false, Ok(i)) => Ok(i),
+      (true, Ok(i)) => Ok(-i),
+      // ```-2^127```
+      (true, Err(_)) if mag == 1 << 127 => Ok(i128::MIN),
+      _ => Err(
+        (), //.
+      ),
+    }
+  }
+}
+
+// Conversions from the remaining primitives, through `u128` and `i128`.
+macro_rules! integer_widening {
+  ($($t:ty => $via:ty),*) => {
+    $(
+      impl From<$t> for Integer {
+        #[doc = concat!("Produce an integer from a [`", stringify!($t), "`].")]
+        fn from(n: $t) -> Integer {
+          Integer::from(n as $via)
+        }
+      }
+    )*
+  };
+}
+
+integer_widening!(u8 => u128, u16 => u128, usize => u128, i8 => i128, i16 => i128, isize => i128);
+
+// Checked conversions into the primitives, through `u128` and `i128`.
+macro_rules! integer_narrowing {
+  ($($t:ty => $via:ty),*) => {
+    $(
+      impl TryFrom<Integer> for $t {
+        type Error = ();
+
+        fn try_from(z: Integer) -> Result<Self, Self::Error> {
+          <$via>::try_from(z).and_then(|n| <$t>::try_from(n).map_err(|_| ()))
+        }
+      }
+    )*
+  };
+}
+
+integer_narrowing!(u8 => u128, u16 => u128, u32 => u128, u64 => u128, usize => u128, i8 => i128, i16 => i128, i32 => i128, i64 => i128, isize => i128);
+
 impl TryFrom<Integer> for Natural {
   type Error = ();
 
@@ -322,3 +707,110 @@ impl fmt::Display for Integer {
     f.pad_integral(matches!(self.sgn, Positive), "ℤ", &format!("{}", self.mag))
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ops() {
+    type Z = Integer;
+    let z = |i: i128| Z::from_str(&i.to_string()).unwrap();
+
+    let values = [0, 1, -1, 7, -12, 1 << 64, -(1 << 64), (1 << 70) + 3, -(1 << 90) + 5, u64::MAX as i128, -(u64::MAX as i128)];
+    for a in values {
+      for b in values {
+        assert_eq!(z(a) + z(b), z(a + b));
+        assert_eq!(z(a) - z(b), z(a - b));
+        assert_eq!(z(a).cmp(&z(b)), a.cmp(&b));
+      }
+    }
+
+    // ```-0 = 0```
+    assert_eq!(z(-5) * Z::ZERO, Z::ZERO);
+    assert_eq!(-Z::ZERO, Z::ZERO);
+  }
+
+  #[test]
+  fn bitwise() {
+    type Z = Integer;
+    let z = |i: i128| Z::from_str(&i.to_string()).unwrap();
+
+    let values = [0, 1, -1, 5, -6, 1 << 70, -(1 << 70) + 3, i64::MAX as i128 * 3, i64::MIN as i128 * 5];
+    for a in values {
+      // ```!a = -a - 1```
+      assert_eq!(!z(a), z(!a));
+      assert_eq!(z(a) << 13, z(a << 13));
+      assert_eq!(z(a) >> 3, z(a >> 3));
+      assert_eq!(z(a) >> 100, z(a >> 100));
+      assert_eq!(z(a).bits(), (128 - if a < 0 { !a } else { a }.leading_zeros()) as usize);
+      assert_eq!(z(a).count_ones(), (a >= 0).then_some(a.count_ones() as usize));
+
+      for b in values {
+        assert_eq!(z(a) & z(b), z(a & b));
+        assert_eq!(z(a) | z(b), z(a | b));
+        assert_eq!(z(a) ^ z(b), z(a ^ b));
+      }
+
+      for idx in [0, 1, 2, 63, 64, 70, 100] {
+        assert_eq!(z(a).bit(idx), (a >> idx) & 1 == 1);
+
+        let mut set = z(a);
+        set.set_bit(idx, true);
+        assert_eq!(set, z(a | (1 << idx)));
+        set.set_bit(idx, false);
+        assert_eq!(set, z(a & !(1 << idx)));
+      }
+    }
+  }
+
+  #[test]
+  fn parse() {
+    type Z = Integer;
+    let z = |i: i128| Z::from_str(&i.to_string()).unwrap();
+
+    assert_eq!(Z::from_str("-0x_ff"), Err(ParseError::InvalidDigit(3)));
+    assert_eq!(Z::from_str("-0xff"), Ok(z(-255)));
+    assert_eq!(Z::from_str("+0b1010_1010"), Ok(z(170)));
+    assert_eq!(Z::from_str("0o777"), Ok(z(511)));
+    assert_eq!(Z::from_str("1_000_000_000_000_000_000_000"), Ok(z(10i128.pow(21))));
+    assert_eq!(Z::from_str_radix("-zz", 36), Ok(z(-1295)));
+    assert_eq!(Natural::from_str("00042"), Ok(Natural::from(42u32)));
+
+    assert_eq!(Z::from_str(""), Err(ParseError::Empty));
+    assert_eq!(Z::from_str("-"), Err(ParseError::Empty));
+    assert_eq!(Z::from_str("0x"), Err(ParseError::Empty));
+    assert_eq!(Z::from_str("12a4"), Err(ParseError::InvalidDigit(2)));
+    assert_eq!(Z::from_str("-1__0"), Err(ParseError::InvalidDigit(3)));
+    assert_eq!(Z::from_str("10_"), Err(ParseError::InvalidDigit(2)));
+    assert_eq!(Z::from_str("0b102"), Err(ParseError::InvalidDigit(4)));
+    assert_eq!(Z::from_str("123456789012345678901234567890x"), Err(ParseError::InvalidDigit(30)));
+  }
+
+  #[test]
+  fn conversions() {
+    type Z = Integer;
+
+    for i in [0, 1, -1, i128::MAX, i128::MIN, i64::MIN as i128, u64::MAX as i128 + 1] {
+      assert_eq!(i128::try_from(Z::from(i)), Ok(i));
+      assert_eq!(i64::try_from(Z::from(i)).ok(), i64::try_from(i).ok());
+      assert_eq!(u8::try_from(Z::from(i)).ok(), u8::try_from(i).ok());
+      assert_eq!(usize::try_from(Z::from(i)).ok(), usize::try_from(i).ok());
+    }
+    assert_eq!(u128::try_from(Z::from(u128::MAX)), Ok(u128::MAX));
+    assert_eq!(i128::try_from(Z::from(u128::MAX)), Err(()));
+    assert_eq!(u128::try_from(Z::from(u128::MAX) + Z::ONE), Err(()));
+    assert_eq!(Z::from(-5i8), Z::from(-5));
+    assert_eq!(Natural::try_from(Z::from(300u16)).and_then(u8::try_from), Err(()));
+
+    // ```2^53 + 1``` is a tie between ```2^53``` and ```2^53 + 2```
+    assert_eq!(Z::from((1i64 << 53) + 1).to_f64(), (1u64 << 53) as f64);
+    assert_eq!(Z::from((1i64 << 53) + 3).to_f64(), ((1u64 << 53) + 4) as f64);
+    assert_eq!(Z::from(-7).to_f64(), -7.0);
+    assert_eq!((Z::ONE << 1024).to_f64(), f64::INFINITY);
+    assert_eq!(((Z::ONE << 1024) - (Z::ONE << 971)).to_f64(), f64::MAX);
+    // the tie between ```f64::MAX``` and ```2^1024``` overflows
+    assert_eq!(((Z::ONE << 1024) - (Z::ONE << 970)).to_f64(), f64::INFINITY);
+    assert_eq!(((Z::ONE << 1024) - (Z::ONE << 970) - Z::ONE).neg().to_f64(), -f64::MAX);
+  }
+}
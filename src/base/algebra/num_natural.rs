@@ -1,9 +1,10 @@
+use crate::base::algebra::num_integer::Integer;
 use crate::base::algebra::repr::*;
 
 use std::cmp;
 use std::fmt;
 use std::mem;
-use std::ops::{Add, AddAssign, BitOr, BitOrAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign};
+use std::ops::{Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign};
 use std::str::FromStr;
 
 /// A natural ℕ.
@@ -18,6 +19,56 @@ impl Natural {
   /// The multiplicative double 2.
   pub const TWO: Natural = Natural(Digits::Fix(2));
 
+  /// Parse a natural written in base `radix`, with single underscores allowed between digits.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `radix` is not in `[2, 36]`.
+  pub fn from_str_radix(src: &str, radix: u32) -> Result<Natural, ParseError> {
+    Natural::parse(src, radix, 0)
+  }
+
+  // ```0x```, ```0o``` and ```0b``` prefixes select the radix
+  pub(crate) fn parse_prefixed(src: &str, pos: usize) -> Result<Natural, ParseError> {
+    match src.get(..2) {
+      Some("0x" | "0X") => Natural::parse(&src[2..], 16, pos + 2),
+      Some("0o" | "0O") => Natural::parse(&src[2..], 8, pos + 2),
+      Some("0b" | "0B") => Natural::parse(&src[2..], 2, pos + 2),
+      _ => Natural::parse(src, 10, pos),
+    }
+  }
+
+  // Error positions are offset by `pos`, the position of `src` in the whole input.
+  pub(crate) fn parse(src: &str, radix: u32, pos: usize) -> Result<Natural, ParseError> {
+    assert!((2..=36).contains(&radix), "radix must lie in [2, 36]");
+
+    let bytes = src.as_bytes();
+    let mut digits = String::with_capacity(bytes.len());
+    for (i, &byte) in bytes.iter().enumerate() {
+      // ```1_000```, but not ```_1```, ```1_``` or ```1__0```
+      if byte == b'_' && i > 0 && i + 1 < bytes.len() && bytes[i - 1] != b'_' {
+        continue;
+      }
+      if digit_from_utf8_byte(byte, radix).is_none() {
+        return Err(ParseError::InvalidDigit(pos + i));
+      }
+      digits.push(byte as char);
+    }
+
+    if digits.is_empty() {
+      return Err(ParseError::Empty);
+    }
+    Ok(Natural(Digits::parse(
+      digits.trim_start_matches('0'), //.
+      radix,
+    )?))
+  }
+
+  /// Convert to the nearest [`f64`] (ties to even), overflowing to infinity.
+  pub fn to_f64(&self) -> f64 {
+    Integer::from(self.clone()).to_f64()
+  }
+
   pub(crate) fn incr(&mut self) {
     *self = mem::take(self) + Self::ONE;
   }
@@ -41,8 +92,34 @@ impl Natural {
     self.0.trailing_zeros()
   }
 
+  /// Return the number of ones in the binary representation of `self` (population count).
+  pub fn count_ones(&self) -> usize {
+    self.0.count_ones()
+  }
+
+  /// Return the number of significant bits of `self`, i.e. `⌊log2(self)⌋ + 1` and `0` for zero.
+  pub fn bits(&self) -> usize {
+    self.0.bits()
+  }
+
+  /// Return the bit at position `idx` in the binary representation of `self`.
+  pub fn bit(&self, idx: usize) -> bool {
+    self.0.bit(idx)
+  }
+
+  /// Set the bit at position `idx` in the binary representation of `self`.
+  pub fn set_bit(&mut self, idx: usize, bit: bool) {
+    *self = Natural(mem::take(self).0.set_bit(idx, bit));
+  }
+
   /// Raise `self` to the power of `exp`.
   pub fn pow(self, exp: u64) -> Self {
+    match exp {
+      0 => return Self::ONE,
+      1 => return self,
+      _ => {}
+    }
+
     let mut bit = u64::BITS - 2 - exp.leading_zeros();
     let mut pow = self.clone() * self.clone();
 
@@ -59,6 +136,24 @@ impl Natural {
     pow
   }
 
+  /// Compute the integer square root `⌊√self⌋`.
+  pub fn sqrt(self) -> Self {
+    if self <= Self::ONE {
+      return self;
+    }
+
+    // Newton iteration from above
+    // ```x_{k+1} = (x_k + n/x_k)/2```
+    let mut x = Self::ONE << (self.bits() / 2 + 1);
+    loop {
+      let y = (x.clone() + self.clone() / x.clone()) >> 1;
+      if y >= x {
+        return x;
+      }
+      x = y;
+    }
+  }
+
   /// Compute the divisor and remainder of two naturals.
   pub fn div_rem(self, o: Self) -> (Self, Self) {
     let (q, r) = self.0.div_rem(o.0);
@@ -144,6 +239,195 @@ impl Natural {
     }
     b
   }
+
+  /// Compute the multinomial coefficient `(k_1 + ... + k_m)!/(k_1!*...*k_m!)`.
+  pub fn multinomial(k: &[Self]) -> Self {
+    // ```(k_1 + ... + k_m; k_1, ..., k_m) = ∏{i=1->m} (k_1 + ... + k_i k_i)```
+    let mut sum = Self::ZERO;
+    let mut m = Self::ONE;
+    for ki in k {
+      sum += ki.clone();
+      m *= Self::binomial(sum.clone(), ki.clone());
+    }
+    m
+  }
+
+  /// Compute the Fibonacci number `F_n`.
+  pub fn fibonacci(n: u64) -> Self {
+    Self::fibonacci_pair(n).0
+  }
+
+  /// Compute the Lucas number `L_n`.
+  pub fn lucas(n: u64) -> Self {
+    // ```L_n = 2F_n+1 - F_n```
+    let (f, g) = Self::fibonacci_pair(n);
+    g * Self::TWO - f
+  }
+
+  /// Compute the Catalan number `C_n = (2n n)/(n + 1)`.
+  pub fn catalan(n: u64) -> Self {
+    let n = Self::from(n);
+    Self::binomial(n.clone() * Self::TWO, n.clone()) / (n + Self::ONE)
+  }
+
+  /// Compute the Stirling number of the second kind `S(n, k)`, the number of partitions of `n` elements into `k`
+  /// blocks.
+  pub fn stirling2(n: u64, k: u64) -> Self {
+    if k > n {
+      return Self::ZERO;
+    }
+
+    // ```S(m + 1, j) = j*S(m, j) + S(m, j - 1)```
+    let mut row = vec![Self::ZERO; k as usize + 1];
+    row[0] = Self::ONE;
+    for _ in 0..n {
+      for j in (1..=k as usize).rev() {
+        row[j] = row[j].clone() * Self::from(j as u64) + row[j - 1].clone();
+      }
+      row[0] = Self::ZERO;
+    }
+    row.swap_remove(k as usize)
+  }
+
+  /// Compute the Bell number `B_n`, the number of partitions of `n` elements.
+  pub fn bell(n: u64) -> Self {
+    // Bell triangle: each row starts with the last entry of the previous one
+    let mut row = vec![Self::ONE];
+    for _ in 0..n {
+      let mut next = Vec::with_capacity(row.len() + 1);
+      next.push(row[row.len() - 1].clone());
+      for x in row {
+        next.push(next[next.len() - 1].clone() + x);
+      }
+      row = next;
+    }
+    row.swap_remove(0)
+  }
+
+  /// Compute the number of partitions `p(n)` of `n` into positive integers.
+  pub fn partition(n: u64) -> Self {
+    // pentagonal number theorem
+    // ```p(m) = ∑{k>=1} (-1)^(k + 1)*(p(m - k(3k - 1)/2) + p(m - k(3k + 1)/2))```
+    let mut p: Vec<Self> = vec![Self::ONE];
+    for m in 1..=n {
+      let (mut pos, mut neg) = (Self::ZERO, Self::ZERO);
+      for k in 1.. {
+        let g = k * (3 * k - 1) / 2;
+        if g > m {
+          break;
+        }
+        let mut term = p[(m - g) as usize].clone();
+        if g + k <= m {
+          term += p[(m - g - k) as usize].clone();
+        }
+        if k % 2 == 1 {
+          pos += term;
+        } else {
+          neg += term;
+        }
+      }
+      p.push(pos - neg);
+    }
+    p.swap_remove(n as usize)
+  }
+
+  /// Compute the divisor function `σ_k(n)`, the sum of the `k`-th powers of the divisors of `n`.
+  pub fn divisor_sigma(k: u64, n: Self) -> Self {
+    // ```σ_k(p_1^e_1*...*p_m^e_m) = ∏{i=1->m} ∑{j=0->e_i} p_i^(j*k)```
+    n.factor().into_iter().fold(Self::ONE, |acc, (p, e)| {
+      let pk = p.pow(k);
+      let mut sum = Self::ONE;
+      let mut pow = Self::ONE;
+      for _ in 0..e {
+        pow *= pk.clone();
+        sum += pow.clone();
+      }
+      acc * sum
+    })
+  }
+
+  /// Compute `self^e (mod m)`.
+  pub fn pow_mod(self, mut e: Self, m: &Self) -> Self {
+    let mut b = self % m.clone();
+    let mut r = Self::ONE % m.clone();
+    while e > Self::ZERO {
+      if e.bit(0) {
+        r = r * b.clone() % m.clone();
+      }
+      b = b.clone() * b % m.clone();
+      e >>= 1;
+    }
+    r
+  }
+
+  /// Test primality (Miller-Rabin).
+  ///
+  /// The test is deterministic below `3.3*10^24` and probabilistic (with a false positive rate far below `4^-12`)
+  /// above.
+  pub fn is_prime(&self) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if self < &Self::TWO {
+      return false;
+    }
+    if let Some(&b) = BASES.iter().find(|&&b| self.clone() % Self::from(b) == Self::ZERO) {
+      return self == &Self::from(b);
+    }
+
+    // ```n - 1 = d*2^s```
+    let n1 = self.clone() - Self::ONE;
+    let s = n1.trailing_zeros();
+    let d = n1.clone() >> s;
+    BASES.iter().all(|&b| {
+      let mut x = Self::from(b).pow_mod(d.clone(), self);
+      if x == Self::ONE || x == n1 {
+        return true;
+      }
+      for _ in 1..s {
+        x = x.clone() * x % self.clone();
+        if x == n1 {
+          return true;
+        }
+      }
+      false
+    })
+  }
+
+  /// Factor by trial division.
+  pub(crate) fn factor(mut self) -> Vec<(Self, u64)> {
+    let mut factors = vec![];
+    let mut p = Self::TWO;
+    while p.clone() * p.clone() <= self {
+      let mut k = 0;
+      while self.clone() % p.clone() == Self::ZERO {
+        self /= p.clone();
+        k += 1;
+      }
+      if k > 0 {
+        factors.push((p.clone(), k));
+      }
+      p += if p == Self::TWO { Self::ONE } else { Self::TWO };
+    }
+
+    if self > Self::ONE {
+      factors.push((self, 1));
+    }
+
+    factors
+  }
+
+  // ```(F_n, F_n+1)``` by fast doubling
+  fn fibonacci_pair(n: u64) -> (Self, Self) {
+    // ```F_2m = F_m*(2F_m+1 - F_m)```
+    // ```F_2m+1 = F_m^2 + F_m+1^2```
+    let mut f = (Self::ZERO, Self::ONE);
+    for i in (0..u64::BITS - n.leading_zeros()).rev() {
+      let (a, b) = f;
+      let c = a.clone() * (b.clone() * Self::TWO - a.clone());
+      let d = a.clone() * a + b.clone() * b;
+      f = if (n >> i) & 1 == 1 { (d.clone(), c + d) } else { (c, d) };
+    }
+    f
+  }
 }
 
 impl Add for Natural {
@@ -278,6 +562,50 @@ impl BitOrAssign for Natural {
   }
 }
 
+impl BitAnd for Natural {
+  type Output = Natural;
+
+  #[inline]
+  fn bitand(
+    self, //.
+    rhs: Self,
+  ) -> Self::Output {
+    Natural(self.0 & rhs.0)
+  }
+}
+
+impl BitAndAssign for Natural {
+  #[inline]
+  fn bitand_assign(
+    &mut self,
+    rhs: Self, //.
+  ) {
+    *self = mem::take(self) & rhs;
+  }
+}
+
+impl BitXor for Natural {
+  type Output = Natural;
+
+  #[inline]
+  fn bitxor(
+    self, //.
+    rhs: Self,
+  ) -> Self::Output {
+    Natural(self.0 ^ rhs.0)
+  }
+}
+
+impl BitXorAssign for Natural {
+  #[inline]
+  fn bitxor_assign(
+    &mut self,
+    rhs: Self, //.
+  ) {
+    *self = mem::take(self) ^ rhs;
+  }
+}
+
 impl Shl<usize> for Natural {
   type Output = Natural;
 
@@ -325,12 +653,12 @@ impl ShrAssign<usize> for Natural {
 impl FromStr for Natural {
   type Err = ParseError;
 
+  /// Parse a decimal natural, or a binary, octal or hexadecimal one with prefix `0b`, `0o` or `0x`.
   fn from_str(s: &str) -> Result<Natural, ParseError> {
-    let num = s.strip_prefix('+').unwrap_or(s).trim_start_matches('0');
-    Ok(Natural(Digits::parse(
-      num, //.
-      10,
-    )?))
+    match s.strip_prefix('+') {
+      Some(num) => Natural::parse_prefixed(num, 1),
+      None => Natural::parse_prefixed(s, 0),
+    }
   }
 }
 
@@ -374,6 +702,52 @@ impl TryFrom<Natural> for u64 {
   }
 }
 
+impl TryFrom<Natural> for u128 {
+  type Error = ();
+
+  fn try_from(n: Natural) -> Result<Self, Self::Error> {
+    match n.0 {
+      Digits::Fix(n) => Ok(n as u128),
+      Digits::Arb(array) if array.len() == 2 => Ok(Dual::from(array[0]) | Dual::from(array[1]) << WORD_BITS),
+      Digits::Arb(_) => Err(
+        (), //.
+      ),
+    }
+  }
+}
+
+// Conversions with the remaining unsigned primitives, through `u128`.
+macro_rules! natural_conversions {
+  ($($t:ty),*) => {
+    $(
+      impl From<$t> for Natural {
+        #[doc = concat!("Produce a natural from a [`", stringify!($t), "`].")]
+        fn from(n: $t) -> Self {
+          Natural::from(n as u128)
+        }
+      }
+
+      impl TryFrom<Natural> for $t {
+        type Error = ();
+
+        fn try_from(n: Natural) -> Result<Self, Self::Error> {
+          u128::try_from(n).and_then(|n| <$t>::try_from(n).map_err(|_| ()))
+        }
+      }
+    )*
+  };
+}
+
+natural_conversions!(u8, u16, usize);
+
+impl TryFrom<Natural> for u32 {
+  type Error = ();
+
+  fn try_from(n: Natural) -> Result<Self, Self::Error> {
+    u64::try_from(n).and_then(|n| u32::try_from(n).map_err(|_| ()))
+  }
+}
+
 impl Default for Natural {
   /// The identity natural 0 (zero).
   fn default() -> Self {
@@ -0,0 +1,391 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::poly::{GfPoly, Poly};
+use super::FiniteField;
+use crate::{Integer, Natural, Rational};
+
+/// A p-adic number `p^v*u (mod p^(v + r))`, with `u` a unit known to `r` digits of relative precision.
+///
+/// Precision is tracked through arithmetic: a result is only given to the digits that are determined by the
+/// operands. Zero is known to an absolute precision, `0 (mod p^v)`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Padic {
+  p: Natural,
+  /// Valuation, or absolute precision of zero.
+  val: i64,
+  /// Unit part in ```[0, p^r[```, coprime to `p` (`0` for zero).
+  unit: Natural,
+  /// Relative precision (`0` for zero).
+  prec: u64,
+}
+
+impl Padic {
+  /// Create the p-adic expansion of `q` to `prec` digits of relative precision, or `None` if `p` is not prime or
+  /// `prec = 0`.
+  pub fn from_rational(p: Natural, q: &Rational, prec: u64) -> Option<Padic> {
+    if !p.is_prime() || prec == 0 {
+      return None;
+    }
+    let Some(val) = q.valuation(&p) else {
+      return Some(Padic::zero(p, prec as i64));
+    };
+
+    // ```n/d = p^v*(n'/d')```
+    let strip = |z: &Integer| z.clone().abs() / p.clone().pow(z.valuation(&p).unwrap_or(0));
+    let m = p.clone().pow(prec);
+    let unit = strip(&q.num) * inv_mod(strip(&q.den), &m) % m.clone();
+    let unit = if q.is_negative() { (m.clone() - unit) % m } else { unit };
+    Some(Padic { p, val, unit, prec })
+  }
+
+  /// Create the p-adic expansion of `z` to `prec` digits of relative precision, or `None` if `p` is not prime or
+  /// `prec = 0`.
+  pub fn from_integer(p: Natural, z: &Integer, prec: u64) -> Option<Padic> {
+    Padic::from_rational(p, &Rational::from(z.clone()), prec)
+  }
+
+  /// Return `0 (mod p^n)`.
+  pub fn zero(p: Natural, n: i64) -> Padic {
+    Padic {
+      p, //.
+      val: n,
+      unit: Natural::ZERO,
+      prec: 0,
+    }
+  }
+
+  /// Return the prime `p`.
+  pub fn prime(&self) -> &Natural {
+    &self.p
+  }
+
+  /// Return the valuation `ν_p(self)`, or `None` for zero.
+  pub fn valuation(&self) -> Option<i64> {
+    (!self.is_zero()).then_some(self.val)
+  }
+
+  /// Return the unit part `u`, with `self = p^v*u`.
+  pub fn unit(&self) -> &Natural {
+    &self.unit
+  }
+
+  /// Return the relative precision, the number of known digits of the unit part.
+  pub fn precision(&self) -> u64 {
+    self.prec
+  }
+
+  /// Return the absolute precision `n`, with `self` known modulo `p^n`.
+  pub fn absolute_precision(&self) -> i64 {
+    self.val + self.prec as i64
+  }
+
+  /// Return `true` if `self` is zero to its precision.
+  pub fn is_zero(&self) -> bool {
+    self.prec == 0
+  }
+
+  /// Return the digits `[d_0, d_1, ..., d_r-1]` of the unit part in base `p`.
+  pub fn digits(&self) -> Vec<Natural> {
+    let mut u = self.unit.clone();
+    let mut digits = vec![];
+    for _ in 0..self.prec {
+      let (q, r) = u.div_rem(self.p.clone());
+      digits.push(r);
+      u = q;
+    }
+    digits
+  }
+
+  /// Return the rational `p^v*u`, the truncated expansion.
+  pub fn to_rational(&self) -> Rational {
+    let u = Integer::from(self.unit.clone());
+    match self.val {
+      v if v >= 0 => Rational::from(u * Integer::from(self.p.clone().pow(v as u64))),
+      v => Rational::new(u, Integer::from(self.p.clone().pow(v.unsigned_abs()))),
+    }
+  }
+
+  /// Lower the absolute precision to `n`, so that `self` is known modulo `p^n`.
+  pub fn truncate(self, n: i64) -> Padic {
+    if n >= self.absolute_precision() {
+      self
+    } else if n <= self.val {
+      Padic::zero(self.p, n)
+    } else {
+      let prec = (n - self.val) as u64;
+      let unit = self.unit % self.p.clone().pow(prec);
+      Padic { unit, prec, ..self }
+    }
+  }
+
+  /// Return the multiplicative inverse, or `None` for zero.
+  pub fn inv(&self) -> Option<Padic> {
+    (!self.is_zero()).then(|| Padic {
+      p: self.p.clone(),
+      val: -self.val,
+      unit: inv_mod(self.unit.clone(), &self.p.clone().pow(self.prec)),
+      prec: self.prec,
+    })
+  }
+
+  /// Lift an approximate root `a` of `f` to a root of `f` in `ℤ_p` known modulo `p^prec` (Hensel's lemma), or `None`
+  /// if `p` is not prime, `prec = 0` or ```ν_p(f(a)) <= 2ν_p(f'(a))```.
+  pub fn hensel(f: &Poly, a: &Integer, p: Natural, prec: u64) -> Option<Padic> {
+    if !p.is_prime() || prec == 0 {
+      return None;
+    }
+    let f = integral(f);
+    let df: Vec<_> = f.iter().enumerate().skip(1).map(|(k, c)| c.clone() * Integer::from(k as u64)).collect();
+
+    // ```|x - r| = |f(x)|/|f'(x)|```, ```ν_p(f'(x)) = m``` along the iteration
+    let m = horner(&df, a).valuation(&p)?;
+    if horner(&f, a).valuation(&p).is_some_and(|v| v <= 2 * m) {
+      return None;
+    }
+
+    let pm = Integer::from(p.clone().pow(m));
+    let modulus = p.clone().pow(prec + 2 * m);
+    let mz = Integer::from(modulus.clone());
+    let mut x = a.clone().rem_euclid(mz.clone());
+    loop {
+      let fx = horner(&f, &x).rem_euclid(mz.clone());
+      if fx.valuation(&p).is_none_or(|v| v >= prec + m) {
+        break;
+      }
+      // ```x = x - f(x)/f'(x)```
+      let u = (horner(&df, &x) / pm.clone()).rem_euclid(mz.clone()).abs();
+      let step = fx / pm.clone() * Integer::from(inv_mod(u, &modulus));
+      x = (x - step).rem_euclid(mz.clone());
+    }
+
+    Some(Padic::from_integer(p, &x, prec)?.truncate(prec as i64))
+  }
+
+  /// Return the roots of `f` in `ℤ_p` that are simple modulo `p`, known modulo `p^prec` and sorted by their residue.
+  ///
+  /// The roots modulo `p` are read from the linear factors of `f` over `𝔽_p`, then lifted by [`Padic::hensel`].
+  pub fn roots(f: &Poly, p: Natural, prec: u64) -> Vec<Padic> {
+    let Some(field) = FiniteField::prime(p.clone()) else {
+      return vec![];
+    };
+    let fp = GfPoly::from_integers(&field, &integral(f));
+    if fp.is_zero() {
+      return vec![];
+    }
+
+    let mut roots: Vec<_> = (fp.factor().1.into_iter())
+      .filter(|(g, k)| *k == 1 && g.deg() == Some(1))
+      .filter_map(|(g, _)| {
+        let r = (-g.coef()[0].clone()).coef().first().cloned().unwrap_or(Natural::ZERO);
+        Padic::hensel(f, &Integer::from(r), p.clone(), prec)
+      })
+      .collect();
+    roots.sort_by(|a, b| (a.unit.clone() % p.clone()).cmp(&(b.unit.clone() % p.clone())).then(a.val.cmp(&b.val)));
+    roots
+  }
+
+  // Normalize ```p^v*s (mod p^n)```.
+  fn normalize(p: Natural, v: i64, s: Natural, n: i64) -> Padic {
+    let Some(k) = Integer::from(s.clone()).valuation(&p) else {
+      return Padic::zero(p, n);
+    };
+    let val = v + k as i64;
+    let unit = s / p.clone().pow(k);
+    Padic { p, val, unit, prec: (n - val) as u64 }
+  }
+
+  fn same_prime(&self, o: &Padic) {
+    assert!(self.p == o.p, "p-adic numbers of different primes");
+  }
+}
+
+impl Add for Padic {
+  type Output = Padic;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    self.same_prime(&rhs);
+    let n = self.absolute_precision().min(rhs.absolute_precision());
+    if self.is_zero() {
+      return rhs.truncate(n);
+    } else if rhs.is_zero() {
+      return self.truncate(n);
+    }
+
+    // ```p^v*(u_a*p^(v_a - v) + u_b*p^(v_b - v)) (mod p^n)```
+    let v = self.val.min(rhs.val);
+    if n <= v {
+      return Padic::zero(self.p, n);
+    }
+    let shift = |x: &Padic| x.unit.clone() * x.p.clone().pow((x.val - v) as u64);
+    let s = (shift(&self) + shift(&rhs)) % self.p.clone().pow((n - v) as u64);
+    Padic::normalize(self.p, v, s, n)
+  }
+}
+
+impl Sub for Padic {
+  type Output = Padic;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    self + -rhs
+  }
+}
+
+impl Neg for Padic {
+  type Output = Padic;
+
+  fn neg(self) -> Self::Output {
+    if self.is_zero() {
+      return self;
+    }
+    let unit = self.p.clone().pow(self.prec) - self.unit;
+    Padic { unit, ..self }
+  }
+}
+
+impl Mul for Padic {
+  type Output = Padic;
+
+  fn mul(self, rhs: Self) -> Self::Output {
+    self.same_prime(&rhs);
+    if self.is_zero() || rhs.is_zero() {
+      return Padic::zero(self.p, self.val + rhs.val);
+    }
+
+    let prec = self.prec.min(rhs.prec);
+    let unit = self.unit * rhs.unit % self.p.clone().pow(prec);
+    Padic {
+      p: self.p,
+      val: self.val + rhs.val,
+      unit,
+      prec,
+    }
+  }
+}
+
+impl Div for Padic {
+  type Output = Padic;
+
+  // division is the multiplication by the inverse
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn div(self, rhs: Self) -> Self::Output {
+    self * rhs.inv().expect("attempt to divide by zero")
+  }
+}
+
+impl fmt::Display for Padic {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    // ```...d_1 d_0 . d_-1 d_-2...``` in base `p`, from the absolute precision down
+    let digits = self.digits();
+    let digit = |i: i64| match i - self.val {
+      k if k < 0 || k >= digits.len() as i64 => Natural::ZERO,
+      k => digits[k as usize].clone(),
+    };
+    let fmt_digit = |d: Natural| match u32::try_from(d.clone()) {
+      Ok(d) if self.p <= Natural::from(36u32) => char::from_digit(d, 36).map_or_else(String::new, |c| c.to_string()),
+      _ => format!("[{d}]"),
+    };
+
+    let n = self.absolute_precision();
+    let int: String = (0..n).rev().map(|i| fmt_digit(digit(i))).collect();
+    let frac: String = (self.val.min(0)..n.min(0)).rev().map(|i| fmt_digit(digit(i))).collect();
+    match frac.as_str() {
+      "" => write!(f, "…{int}_{}", self.p),
+      _ => write!(f, "…{int}.{frac}_{}", self.p),
+    }
+  }
+}
+
+// ```a^-1 (mod m)```, for ```gcd(a, m) = 1```
+fn inv_mod(a: Natural, m: &Natural) -> Natural {
+  let mz = Integer::from(m.clone());
+  let (mut r0, mut r1) = (mz.clone(), Integer::from(a % m.clone()));
+  let (mut s0, mut s1) = (Integer::ZERO, Integer::ONE);
+  while r1 != Integer::ZERO {
+    let q = r0.clone() / r1.clone();
+    (r0, r1) = (r1.clone(), r0 - q.clone() * r1);
+    (s0, s1) = (s1.clone(), s0 - q * s1);
+  }
+  s0.rem_euclid(mz).abs()
+}
+
+// Integer coefficients of a multiple of `f`, with the same roots.
+fn integral(f: &Poly) -> Vec<Integer> {
+  let l = f.coef().iter().fold(Integer::ONE, |l, c| Integer::lcm(l, c.den.clone()));
+  f.coef().iter().map(|c| (c.clone() * Rational::from(l.clone())).reduce().num).collect()
+}
+
+fn horner(c: &[Integer], x: &Integer) -> Integer {
+  c.iter().rev().fold(Integer::ZERO, |acc, c| acc * x.clone() + c.clone())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn q(n: i64, d: i64) -> Rational {
+    Rational::new(Integer::from(n), Integer::from(d))
+  }
+
+  fn padic(p: u64, x: Rational, prec: u64) -> Padic {
+    Padic::from_rational(Natural::from(p), &x, prec).unwrap()
+  }
+
+  #[test]
+  fn valuations() {
+    let p = Natural::from(3u64);
+    assert_eq!(Integer::from(-162).valuation(&p), Some(4));
+    assert_eq!(Integer::from(7).valuation(&p), Some(0));
+    assert_eq!(Integer::ZERO.valuation(&p), None);
+    assert_eq!(Integer::from(8).valuation(&Natural::ONE), None);
+    assert_eq!(q(5, 18).valuation(&p), Some(-2));
+    assert_eq!(q(-27, 10).valuation(&p), Some(3));
+  }
+
+  #[test]
+  fn arithmetic() {
+    assert_eq!(padic(5, q(-1, 1), 4).to_string(), "…4444_5");
+    assert_eq!(padic(5, q(1, 3), 4).to_string(), "…3132_5");
+    assert_eq!(padic(5, q(7, 10), 3).to_string(), "…23.1_5");
+    assert_eq!(padic(7, q(0, 1), 3).to_string(), "…000_7");
+    assert_eq!(padic(37, q(36, 1), 2).to_string(), "…[0][36]_37");
+    assert!(Padic::from_rational(Natural::from(6u64), &q(1, 1), 4).is_none());
+
+    // ```1/3*3 = 1```, ```1/3 + 2/3 = 1```
+    let third = padic(5, q(1, 3), 6);
+    assert_eq!(third.clone() * padic(5, q(3, 1), 6), padic(5, q(1, 1), 6));
+    assert_eq!(third.clone() + padic(5, q(2, 3), 6), padic(5, q(1, 1), 6));
+    assert_eq!(third.inv().unwrap(), padic(5, q(3, 1), 6));
+    // cancellation loses relative precision
+    let d = padic(5, q(26, 1), 4) - padic(5, q(1, 1), 4);
+    assert_eq!((d.valuation(), d.precision(), d.to_rational()), (Some(2), 2, q(25, 1)));
+    let d = padic(5, q(626, 1), 4) - padic(5, q(1, 1), 4);
+    assert!(d.is_zero() && d.absolute_precision() == 4);
+    assert_eq!(padic(5, q(50, 1), 3) / padic(5, q(10, 1), 3), padic(5, q(5, 1), 3));
+    assert_eq!(padic(2, q(3, 4), 5) * padic(2, q(4, 1), 5), padic(2, q(3, 1), 5));
+  }
+
+  #[test]
+  fn hensel() {
+    let poly = |c: &[i64]| Poly::from_integers(c.iter().map(|&c| Integer::from(c)).collect());
+    // ```√2 ∈ ℤ_7```
+    let roots = Padic::roots(&poly(&[-2, 0, 1]), Natural::from(7u64), 10);
+    assert_eq!(roots.len(), 2);
+    assert_eq!(roots[0].digits()[..4], [3u64, 1, 2, 6].map(Natural::from));
+    for r in roots {
+      let two = r.clone() * r;
+      assert_eq!(two, padic(7, q(2, 1), 10));
+    }
+    assert!(Padic::roots(&poly(&[-3, 0, 1]), Natural::from(7u64), 10).is_empty());
+
+    // ```√17 ∈ ℤ_2```, with ```ν_2(f'(1)) = 1```
+    let r = Padic::hensel(&poly(&[-17, 0, 1]), &Integer::ONE, Natural::TWO, 20).unwrap();
+    assert_eq!(r.absolute_precision(), 20);
+    let sq = r.to_rational() * r.to_rational() - q(17, 1);
+    assert!(sq.valuation(&Natural::TWO).unwrap() >= 20);
+    assert!(Padic::hensel(&poly(&[-3, 0, 1]), &Integer::ONE, Natural::TWO, 20).is_none());
+    // rational coefficients
+    let r = Padic::hensel(&Poly::new(vec![q(-1, 3), q(1, 1)]), &Integer::from(2), Natural::from(5u64), 4).unwrap();
+    assert_eq!(r, padic(5, q(1, 3), 4));
+  }
+}
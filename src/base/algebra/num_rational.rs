@@ -1,8 +1,15 @@
+use crate::base::algebra::num_float::{Float, Round};
 use crate::base::algebra::num_integer::Integer;
+use crate::base::algebra::num_natural::Natural;
+use crate::base::algebra::repr::{ParseError, Sign};
 
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+// Largest decimal exponent accepted when parsing, ```10^(2^20)``` has about 3.5 million bits.
+const MAX_EXP: u64 = 1 << 20;
 
 /// A rational ℚ.
 #[derive(Debug, Clone)]
@@ -23,6 +30,68 @@ impl Rational {
     }
   }
 
+  /// Convert to the nearest [`f64`] (ties to even), overflowing to infinity and underflowing to zero.
+  pub fn to_f64(&self) -> f64 {
+    // the precision of the result depends on the magnitude, found by truncation
+    let Some(e) = Float::from_rational(self.clone(), 64, Round::Zero).magnitude() else {
+      return 0.0;
+    };
+
+    match e + 1074 {
+      prec if prec >= 1 => Float::from_rational(self.clone(), prec.min(53) as usize, Round::Nearest).to_f64(),
+      // ```2^-1075 <= |x| < 2^-1074```, the tie rounds to zero
+      0 => {
+        let tie = Rational::new(Integer::ONE, Integer::from(Natural::ONE << 1075));
+        let abs = if self.is_negative() { -self.clone() } else { self.clone() };
+        let sub = if abs > tie { f64::from_bits(1) } else { 0.0 };
+        if self.is_negative() {
+          -sub
+        } else {
+          sub
+        }
+      }
+      _ if self.is_negative() => -0.0,
+      _ => 0.0,
+    }
+  }
+
+  /// Return the largest integer ```⌊x⌋ <= x```.
+  pub fn floor(&self) -> Integer {
+    let q = self.clone().reduce();
+    q.num.div_euclid(q.den)
+  }
+
+  /// Return the smallest integer ```⌈x⌉ >= x```.
+  pub fn ceil(&self) -> Integer {
+    -(-self.clone()).floor()
+  }
+
+  /// Return the integer part, rounding towards zero.
+  pub fn trunc(&self) -> Integer {
+    if self.is_negative() {
+      self.ceil()
+    } else {
+      self.floor()
+    }
+  }
+
+  /// Return the nearest integer, ties to even.
+  pub fn round(&self) -> Integer {
+    let floor = self.floor();
+    // ```2*(x - ⌊x⌋)``` against 1
+    let twice = (self.clone() - Rational::from(floor.clone())) * Rational::from(Integer::TWO);
+    match twice.cmp(&Rational::from(Integer::ONE)) {
+      Ordering::Less => floor,
+      Ordering::Equal if floor.clone().rem_euclid(Integer::TWO) == Integer::ZERO => floor,
+      _ => floor + Integer::ONE,
+    }
+  }
+
+  /// Return the fractional part ```x - ⌊x⌋ ∈ [0, 1[```.
+  pub fn fract(&self) -> Rational {
+    (self.clone() - Rational::from(self.floor())).reduce()
+  }
+
   /// Return `true` if `self` has a positive sign.
   pub fn is_positive(&self) -> bool {
     !self.is_negative()
@@ -32,6 +101,15 @@ impl Rational {
     (self.num.is_negative() && self.den.is_positive()) || (self.num.is_positive() && self.den.is_negative())
   }
 
+  // ```n/d = (n/g)/(d/g), g = gcd(n, d), d > 0```
+  pub(crate) fn reduce(self) -> Rational {
+    assert!(self.den != Integer::ZERO, "attempt to divide by zero");
+
+    let g = Integer::gcd(self.num.clone(), self.den.clone());
+    let sgn = Integer::from(self.den.ord() as i64);
+    Rational::new(self.num / g.clone() * sgn.clone(), self.den / g * sgn)
+  }
+
   /// Compute the Greatest Common Divisor (GCD) of two rationals `u` and `v`.
   pub fn gcd(u: Self, v: Self) -> Self {
     let (a, c) = (u.num, v.num);
@@ -45,6 +123,31 @@ impl Rational {
     )
   }
 
+  /// Compute the p-adic valuation ```ν_p(n/d) = ν_p(n) - ν_p(d)```, or `None` if `self = 0` or `p < 2`.
+  pub fn valuation(&self, p: &Natural) -> Option<i64> {
+    Some(self.num.valuation(p)? as i64 - self.den.valuation(p)? as i64)
+  }
+
+  /// Compute the Bernoulli number `B_n` (with `B_1 = -1/2`).
+  pub fn bernoulli(n: u64) -> Rational {
+    if n == 1 {
+      return Rational::new(Integer::NEG_ONE, Integer::TWO);
+    }
+    if n % 2 == 1 {
+      return Rational::from(Integer::ZERO);
+    }
+
+    // Akiyama–Tanigawa: ```a_j = (j + 1)*(a_j - a_j+1)```, which leaves ```B_n``` in ```a_0``` (```B_1 = 1/2```)
+    let mut a: Vec<Rational> = Vec::with_capacity(n as usize + 1);
+    for m in 0..=n {
+      a.push(Rational::new(Integer::ONE, Integer::from(m + 1)));
+      for j in (1..=m as usize).rev() {
+        a[j - 1] = (Rational::from(Integer::from(j as u64)) * (a[j - 1].clone() - a[j].clone())).reduce();
+      }
+    }
+    a.swap_remove(0)
+  }
+
   /// Compute the Lowest Common Multiple (LCM) of two rationals `u` and `v`.
   pub fn lcm(u: Self, v: Self) -> Self {
     let (a, c) = (u.num, v.num);
@@ -55,6 +158,140 @@ impl Rational {
       Integer::gcd(b, d),
     )
   }
+
+  /// Expand `self` into a simple continued fraction `[a_0; a_1, ..., a_n]`, with `a_k > 0` for `k > 0`.
+  pub fn to_continued_fraction(&self) -> Vec<Integer> {
+    let sgn = Integer::from(self.den.ord() as i64);
+    let (mut n, mut d) = (self.num.clone() * sgn.clone(), self.den.clone() * sgn);
+
+    let mut terms = vec![];
+    while d != Integer::ZERO {
+      // ```n/d = a + r/d, 0 <= r < d```
+      let a = n.clone().div_euclid(d.clone());
+      let r = n - a.clone() * d.clone();
+      terms.push(a);
+      (n, d) = (d, r);
+    }
+
+    terms
+  }
+
+  /// Collapse a simple continued fraction `[a_0; a_1, ..., a_n]`, or `None` if it is empty.
+  pub fn from_continued_fraction(terms: &[Integer]) -> Option<Rational> {
+    Rational::convergents(terms).pop()
+  }
+
+  /// Compute the convergents `p_k/q_k` of a continued fraction `[a_0; a_1, ..., a_n]`.
+  pub fn convergents(terms: &[Integer]) -> Vec<Rational> {
+    // ```p_k = a_k*p_(k-1) + p_(k-2), q_k = a_k*q_(k-1) + q_(k-2)```
+    let (mut p0, mut p1) = (Integer::ZERO, Integer::ONE);
+    let (mut q0, mut q1) = (Integer::ONE, Integer::ZERO);
+
+    let mut convergents = vec![];
+    for a in terms {
+      (p0, p1) = (p1.clone(), a.clone() * p1 + p0);
+      (q0, q1) = (q1.clone(), a.clone() * q1 + q0);
+      convergents.push(Rational::new(p1.clone(), q1.clone()));
+    }
+
+    convergents
+  }
+
+  /// Find the closest rational to `self` with a denominator at most `max_den`.
+  ///
+  /// Panics if `max_den` is zero.
+  pub fn limit_denominator(&self, max_den: Natural) -> Rational {
+    assert!(max_den != Natural::ZERO, "`max_den` should be at least 1");
+
+    let max_den = Integer::from(max_den);
+    let sgn = Integer::from(self.den.ord() as i64);
+    let (mut n, mut d) = (self.num.clone() * sgn.clone(), self.den.clone() * sgn);
+    if d <= max_den {
+      return self.clone();
+    }
+
+    let (mut p0, mut p1) = (Integer::ZERO, Integer::ONE);
+    let (mut q0, mut q1) = (Integer::ONE, Integer::ZERO);
+    let target = Rational::new(n.clone(), d.clone());
+    loop {
+      let a = n.clone().div_euclid(d.clone());
+      let q2 = q0.clone() + a.clone() * q1.clone();
+      if q2 > max_den {
+        break;
+      }
+
+      (p0, p1) = (p1.clone(), p0 + a.clone() * p1);
+      (q0, q1) = (q1, q2);
+      (n, d) = (d.clone(), n - a * d);
+    }
+
+    // The best approximation is either the last convergent or the semiconvergent
+    // ```(p0 + k*p1)/(q0 + k*q1), k = (max_den - q0)/q1```
+    let k = (max_den - q0.clone()) / q1.clone();
+    let semi = Rational::new(p0 + k.clone() * p1.clone(), q0 + k * q1.clone());
+    let conv = Rational::new(p1, q1);
+
+    let dist = |q: &Rational| {
+      let d = q.clone() - target.clone();
+      if d.is_negative() {
+        -d
+      } else {
+        d
+      }
+    };
+    if dist(&conv) <= dist(&semi) {
+      conv
+    } else {
+      semi
+    }
+  }
+
+  /// Expand the quadratic irrational `(p + sqrt(d))/q` into a periodic continued fraction.
+  ///
+  /// Returns the pre-period `[a_0; ..., a_m]` and the repeating period `(b_1, ..., b_k)`. The period is empty
+  /// when `sqrt(d)` is rational. Returns `None` if `q` is zero.
+  pub fn quadratic_continued_fraction(p: Integer, d: Natural, q: Integer) -> Option<(Vec<Integer>, Vec<Integer>)> {
+    if q == Integer::ZERO {
+      return None;
+    }
+
+    let s = d.clone().sqrt();
+    if s.clone() * s.clone() == d {
+      let q = Rational::new(p + Integer::from(s), q);
+      return Some((q.to_continued_fraction(), vec![]));
+    }
+
+    let (mut p, mut q, mut d) = (p, q, d);
+    if (Integer::from(d.clone()) - p.clone() * p.clone()) % q.clone() != Integer::ZERO {
+      // ```(p + sqrt(d))/q = (p*|q| + sqrt(d*q^2))/(q*|q|)```
+      let m = q.clone().abs();
+      (p, d, q) = (p * Integer::from(m.clone()), d * m.clone() * m.clone(), q * Integer::from(m));
+    }
+    let s = Integer::from(d.clone().sqrt());
+    let d = Integer::from(d);
+
+    let mut seen: Vec<(Integer, Integer)> = vec![];
+    let mut terms = vec![];
+    loop {
+      if let Some(j) = seen.iter().position(|state| state == &(p.clone(), q.clone())) {
+        let period = terms.split_off(j);
+        return Some((terms, period));
+      }
+      seen.push((p.clone(), q.clone()));
+
+      // ```a = floor((p + sqrt(d))/q)```
+      let a = if q.is_positive() {
+        (p.clone() + s.clone()).div_euclid(q.clone())
+      } else {
+        -((p.clone() + s.clone()).div_euclid(-q.clone()) + Integer::ONE)
+      };
+
+      // ```p' = a*q - p, q' = (d - p'^2)/q```
+      p = a.clone() * q.clone() - p;
+      q = (d.clone() - p.clone() * p.clone()) / q;
+      terms.push(a);
+    }
+  }
 }
 
 impl Eq for Rational {}
@@ -71,34 +308,14 @@ impl PartialOrd for Rational {
 }
 
 impl Ord for Rational {
-  fn cmp(
-    &self,
-    o: &Rational, // finite recursive quo/rem cmp
-  ) -> Ordering {
-    let (lhs_int, rhs_int) = (self.num.clone() / self.den.clone(), o.num.clone() / o.den.clone());
-    let (lhs_rem, rhs_rem) = (self.num.clone() % self.den.clone(), o.num.clone() % o.den.clone());
-    let int_cmp = lhs_int.cmp(&rhs_int);
-
-    if int_cmp != Ordering::Equal {
-      int_cmp
+  fn cmp(&self, o: &Rational) -> Ordering {
+    // ```a/b < c/d ⇔ a*d*sgn(b*d) < c*b*sgn(b*d)```
+    let lhs = self.num.clone() * o.den.clone();
+    let rhs = o.num.clone() * self.den.clone();
+    if self.den.is_negative() == o.den.is_negative() {
+      lhs.cmp(&rhs)
     } else {
-      match (lhs_rem != Integer::ZERO, rhs_rem != Integer::ZERO) {
-        (false, false) => {
-          Ordering::Equal //.
-        }
-        (false, true) => {
-          Ordering::Less //.
-        }
-        (true, false) => {
-          Ordering::Greater //.
-        }
-        (true, true) => {
-          // ```a/(a % b) < c/(c % d)```
-          let lhs_recip = Rational::new(self.den.clone(), lhs_rem);
-          let rhs_recip = Rational::new(o.den.clone(), rhs_rem);
-          lhs_recip.cmp(&rhs_recip).reverse()
-        }
-      }
+      rhs.cmp(&lhs)
     }
   }
 }
@@ -119,6 +336,73 @@ impl From<Integer> for Rational {
   }
 }
 
+impl TryFrom<f64> for Rational {
+  type Error = ();
+
+  /// Convert a finite [`f64`] exactly.
+  fn try_from(x: f64) -> Result<Self, Self::Error> {
+    Float::try_from(x).map(|x| x.to_rational())
+  }
+}
+
+impl FromStr for Rational {
+  type Err = ParseError;
+
+  /// Parse a fraction `a/b` of integers, or an exact decimal such as `-1.25` or `1e-3`.
+  fn from_str(s: &str) -> Result<Rational, ParseError> {
+    if let Some((num, den)) = s.split_once('/') {
+      let num = Integer::parse(num, 0)?;
+      let den = Integer::parse(den, s.len() - den.len())?;
+      if den == Integer::ZERO {
+        return Err(ParseError::ZeroDenominator);
+      }
+      return Ok(Rational::new(num, den).reduce());
+    }
+
+    let (sgn, body, pos) = Integer::split_sign(s);
+    if matches!(body.get(..2), Some("0x" | "0X" | "0o" | "0O" | "0b" | "0B")) {
+      return Integer::parse(s, 0).map(Rational::from);
+    }
+
+    // ```m.f e k = (m*10^|f| + f)*10^(k - |f|)```
+    let (mantissa, exp) = match body.find(['e', 'E']) {
+      Some(i) => (&body[..i], Some((&body[i + 1..], pos + i))),
+      None => (body, None),
+    };
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    match (mantissa.is_empty(), int.is_empty() && frac.is_empty()) {
+      (true, _) if exp.is_none() => return Err(ParseError::Empty),
+      (_, true) => return Err(ParseError::InvalidDigit(pos)),
+      _ => {}
+    }
+
+    let parse = |src: &str, at: usize| if src.is_empty() { Ok(Natural::ZERO) } else { Natural::parse(src, 10, at) };
+    let places = frac.bytes().filter(u8::is_ascii_digit).count() as i64;
+    let mag = Integer::from(parse(int, pos)? * Natural::from(10u32).pow(places as u64) + parse(frac, pos + int.len() + 1)?);
+
+    let k = match exp {
+      None => 0,
+      Some((src, at)) => match Integer::parse(src, at + 1) {
+        Ok(k) => {
+          let abs = u64::try_from(k.clone().abs()).ok().and_then(|m| i64::try_from(m).ok()).ok_or(ParseError::Overflow)?;
+          if k.is_negative() {
+            -abs
+          } else {
+            abs
+          }
+        }
+        Err(ParseError::Empty) => return Err(ParseError::InvalidDigit(at)),
+        Err(err) => return Err(err),
+      },
+    };
+
+    let scale = k.checked_sub(places).filter(|e| e.unsigned_abs() <= MAX_EXP).ok_or(ParseError::Overflow)?;
+    let ten = Integer::from(10).pow(scale.unsigned_abs());
+    let mag = if scale >= 0 { Rational::from(mag * ten) } else { Rational::new(mag, ten).reduce() };
+    Ok(if matches!(sgn, Sign::Negative) { -mag } else { mag })
+  }
+}
+
 impl Add for Rational {
   type Output = Rational;
 
@@ -321,5 +605,148 @@ mod tests {
       n1_2.clone() * (r3_7.clone() * r9_4.clone()), //=
       (n1_2.clone() * r9_4.clone()) * r3_7.clone()
     );
+
+    // negative fractions order by value
+    assert!(Q::new(Z::from(-7), Z::from(16)) < Q::from(Z::ZERO));
+    assert!(Q::new(Z::from(-3), Z::from(2)) < Q::new(Z::from(-4), Z::from(3)));
+    assert!(Q::new(Z::from(1), Z::from(-2)) < Q::new(Z::from(-1), Z::from(3)));
+  }
+
+  #[test]
+  fn continued_fractions() {
+    type Z = Integer;
+    type Q = Rational;
+    let cf = |terms: &[i64]| terms.iter().map(|&a| Z::from(a)).collect::<Vec<_>>();
+
+    // ```415/93 = [4; 2, 6, 7]```
+    let r = Q::new(Z::from(415), Z::from(93));
+    assert_eq!(r.to_continued_fraction(), cf(&[4, 2, 6, 7]));
+    assert_eq!(
+      Q::convergents(&cf(&[4, 2, 6, 7])),
+      vec![Q::from(Z::from(4)), Q::new(Z::from(9), Z::from(2)), Q::new(Z::from(58), Z::from(13)), r.clone()]
+    );
+    assert_eq!(Q::from_continued_fraction(&r.to_continued_fraction()), Some(r));
+    assert_eq!(Q::from_continued_fraction(&[]), None);
+
+    // floored terms ```-415/93 = [-5; 1, 1, 6, 7]```
+    let r = Q::new(Z::from(415), Z::from(-93));
+    assert_eq!(r.to_continued_fraction(), cf(&[-5, 1, 1, 6, 7]));
+    assert_eq!(Q::from_continued_fraction(&r.to_continued_fraction()), Some(r));
+  }
+
+  #[test]
+  fn best_approximation() {
+    type Z = Integer;
+    type Q = Rational;
+
+    // ```pi ~ 3.14159265358979```
+    let pi = Q::new(Z::from(314159265358979_i64), Z::from(100000000000000_i64));
+    assert_eq!(pi.limit_denominator(Natural::from(10u64)), Q::new(Z::from(22), Z::from(7)));
+    assert_eq!(pi.limit_denominator(Natural::from(100u64)), Q::new(Z::from(311), Z::from(99)));
+    assert_eq!(pi.limit_denominator(Natural::from(1000u64)), Q::new(Z::from(355), Z::from(113)));
+    assert_eq!((-pi).limit_denominator(Natural::from(1000u64)), Q::new(Z::from(-355), Z::from(113)));
+
+    // semiconvergent ```0.8 ~ 3/4, 4/5```
+    assert_eq!(Q::new(Z::from(4), Z::from(5)).limit_denominator(Natural::from(4u64)), Q::new(Z::from(3), Z::from(4)));
+    assert_eq!(Q::new(Z::from(1), Z::from(3)).limit_denominator(Natural::from(3u64)), Q::new(Z::from(1), Z::from(3)));
+  }
+
+  #[test]
+  fn quadratic_irrationals() {
+    type Z = Integer;
+    type Q = Rational;
+    let cf = |terms: &[i64]| terms.iter().map(|&a| Z::from(a)).collect::<Vec<_>>();
+    let sqrt = |n: u64| Q::quadratic_continued_fraction(Z::from(0), Natural::from(n), Z::from(1));
+
+    // ```sqrt(2) = [1; (2)]```
+    assert_eq!(sqrt(2), Some((cf(&[1]), cf(&[2]))));
+    // ```sqrt(7) = [2; (1, 1, 1, 4)]```
+    assert_eq!(sqrt(7), Some((cf(&[2]), cf(&[1, 1, 1, 4]))));
+    // ```sqrt(16) = 4```
+    assert_eq!(sqrt(16), Some((cf(&[4]), cf(&[]))));
+    // ```(1 + sqrt(5))/2 = [(1)]```
+    assert_eq!(Q::quadratic_continued_fraction(Z::from(1), Natural::from(5u64), Z::from(2)), Some((cf(&[]), cf(&[1]))));
+    // ```-sqrt(3)/2 = [-1; 7, (2, 6)]```
+    assert_eq!(Q::quadratic_continued_fraction(Z::from(0), Natural::from(3u64), Z::from(-2)), Some((cf(&[-1, 7]), cf(&[2, 6]))));
+    assert_eq!(Q::quadratic_continued_fraction(Z::from(0), Natural::from(3u64), Z::from(0)), None);
+  }
+
+  #[test]
+  fn parse() {
+    type Z = Integer;
+    type Q = Rational;
+    let q = |n: i64, d: i64| Q::new(Z::from(n), Z::from(d));
+
+    assert_eq!(Q::from_str("6/-4"), Ok(q(-3, 2)));
+    assert_eq!(Q::from_str("0x10/0b11"), Ok(q(16, 3)));
+    assert_eq!(Q::from_str("-1.25"), Ok(q(-5, 4)));
+    assert_eq!(Q::from_str("1e-3"), Ok(q(1, 1000)));
+    assert_eq!(Q::from_str("2.5E+2"), Ok(q(250, 1)));
+    assert_eq!(Q::from_str(".5"), Ok(q(1, 2)));
+    assert_eq!(Q::from_str("3."), Ok(q(3, 1)));
+    assert_eq!(Q::from_str("1_000.000_1"), Ok(q(10000001, 10000)));
+    assert_eq!(Q::from_str("-0x1e5"), Ok(q(-485, 1)));
+
+    assert_eq!(Q::from_str(""), Err(ParseError::Empty));
+    assert_eq!(Q::from_str("-"), Err(ParseError::Empty));
+    assert_eq!(Q::from_str("1/"), Err(ParseError::Empty));
+    assert_eq!(Q::from_str("1/0"), Err(ParseError::ZeroDenominator));
+    assert_eq!(Q::from_str("1/2/3"), Err(ParseError::InvalidDigit(3)));
+    assert_eq!(Q::from_str("1.2.3"), Err(ParseError::InvalidDigit(3)));
+    assert_eq!(Q::from_str("."), Err(ParseError::InvalidDigit(0)));
+    assert_eq!(Q::from_str("1e"), Err(ParseError::InvalidDigit(1)));
+    assert_eq!(Q::from_str("1e5x"), Err(ParseError::InvalidDigit(3)));
+    assert_eq!(Q::from_str("1e99999999999999999999"), Err(ParseError::Overflow));
+    assert_eq!(Q::from_str("1e-2000000"), Err(ParseError::Overflow));
+  }
+
+  #[test]
+  fn floats() {
+    type Q = Rational;
+
+    for x in [0.0, 1.0, -0.1, 1e300, -2.5e-310, f64::MAX, f64::MIN_POSITIVE, 5e-324, f64::EPSILON] {
+      let q = Q::try_from(x).expect("finite");
+      assert_eq!(q.to_f64(), x);
+    }
+    assert_eq!(Q::try_from(f64::NAN), Err(()));
+    assert_eq!(Q::try_from(0.5), Ok(Q::new(Integer::ONE, Integer::TWO)));
+
+    // decimal parsing of `f64` is correctly rounded
+    for src in [
+      "0.1",
+      "-1e23",
+      "9007199254740993",
+      "2.2250738585072011e-308",
+      "4.9406564584124654e-324",
+      "2.4703282292062328e-324",
+      "2.4703282292062327e-324",
+      "1.7976931348623158e308",
+      "1.8e308",
+      "1e-400",
+    ] {
+      let x: f64 = src.parse().expect("a float");
+      assert_eq!(Q::from_str(src).expect("a decimal").to_f64(), x, "{src}");
+    }
+  }
+
+  #[test]
+  fn rounding() {
+    type Z = Integer;
+    type Q = Rational;
+    let q = |n: i64, d: i64| Q::new(Z::from(n), Z::from(d));
+
+    // ```(x, ⌊x⌋, ⌈x⌉, trunc(x), round(x))```
+    for (x, f, c, t, r) in [
+      (q(7, 2), 3, 4, 3, 4),
+      (q(-7, 2), -4, -3, -3, -4),
+      (q(5, 2), 2, 3, 2, 2),
+      (q(-5, 2), -3, -2, -2, -2),
+      (q(8, -3), -3, -2, -2, -3),
+      (q(4, 2), 2, 2, 2, 2),
+    ] {
+      assert_eq!((x.floor(), x.ceil(), x.trunc(), x.round()), (Z::from(f), Z::from(c), Z::from(t), Z::from(r)));
+      assert_eq!(x.fract(), (x.clone() - Q::from(Z::from(f))).reduce());
+    }
+    assert_eq!(q(-7, 2).fract(), q(1, 2));
   }
 }
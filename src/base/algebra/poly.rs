@@ -1,6 +1,11 @@
 use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
 
-use crate::Tree;
+use super::{FiniteField, Gf};
+use crate::base::alg::{AOp, Algebra, Assoc, BOp, UOp};
+use crate::base::budget;
+use crate::{Expr, Integer, Natural, Number, Random, Rational, Symbol, Tree};
 
 // Orderings
 pub fn order_expr<'t, L, R>(
@@ -32,3 +37,1250 @@ where
     }
   }
 }
+
+/// A dense univariate polynomial `c_0 + c_1*x + ... + c_n*x^n` over ℚ.
+///
+/// Coefficients are stored reduced from the constant term up, without trailing zeros (the zero polynomial has
+/// no coefficient), so that structural equality coincides with polynomial equality.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Poly {
+  coef: Vec<Rational>,
+}
+
+impl Poly {
+  /// Create a new [`Poly`] from its coefficients `[c_0, c_1, ..., c_n]`.
+  pub fn new(coef: Vec<Rational>) -> Poly {
+    let mut coef: Vec<_> = coef.into_iter().map(Rational::reduce).collect();
+    while coef.last().is_some_and(|c| c.num == Integer::ZERO) {
+      coef.pop();
+    }
+    Poly { coef }
+  }
+
+  /// Create a new [`Poly`] from its integer coefficients `[c_0, c_1, ..., c_n]`.
+  pub fn from_integers(coef: Vec<Integer>) -> Poly {
+    Poly::new(coef.into_iter().map(Rational::from).collect())
+  }
+
+  /// The zero polynomial.
+  pub fn zero() -> Poly {
+    Poly { coef: vec![] }
+  }
+
+  /// The constant polynomial `c`.
+  pub fn constant(c: Rational) -> Poly {
+    Poly::new(vec![c])
+  }
+
+  /// The monomial `c*x^k`.
+  pub fn monomial(c: Rational, k: usize) -> Poly {
+    let mut coef = vec![q_zero(); k];
+    coef.push(c);
+    Poly::new(coef)
+  }
+
+  /// Return the coefficients `[c_0, c_1, ..., c_n]`.
+  pub fn coef(&self) -> &[Rational] {
+    &self.coef
+  }
+
+  /// Return the degree, or `None` for the zero polynomial.
+  pub fn deg(&self) -> Option<usize> {
+    self.coef.len().checked_sub(1)
+  }
+
+  /// Return `true` if `self` is the zero polynomial.
+  pub fn is_zero(&self) -> bool {
+    self.coef.is_empty()
+  }
+
+  /// Return the leading coefficient (`0` for the zero polynomial).
+  pub fn lead(&self) -> Rational {
+    self.coef.last().cloned().unwrap_or_else(q_zero)
+  }
+
+  /// Evaluate at `x` (Horner scheme).
+  pub fn eval(&self, x: &Rational) -> Rational {
+    self.coef.iter().rev().fold(q_zero(), |acc, c| (acc * x.clone() + c.clone()).reduce())
+  }
+
+  /// Compute the formal derivative.
+  pub fn derivative(&self) -> Poly {
+    Poly::new(self.coef.iter().enumerate().skip(1).map(|(k, c)| c.clone() * Rational::from(Integer::from(k as u64))).collect())
+  }
+
+  /// Divide by the leading coefficient.
+  pub fn monic(self) -> Poly {
+    if self.is_zero() {
+      return self;
+    }
+
+    let lead = self.lead();
+    Poly::new(self.coef.into_iter().map(|c| c / lead.clone()).collect())
+  }
+
+  /// Multiply by the constant `c`.
+  pub fn scale(self, c: &Rational) -> Poly {
+    Poly::new(self.coef.into_iter().map(|a| a * c.clone()).collect())
+  }
+
+  /// Compute the composition `self(q(x))`.
+  pub fn compose(&self, q: &Poly) -> Poly {
+    self.coef.iter().rev().fold(Poly::zero(), |acc, c| acc * q.clone() + Poly::constant(c.clone()))
+  }
+
+  /// Compute the quotient and remainder of the Euclidean division by `d`, or `None` if `d` is zero.
+  pub fn div_rem(&self, d: &Poly) -> Option<(Poly, Poly)> {
+    let dd = d.deg()?;
+    let dl = d.lead();
+
+    let mut q = vec![q_zero(); self.coef.len().saturating_sub(dd)];
+    let mut r = self.clone();
+    while let Some(rd) = r.deg().filter(|&rd| rd >= dd) {
+      // ```r = r - c*x^k*d```
+      let c = (r.lead() / dl.clone()).reduce();
+      r = r - d.shift(rd - dd).scale(&c);
+      q[rd - dd] = c;
+    }
+
+    Some((Poly::new(q), r))
+  }
+
+  /// Compute the monic Greatest Common Divisor (GCD) of two polynomials `u` and `v`.
+  pub fn gcd(u: &Poly, v: &Poly) -> Poly {
+    // primitive remainder sequence over ℤ, avoiding the coefficient growth of the rational one
+    let (mut u, mut v) = (u.to_integers(), v.to_integers());
+    while !v.is_empty() {
+      let r = z_primitive(z_prem(&u, &v));
+      (u, v) = (v, r);
+    }
+    Poly::from_integers(u).monic()
+  }
+
+  /// Compute the square-free decomposition `[(s_1, 1), (s_2, 2), ...]` with monic `s_k` (Yun).
+  pub fn squarefree(&self) -> Vec<(Poly, usize)> {
+    if self.deg().unwrap_or(0) == 0 {
+      return vec![];
+    }
+
+    let quo = |a: &Poly, b: &Poly| a.div_rem(b).map_or_else(Poly::zero, |(q, _)| q);
+    let d = self.derivative();
+    let a = Poly::gcd(self, &d);
+    let (mut b, c) = (quo(self, &a), quo(&d, &a));
+    let mut d = c - b.derivative();
+
+    let mut out = vec![];
+    let mut k = 1;
+    while b.deg().is_some_and(|n| n > 0) {
+      let a = Poly::gcd(&b, &d);
+      (b, d) = (quo(&b, &a), quo(&d, &a));
+      d = d - b.derivative();
+      if a.deg().is_some_and(|n| n > 0) {
+        out.push((a, k));
+      }
+      k += 1;
+    }
+
+    out
+  }
+
+  /// Compute the Sturm sequence `p, p', -rem(p, p'), ...`.
+  pub fn sturm(&self) -> Vec<Poly> {
+    let mut seq = vec![self.clone(), self.derivative()];
+    while let Some((_, r)) = seq[seq.len() - 2].div_rem(&seq[seq.len() - 1]) {
+      if r.is_zero() {
+        break;
+      }
+      seq.push(-r);
+    }
+    seq.retain(|p| !p.is_zero());
+    seq
+  }
+
+  /// Count the distinct real roots in the interval `(lo, hi]`.
+  pub fn count_roots(&self, lo: &Rational, hi: &Rational) -> usize {
+    Poly::sturm_count(&self.sturm(), lo, hi)
+  }
+
+  /// Isolate the distinct real roots into disjoint intervals `(lo, hi]`, in increasing order.
+  pub fn real_roots(&self) -> Vec<(Rational, Rational)> {
+    let Some((s, _)) = self.div_rem(&Poly::gcd(self, &self.derivative())) else {
+      return vec![];
+    };
+    if s.deg().unwrap_or(0) == 0 {
+      return vec![];
+    }
+
+    let seq = s.sturm();
+    let b = s.root_bound();
+    let mut out = vec![];
+    let mut stack = vec![(-b.clone(), b)];
+    while let Some((lo, hi)) = stack.pop() {
+      match Poly::sturm_count(&seq, &lo, &hi) {
+        0 => {}
+        1 => out.push((lo, hi)),
+        _ => {
+          // bisect, the upper half being visited last
+          let mid = q_mid(&lo, &hi);
+          stack.push((mid.clone(), hi));
+          stack.push((lo, mid));
+        }
+      }
+    }
+
+    out
+  }
+
+  /// Return a bound `b` such that every complex root `z` satisfies `|z| < b` (Cauchy).
+  pub fn root_bound(&self) -> Rational {
+    let lead = self.lead();
+    let max = self.coef.iter().map(|c| q_abs((c.clone() / lead.clone()).reduce())).max().unwrap_or_else(q_zero);
+    (max + q_one()).reduce()
+  }
+
+  /// Factor into irreducible polynomials over ℚ, as `(lead, [(monic factor, multiplicity)])`.
+  ///
+  /// Square-free parts are factored over ℤ with the Berlekamp-Zassenhaus method: modular factorization
+  /// (distinct and equal degree), Hensel lifting and recombination of the lifted factors.
+  pub fn factor(&self) -> (Rational, Vec<(Poly, usize)>) {
+    let mut out = vec![];
+    for (s, k) in self.squarefree() {
+      for f in zassenhaus(s.to_integers()) {
+        out.push((Poly::from_integers(f).monic(), k));
+      }
+    }
+
+    out.sort_by(|(a, i), (b, j)| a.deg().cmp(&b.deg()).then(a.cmp(b)).then(i.cmp(j)));
+    (self.lead(), out)
+  }
+
+  /// Convert a polynomial expression in `x` with rational coefficients, or `None` if it is not one.
+  pub fn from_tree(expr: &Tree, x: &Tree) -> Option<Poly> {
+    if expr == x {
+      return Some(Poly::monomial(q_one(), 1));
+    }
+
+    match expr {
+      Tree::Num(Number::Int(z)) => Some(Poly::constant(Rational::from(z.clone()))),
+      Tree::Num(Number::Rat(q)) => Some(Poly::constant(q.clone())),
+
+      Tree::Alg(Algebra::UExpr { map: UOp::Id, arg }) => Poly::from_tree(arg, x),
+      Tree::Alg(Algebra::AssocExpr(Assoc { map, arg })) => {
+        let mut acc = match map {
+          AOp::Add => Poly::zero(),
+          AOp::Mul => Poly::constant(q_one()),
+        };
+        for e in arg {
+          let e = Poly::from_tree(e, x)?;
+          acc = match map {
+            AOp::Add => acc + e,
+            AOp::Mul => acc * e,
+          };
+        }
+        Some(acc)
+      }
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, e) }) => {
+        let Tree::Num(Number::Int(n)) = e.as_ref() else {
+          return None;
+        };
+        let n = u64::try_from(Natural::try_from(n.clone()).ok()?).ok()?;
+        let b = Poly::from_tree(b, x)?;
+        (0..n).try_fold(Poly::constant(q_one()), |acc, _| budget::step().ok().map(|()| acc * b.clone()))
+      }
+
+      _ => None,
+    }
+  }
+
+  /// Convert into an expression in `x`.
+  pub fn to_tree(&self, x: &Tree) -> Tree {
+    let tree = self
+      .coef
+      .iter()
+      .enumerate()
+      .filter(|(_, c)| c.num != Integer::ZERO)
+      .map(|(k, c)| Tree::from(c.clone()).mul(x.clone().pow(Tree::from(k as u64))))
+      .fold(Tree::from(0), |acc, e| acc.add(e));
+    tree.clone().trivial().unwrap_or(tree)
+  }
+
+  // Helpers
+  pub(crate) fn sturm_count(seq: &[Poly], lo: &Rational, hi: &Rational) -> usize {
+    // ```#{x ∈ (lo, hi], p(x) = 0} = V(lo) - V(hi)```
+    let var = |x: &Rational| {
+      let sgn: Vec<_> = seq.iter().map(|p| p.eval(x).num.ord()).filter(|s| s.is_ne()).collect();
+      sgn.windows(2).filter(|w| w[0] != w[1]).count()
+    };
+    var(lo).saturating_sub(var(hi))
+  }
+
+  fn shift(&self, k: usize) -> Poly {
+    let mut coef = vec![q_zero(); k];
+    coef.extend(self.coef.iter().cloned());
+    Poly { coef }
+  }
+
+  // Primitive integer polynomial with a positive leading coefficient.
+  fn to_integers(&self) -> Vec<Integer> {
+    let l = self.coef.iter().fold(Integer::ONE, |acc, c| Integer::lcm(acc, c.den.clone()));
+    let coef: Vec<_> = self.coef.iter().map(|c| c.num.clone() * (l.clone() / c.den.clone())).collect();
+    z_primitive(coef)
+  }
+}
+
+impl Add for Poly {
+  type Output = Poly;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    let (mut long, short) = if self.coef.len() >= rhs.coef.len() { (self.coef, rhs.coef) } else { (rhs.coef, self.coef) };
+    for (a, b) in long.iter_mut().zip(short) {
+      *a = a.clone() + b;
+    }
+    Poly::new(long)
+  }
+}
+
+impl Sub for Poly {
+  type Output = Poly;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    self + -rhs
+  }
+}
+
+impl Mul for Poly {
+  type Output = Poly;
+
+  fn mul(self, rhs: Self) -> Self::Output {
+    if self.is_zero() || rhs.is_zero() {
+      return Poly::zero();
+    }
+
+    let mut coef = vec![q_zero(); self.coef.len() + rhs.coef.len() - 1];
+    for (i, a) in self.coef.iter().enumerate() {
+      for (j, b) in rhs.coef.iter().enumerate() {
+        coef[i + j] = (coef[i + j].clone() + a.clone() * b.clone()).reduce();
+      }
+    }
+    Poly::new(coef)
+  }
+}
+
+impl Neg for Poly {
+  type Output = Poly;
+
+  fn neg(self) -> Self::Output {
+    Poly {
+      coef: self.coef.into_iter().map(Rational::neg).collect(),
+    }
+  }
+}
+
+impl fmt::Display for Poly {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let x = Symbol::new("x", Number::R).map_or(Tree::Form, Tree::Sym);
+    write!(f, "{}", self.to_tree(&x))
+  }
+}
+
+/// A dense univariate polynomial `c_0 + c_1*x + ... + c_n*x^n` over a [`FiniteField`].
+///
+/// Coefficients are stored from the constant term up, without trailing zeros.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GfPoly {
+  field: FiniteField,
+  coef: Vec<Gf>,
+}
+
+impl GfPoly {
+  /// Create a new [`GfPoly`] from its coefficients `[c_0, c_1, ..., c_n]`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a coefficient does not belong to `field`.
+  pub fn new(field: &FiniteField, mut coef: Vec<Gf>) -> GfPoly {
+    assert!(coef.iter().all(|c| c.field() == field), "elements of different fields");
+    while coef.last().is_some_and(Gf::is_zero) {
+      coef.pop();
+    }
+    GfPoly { field: field.clone(), coef }
+  }
+
+  /// Create a new [`GfPoly`] from the images of integer coefficients `[c_0, c_1, ..., c_n]`.
+  pub fn from_integers(field: &FiniteField, coef: &[Integer]) -> GfPoly {
+    GfPoly::new(field, coef.iter().map(|c| field.from_integer(c)).collect())
+  }
+
+  /// Reduce a rational polynomial, or `None` if a denominator is divisible by the characteristic.
+  pub fn from_poly(field: &FiniteField, p: &Poly) -> Option<GfPoly> {
+    let coef: Option<Vec<_>> = p.coef.iter().map(|c| field.from_rational(c)).collect();
+    Some(GfPoly::new(field, coef?))
+  }
+
+  /// The zero polynomial.
+  pub fn zero(field: &FiniteField) -> GfPoly {
+    GfPoly { field: field.clone(), coef: vec![] }
+  }
+
+  /// The monomial `c*x^k`.
+  pub fn monomial(c: Gf, k: usize) -> GfPoly {
+    let field = c.field().clone();
+    let mut coef = vec![field.zero(); k];
+    coef.push(c);
+    GfPoly::new(&field, coef)
+  }
+
+  /// Return the field of the coefficients.
+  pub fn field(&self) -> &FiniteField {
+    &self.field
+  }
+
+  /// Return the coefficients `[c_0, c_1, ..., c_n]`.
+  pub fn coef(&self) -> &[Gf] {
+    &self.coef
+  }
+
+  /// Return the degree, or `None` for the zero polynomial.
+  pub fn deg(&self) -> Option<usize> {
+    self.coef.len().checked_sub(1)
+  }
+
+  /// Return `true` if `self` is the zero polynomial.
+  pub fn is_zero(&self) -> bool {
+    self.coef.is_empty()
+  }
+
+  /// Return the leading coefficient (`0` for the zero polynomial).
+  pub fn lead(&self) -> Gf {
+    self.coef.last().cloned().unwrap_or_else(|| self.field.zero())
+  }
+
+  /// Evaluate at `x` (Horner scheme).
+  pub fn eval(&self, x: &Gf) -> Gf {
+    self.coef.iter().rev().fold(self.field.zero(), |acc, c| acc * x.clone() + c.clone())
+  }
+
+  /// Compute the formal derivative.
+  pub fn derivative(&self) -> GfPoly {
+    let coef = self.coef.iter().enumerate().skip(1).map(|(k, c)| self.field.from_integer(&Integer::from(k as u64)) * c.clone());
+    GfPoly::new(&self.field, coef.collect())
+  }
+
+  /// Divide by the leading coefficient.
+  pub fn monic(self) -> GfPoly {
+    match self.lead().inv() {
+      Some(inv) => self.scale(&inv),
+      None => self,
+    }
+  }
+
+  /// Multiply by the constant `c`.
+  pub fn scale(self, c: &Gf) -> GfPoly {
+    GfPoly::new(&self.field, self.coef.into_iter().map(|a| a * c.clone()).collect())
+  }
+
+  /// Compute the quotient and remainder of the Euclidean division by `d`, or `None` if `d` is zero.
+  pub fn div_rem(&self, d: &GfPoly) -> Option<(GfPoly, GfPoly)> {
+    let dd = d.deg()?;
+    let inv = d.lead().inv()?;
+
+    let mut q = vec![self.field.zero(); self.coef.len().saturating_sub(dd)];
+    let mut r = self.clone();
+    while let Some(rd) = r.deg().filter(|&rd| rd >= dd) {
+      // ```r = r - c*x^k*d```
+      let c = r.lead() * inv.clone();
+      r = r - GfPoly::monomial(c.clone(), rd - dd) * d.clone();
+      q[rd - dd] = c;
+    }
+
+    Some((GfPoly::new(&self.field, q), r))
+  }
+
+  /// Compute the monic Greatest Common Divisor (GCD) of two polynomials `u` and `v`.
+  pub fn gcd(u: &GfPoly, v: &GfPoly) -> GfPoly {
+    let (mut u, mut v) = (u.clone(), v.clone());
+    while let Some((_, r)) = u.div_rem(&v) {
+      (u, v) = (v, r);
+    }
+    u.monic()
+  }
+
+  /// Compute `self^e (mod m)`.
+  pub fn pow_mod(&self, e: &Natural, m: &GfPoly) -> GfPoly {
+    let rem = |a: GfPoly| a.div_rem(m).map_or_else(|| GfPoly::zero(&self.field), |(_, r)| r);
+    let b = rem(self.clone());
+    let mut r = rem(GfPoly::monomial(self.field.one(), 0));
+    for i in (0..e.bits()).rev() {
+      r = rem(r.clone() * r);
+      if e.bit(i) {
+        r = rem(r * b.clone());
+      }
+    }
+    r
+  }
+
+  /// Compute the square-free decomposition `[(s_1, k_1), (s_2, k_2), ...]` of a nonzero polynomial, with monic
+  /// `s_i` and distinct multiplicities `k_i`.
+  pub fn squarefree(&self) -> Vec<(GfPoly, usize)> {
+    let mut out = vec![];
+    if self.deg().unwrap_or(0) == 0 {
+      return out;
+    }
+
+    let f = self.clone().monic();
+    let d = f.derivative();
+    let mut c = if d.is_zero() { f.clone() } else { GfPoly::gcd(&f, &d) };
+    if !d.is_zero() {
+      // factors of multiplicity prime to p
+      let mut w = f.quo(&c);
+      let mut k = 1;
+      while w.deg().is_some_and(|n| n > 0) {
+        let y = GfPoly::gcd(&w, &c);
+        let z = w.quo(&y);
+        if z.deg().is_some_and(|n| n > 0) {
+          out.push((z, k));
+        }
+        (w, c, k) = (y.clone(), c.quo(&y), k + 1);
+      }
+    }
+
+    // ```c(x) = g(x^p) = g^(1/p)(x)^p```
+    if c.deg().is_some_and(|n| n > 0) {
+      let p = usize::try_from(self.field.char().clone()).expect("a p-th power has degree at least p");
+      for (g, k) in c.pth_root().squarefree() {
+        out.push((g, k * p));
+      }
+    }
+
+    out.sort_by(|(a, i), (b, j)| i.cmp(j).then(a.cmp(b)));
+    out
+  }
+
+  /// Test irreducibility (Rabin).
+  pub fn is_irreducible(&self) -> bool {
+    let Some(n) = self.deg().filter(|&n| n > 0) else {
+      return false;
+    };
+
+    // ```x^(q^n) = x (mod f)``` and ```gcd(x^(q^(n/r)) - x, f) = 1``` for every prime ```r | n```
+    let f = self.clone().monic();
+    let x = GfPoly::monomial(self.field.one(), 1);
+    let frobenius = |k: usize| (0..k).fold(x.clone(), |acc, _| acc.pow_mod(&self.field.order(), &f));
+    let divisors = Natural::from(n as u64).factor();
+    frobenius(n) == x.clone().div_rem(&f).map_or_else(|| x.clone(), |(_, r)| r)
+      && divisors.into_iter().all(|(r, _)| {
+        let r = usize::try_from(r).expect("a divisor of the degree");
+        GfPoly::gcd(&(frobenius(n / r) - x.clone()), &f).deg() == Some(0)
+      })
+  }
+
+  /// Test if the polynomial is irreducible and its roots generate the multiplicative group of `GF(q^n)`.
+  pub fn is_primitive(&self) -> bool {
+    if !self.is_irreducible() || self.coef[0].is_zero() {
+      return false;
+    }
+
+    // ```x^((q^n - 1)/r) != 1 (mod f)``` for every prime ```r | q^n - 1```
+    let n = self.deg().unwrap_or(0) as u64;
+    let qn1 = self.field.order().pow(n) - Natural::ONE;
+    let x = GfPoly::monomial(self.field.one(), 1);
+    let one = GfPoly::monomial(self.field.one(), 0);
+    qn1.clone().factor().into_iter().all(|(r, _)| x.pow_mod(&(qn1.clone() / r), self) != one)
+  }
+
+  /// Return the first monic irreducible polynomial of degree `n > 0`, in the order of the coefficients
+  /// `[c_0, ..., c_n-1]` read as base `q` digits (see [`FiniteField::element_at`]).
+  pub fn irreducible(field: &FiniteField, n: usize) -> GfPoly {
+    GfPoly::monic_search(field, n, GfPoly::is_irreducible)
+  }
+
+  /// Return the first monic primitive polynomial of degree `n > 0`, in the order of [`GfPoly::irreducible`].
+  pub fn primitive(field: &FiniteField, n: usize) -> GfPoly {
+    GfPoly::monic_search(field, n, GfPoly::is_primitive)
+  }
+
+  /// Factor into irreducible polynomials, as `(lead, [(monic factor, multiplicity)])`.
+  ///
+  /// Square-free parts are split by distinct degree, then by equal degree with the randomized Cantor-Zassenhaus
+  /// method (seeded, so that the result is reproducible).
+  pub fn factor(&self) -> (Gf, Vec<(GfPoly, usize)>) {
+    let mut rng = Random::new(0);
+    let mut out = vec![];
+    for (s, k) in self.squarefree() {
+      for (g, d) in s.distinct_degree() {
+        for h in g.equal_degree(d, &mut rng) {
+          out.push((h, k));
+        }
+      }
+    }
+
+    out.sort_by(|(a, i), (b, j)| a.deg().cmp(&b.deg()).then(a.cmp(b)).then(i.cmp(j)));
+    (self.lead(), out)
+  }
+
+  // Helpers
+  fn quo(&self, d: &GfPoly) -> GfPoly {
+    self.div_rem(d).map_or_else(|| GfPoly::zero(&self.field), |(q, _)| q)
+  }
+
+  // ```g``` such that ```g^p = self```, for a polynomial in ```x^p```.
+  fn pth_root(&self) -> GfPoly {
+    // ```a^(1/p) = a^(p^(n - 1))``` in ```GF(p^n)```
+    let p = self.field.char();
+    let e = p.clone().pow(self.field.degree() as u64 - 1);
+    let step = usize::try_from(p.clone()).expect("a p-th power has degree at least p");
+    GfPoly::new(&self.field, self.coef.iter().step_by(step).map(|c| c.pow(&e)).collect())
+  }
+
+  fn monic_search<F: Fn(&GfPoly) -> bool>(field: &FiniteField, n: usize, accept: F) -> GfPoly {
+    let q = field.order();
+    let mut i = Natural::ZERO;
+    loop {
+      let (mut coef, mut j) = (vec![], i.clone());
+      for _ in 0..n {
+        let (quo, rem) = j.div_rem(q.clone());
+        coef.push(field.element_at(rem));
+        j = quo;
+      }
+      coef.push(field.one());
+
+      let f = GfPoly::new(field, coef);
+      if accept(&f) {
+        return f;
+      }
+      i.incr();
+    }
+  }
+
+  // Split a monic square-free polynomial into ```[(g_d, d)]```, ```g_d``` the product of its factors of degree ```d```.
+  fn distinct_degree(&self) -> Vec<(GfPoly, usize)> {
+    let x = GfPoly::monomial(self.field.one(), 1);
+    let mut out = vec![];
+    let mut f = self.clone();
+    let mut h = x.clone();
+    let mut d = 1;
+    while f.deg().is_some_and(|n| n >= 2 * d) {
+      // ```gcd(x^(q^d) - x, f)``` gathers the factors of degree ```d```
+      h = h.pow_mod(&self.field.order(), &f);
+      let g = GfPoly::gcd(&f, &(h.clone() - x.clone()));
+      if g.deg().is_some_and(|n| n > 0) {
+        f = f.quo(&g);
+        h = h.div_rem(&f).map_or(h, |(_, r)| r);
+        out.push((g, d));
+      }
+      d += 1;
+    }
+
+    if let Some(n) = f.deg().filter(|&n| n > 0) {
+      out.push((f, n));
+    }
+    out
+  }
+
+  // Split a monic square-free product of irreducible factors of degree `d` (Cantor-Zassenhaus).
+  fn equal_degree(self, d: usize, rng: &mut Random) -> Vec<GfPoly> {
+    let n = self.deg().unwrap_or(0);
+    if n <= d {
+      return vec![self];
+    }
+
+    let q = self.field.order();
+    loop {
+      let a = GfPoly::new(&self.field, (0..n).map(|_| self.field.random(rng)).collect());
+      let b = if self.field.char() == &Natural::TWO {
+        // ```a + a^2 + a^4 + ... + a^(2^(k*d - 1))```, ```q = 2^k```
+        let mut t = a.clone();
+        let mut acc = a;
+        for _ in 1..self.field.degree() * d {
+          t = t.pow_mod(&Natural::TWO, &self);
+          acc = acc + t.clone();
+        }
+        acc
+      } else {
+        // ```a^((q^d - 1)/2) - 1```
+        a.pow_mod(&((q.clone().pow(d as u64) - Natural::ONE) >> 1), &self) - GfPoly::monomial(self.field.one(), 0)
+      };
+
+      let g = GfPoly::gcd(&self, &b);
+      if g.deg().is_some_and(|k| k > 0 && k < n) {
+        let h = self.quo(&g);
+        let mut out = g.equal_degree(d, rng);
+        out.extend(h.equal_degree(d, rng));
+        return out;
+      }
+    }
+  }
+}
+
+impl Add for GfPoly {
+  type Output = GfPoly;
+
+  fn add(self, rhs: Self) -> Self::Output {
+    let (mut long, short) = if self.coef.len() >= rhs.coef.len() { (self.coef, rhs.coef) } else { (rhs.coef, self.coef) };
+    for (a, b) in long.iter_mut().zip(short) {
+      *a = a.clone() + b;
+    }
+    GfPoly::new(&self.field, long)
+  }
+}
+
+impl Sub for GfPoly {
+  type Output = GfPoly;
+
+  fn sub(self, rhs: Self) -> Self::Output {
+    self + -rhs
+  }
+}
+
+impl Mul for GfPoly {
+  type Output = GfPoly;
+
+  fn mul(self, rhs: Self) -> Self::Output {
+    if self.is_zero() || rhs.is_zero() {
+      return GfPoly::zero(&self.field);
+    }
+
+    let mut coef = vec![self.field.zero(); self.coef.len() + rhs.coef.len() - 1];
+    for (i, a) in self.coef.iter().enumerate() {
+      for (j, b) in rhs.coef.iter().enumerate() {
+        coef[i + j] = coef[i + j].clone() + a.clone() * b.clone();
+      }
+    }
+    GfPoly::new(&self.field, coef)
+  }
+}
+
+impl Neg for GfPoly {
+  type Output = GfPoly;
+
+  fn neg(self) -> Self::Output {
+    GfPoly {
+      field: self.field,
+      coef: self.coef.into_iter().map(Gf::neg).collect(),
+    }
+  }
+}
+
+impl fmt::Display for GfPoly {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.is_zero() {
+      return write!(f, "0");
+    }
+
+    // coefficients of extension fields are written in `a` to tell them from the variable
+    let terms: Vec<_> = (self.coef.iter().enumerate().rev())
+      .filter(|(_, c)| !c.is_zero())
+      .map(|(k, c)| {
+        let c = match self.field.degree() {
+          1 => c.to_string(),
+          _ => format!("({})", c.to_string().replace('x', "a")),
+        };
+        match (k, c.as_str()) {
+          (0, _) => c,
+          (1, "1") => "x".to_string(),
+          (1, _) => format!("{c}*x"),
+          (k, "1") => format!("x^{k}"),
+          (k, _) => format!("{c}*x^{k}"),
+        }
+      })
+      .collect();
+    write!(f, "{}", terms.join(" + "))
+  }
+}
+
+fn q_zero() -> Rational {
+  Rational::from(Integer::ZERO)
+}
+
+fn q_one() -> Rational {
+  Rational::from(Integer::ONE)
+}
+
+fn q_abs(q: Rational) -> Rational {
+  if q.is_negative() {
+    -q
+  } else {
+    q
+  }
+}
+
+fn q_mid(lo: &Rational, hi: &Rational) -> Rational {
+  ((lo.clone() + hi.clone()) / Rational::from(Integer::TWO)).reduce()
+}
+
+// Berlekamp-Zassenhaus
+// Polynomials over ℤ and 𝔽_p are dense coefficient vectors, from the constant term up.
+
+/// Factor a primitive square-free polynomial over ℤ with a positive leading coefficient.
+fn zassenhaus(f: Vec<Integer>) -> Vec<Vec<Integer>> {
+  let n = f.len() - 1;
+  if n <= 1 {
+    return vec![f];
+  }
+
+  // A prime keeping f square-free with the fewest modular factors.
+  let lc = f[n].clone();
+  let Some((p, fac)) = (3u64..)
+    .filter(|&p| (2..).take_while(|d| d * d <= p).all(|d| p % d != 0))
+    .filter(|&p| fp_from(&lc, p) != 0)
+    .filter_map(|p| {
+      let fp = fp_monic(f.iter().map(|c| fp_from(c, p)).collect(), p);
+      (fp_deg(&fp_gcd(&fp, &fp_derivative(&fp, p), p)) == 0).then(|| (p, fp_factor(fp, p)))
+    })
+    .take(3)
+    .min_by_key(|(_, fac)| fac.len())
+  else {
+    return vec![f];
+  };
+  if fac.len() == 1 {
+    return vec![f];
+  }
+
+  // ```|g_i| <= (n + 1)*2^n*|f|*lc``` for any factor g of f (Mignotte)
+  let max = f.iter().map(|c| c.clone().abs()).max().unwrap_or(Natural::ZERO);
+  let bound = Integer::from(max) * Integer::from(n as u64 + 1) * Integer::TWO.pow(n as u64) * lc.clone();
+  let (mut k, mut m) = (1, Integer::from(p));
+  while m <= bound.clone() * Integer::TWO {
+    (k, m) = (k + 1, m * Integer::from(p));
+  }
+
+  // Lift ```f = lc*g_1*...*g_r (mod p)``` to ```f = lc*g_1*...*g_r (mod p^k)```, one factor at a time.
+  let mut lifted = vec![];
+  let mut cur = f.clone();
+  for i in 0..fac.len() - 1 {
+    let rest = fac[i + 1..].iter().fold(vec![fp_from(&lc, p)], |acc, g| fp_mul(&acc, g, p));
+    let (g, h) = hensel(&cur, fac[i].clone(), rest, p, k);
+    lifted.push(g);
+    cur = h;
+  }
+  let inv = z_inv_mod(lc.clone(), m.clone());
+  lifted.push(cur.into_iter().map(|c| (c * inv.clone()).rem_euclid(m.clone())).collect());
+
+  // Recombine the lifted factors into true factors.
+  let mut f = f;
+  let mut out = vec![];
+  let mut s = 1;
+  'size: while 2 * s <= lifted.len() {
+    let mut comb: Vec<usize> = (0..s).collect();
+    loop {
+      // once the budget is exhausted, the remaining factors are returned as their product
+      if budget::step().is_err() {
+        break 'size;
+      }
+      let lc = f[f.len() - 1].clone();
+      let g = comb.iter().fold(vec![lc], |acc, &i| z_mul(&acc, &lifted[i]).into_iter().map(|c| z_smod(c, &m)).collect());
+      let g = z_primitive(g);
+      if let Some(q) = z_div_exact(&f, &g) {
+        out.push(g);
+        f = q;
+        comb.iter().rev().for_each(|&i| {
+          lifted.remove(i);
+        });
+        continue 'size;
+      }
+
+      // next combination in lexicographic order
+      let Some(i) = (0..s).rev().find(|&i| comb[i] < lifted.len() - s + i) else {
+        break;
+      };
+      comb[i] += 1;
+      for j in i + 1..s {
+        comb[j] = comb[j - 1] + 1;
+      }
+    }
+    s += 1;
+  }
+
+  out.push(z_primitive(f));
+  out
+}
+
+// Lift ```f = g*h (mod p)``` to ```f = G*H (mod p^k)```, with g monic and gcd(g, h) = 1 (mod p).
+fn hensel(f: &[Integer], g: Vec<u64>, h: Vec<u64>, p: u64, k: usize) -> (Vec<Integer>, Vec<Integer>) {
+  // ```s*g + t*h = 1 (mod p)```
+  let (_, s, t) = fp_xgcd(&g, &h, p);
+  let lift = |a: &[u64]| a.iter().map(|&c| Integer::from(c)).collect::<Vec<_>>();
+  let (mut gz, mut hz) = (lift(&g), lift(&h));
+
+  let mut m = Integer::from(p);
+  for _ in 1..k {
+    // ```e = (f - G*H)/m (mod p)```
+    let gh = z_mul(&gz, &hz);
+    let len = f.len().max(gh.len());
+    let e: Vec<_> = (0..len)
+      .map(|i| {
+        let c = f.get(i).cloned().unwrap_or(Integer::ZERO) - gh.get(i).cloned().unwrap_or(Integer::ZERO);
+        fp_from(&(c / m.clone()), p)
+      })
+      .collect();
+    let e = fp_trim(e);
+
+    // ```a*h + b*g = e (mod p), a = e*t mod g, b = e*s + (e*t quo g)*h```
+    let (q, a) = fp_div_rem(&fp_mul(&e, &t, p), &g, p);
+    let b = fp_add(&fp_mul(&e, &s, p), &fp_mul(&q, &h, p), p);
+
+    let step = |z: &mut Vec<Integer>, d: &[u64]| {
+      z.resize(z.len().max(d.len()), Integer::ZERO);
+      for (c, &x) in z.iter_mut().zip(d) {
+        *c = c.clone() + m.clone() * Integer::from(x);
+      }
+    };
+    step(&mut gz, &a);
+    step(&mut hz, &b);
+    m *= Integer::from(p);
+  }
+
+  let reduce = |z: Vec<Integer>| z.into_iter().map(|c| c.rem_euclid(m.clone())).collect();
+  (reduce(gz), reduce(hz))
+}
+
+fn z_mul(a: &[Integer], b: &[Integer]) -> Vec<Integer> {
+  if a.is_empty() || b.is_empty() {
+    return vec![];
+  }
+
+  let mut c = vec![Integer::ZERO; a.len() + b.len() - 1];
+  for (i, x) in a.iter().enumerate() {
+    for (j, y) in b.iter().enumerate() {
+      c[i + j] = c[i + j].clone() + x.clone() * y.clone();
+    }
+  }
+  c
+}
+
+// Symmetric representative in ```(-m/2, m/2]```.
+fn z_smod(c: Integer, m: &Integer) -> Integer {
+  let c = c.rem_euclid(m.clone());
+  if c.clone() * Integer::TWO > m.clone() {
+    c - m.clone()
+  } else {
+    c
+  }
+}
+
+// Pseudo-remainder ```lc(b)^(deg(a) - deg(b) + 1)*a mod b```.
+fn z_prem(a: &[Integer], b: &[Integer]) -> Vec<Integer> {
+  let db = b.len() - 1;
+  let lc = b[db].clone();
+
+  let mut r = a.to_vec();
+  while r.len() > db {
+    // ```r = lc(b)*r - lc(r)*x^k*b```
+    let k = r.len() - 1 - db;
+    let c = r[r.len() - 1].clone();
+    r.iter_mut().for_each(|x| *x = x.clone() * lc.clone());
+    for (i, y) in b.iter().enumerate() {
+      r[i + k] = r[i + k].clone() - c.clone() * y.clone();
+    }
+    while r.last().is_some_and(|c| *c == Integer::ZERO) {
+      r.pop();
+    }
+  }
+  r
+}
+
+fn z_primitive(mut a: Vec<Integer>) -> Vec<Integer> {
+  while a.last().is_some_and(|c| *c == Integer::ZERO) {
+    a.pop();
+  }
+
+  let g = a.iter().fold(Integer::ZERO, |acc, c| Integer::gcd(acc, c.clone()));
+  let sgn = Integer::from(a.last().map_or(1, |c| c.ord() as i64));
+  if g == Integer::ZERO {
+    return a;
+  }
+  a.into_iter().map(|c| c / g.clone() * sgn.clone()).collect()
+}
+
+fn z_div_exact(a: &[Integer], b: &[Integer]) -> Option<Vec<Integer>> {
+  let (q, r) = Poly::from_integers(a.to_vec()).div_rem(&Poly::from_integers(b.to_vec()))?;
+  if !r.is_zero() || q.coef.iter().any(|c| c.den != Integer::ONE) {
+    return None;
+  }
+  Some(q.coef.into_iter().map(|c| c.num).collect())
+}
+
+// ```a^-1 (mod m)```
+fn z_inv_mod(a: Integer, m: Integer) -> Integer {
+  let (mut r0, mut r1) = (a.rem_euclid(m.clone()), m.clone());
+  let (mut s0, mut s1) = (Integer::ONE, Integer::ZERO);
+  while r1 != Integer::ZERO {
+    let q = r0.clone().div_euclid(r1.clone());
+    (r0, r1) = (r1.clone(), r0 - q.clone() * r1);
+    (s0, s1) = (s1.clone(), s0 - q * s1);
+  }
+  s0.rem_euclid(m)
+}
+
+fn fp_from(c: &Integer, p: u64) -> u64 {
+  Natural::try_from(c.clone().rem_euclid(Integer::from(p))).ok().and_then(|c| u64::try_from(c).ok()).unwrap_or(0)
+}
+
+fn fp_trim(mut a: Vec<u64>) -> Vec<u64> {
+  while a.last() == Some(&0) {
+    a.pop();
+  }
+  a
+}
+
+fn fp_deg(a: &[u64]) -> usize {
+  a.len().saturating_sub(1)
+}
+
+fn fp_pow(mut b: u64, mut e: u64, p: u64) -> u64 {
+  let mut r = 1;
+  while e > 0 {
+    if e & 1 == 1 {
+      r = r * b % p;
+    }
+    b = b * b % p;
+    e >>= 1;
+  }
+  r
+}
+
+fn fp_add(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+  let len = a.len().max(b.len());
+  fp_trim((0..len).map(|i| (a.get(i).unwrap_or(&0) + b.get(i).unwrap_or(&0)) % p).collect())
+}
+
+fn fp_sub(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+  let len = a.len().max(b.len());
+  fp_trim((0..len).map(|i| (a.get(i).unwrap_or(&0) + p - b.get(i).unwrap_or(&0)) % p).collect())
+}
+
+fn fp_mul(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+  if a.is_empty() || b.is_empty() {
+    return vec![];
+  }
+
+  let mut c = vec![0; a.len() + b.len() - 1];
+  for (i, x) in a.iter().enumerate() {
+    for (j, y) in b.iter().enumerate() {
+      c[i + j] = (c[i + j] + x * y) % p;
+    }
+  }
+  fp_trim(c)
+}
+
+fn fp_monic(a: Vec<u64>, p: u64) -> Vec<u64> {
+  let a = fp_trim(a);
+  let inv = a.last().map_or(1, |&l| fp_pow(l, p - 2, p));
+  a.into_iter().map(|c| c * inv % p).collect()
+}
+
+fn fp_derivative(a: &[u64], p: u64) -> Vec<u64> {
+  fp_trim(a.iter().enumerate().skip(1).map(|(k, c)| k as u64 % p * c % p).collect())
+}
+
+fn fp_div_rem(a: &[u64], b: &[u64], p: u64) -> (Vec<u64>, Vec<u64>) {
+  let db = fp_deg(b);
+  let inv = fp_pow(b[db], p - 2, p);
+
+  let mut r = a.to_vec();
+  let mut q = vec![0; a.len().saturating_sub(db)];
+  while r.len() > db && !r.is_empty() {
+    let k = r.len() - 1 - db;
+    let c = r[r.len() - 1] * inv % p;
+    for (i, x) in b.iter().enumerate() {
+      r[i + k] = (r[i + k] + p - c * x % p) % p;
+    }
+    q[k] = c;
+    r = fp_trim(r);
+  }
+  (fp_trim(q), r)
+}
+
+fn fp_gcd(a: &[u64], b: &[u64], p: u64) -> Vec<u64> {
+  let (mut a, mut b) = (a.to_vec(), b.to_vec());
+  while !b.is_empty() {
+    let r = fp_div_rem(&a, &b, p).1;
+    (a, b) = (b, r);
+  }
+  fp_monic(a, p)
+}
+
+// ```s*a + t*b = gcd(a, b)```, with a monic gcd.
+fn fp_xgcd(a: &[u64], b: &[u64], p: u64) -> (Vec<u64>, Vec<u64>, Vec<u64>) {
+  let (mut r0, mut r1) = (a.to_vec(), b.to_vec());
+  let (mut s0, mut s1) = (vec![1], vec![]);
+  let (mut t0, mut t1) = (vec![], vec![1]);
+  while !r1.is_empty() {
+    let (q, r) = fp_div_rem(&r0, &r1, p);
+    (r0, r1) = (r1, r);
+    (s0, s1) = (s1.clone(), fp_sub(&s0, &fp_mul(&q, &s1, p), p));
+    (t0, t1) = (t1.clone(), fp_sub(&t0, &fp_mul(&q, &t1, p), p));
+  }
+
+  let inv = vec![r0.last().map_or(1, |&l| fp_pow(l, p - 2, p))];
+  (fp_mul(&r0, &inv, p), fp_mul(&s0, &inv, p), fp_mul(&t0, &inv, p))
+}
+
+// ```b^e (mod m)```
+fn fp_pow_mod(b: &[u64], e: &Natural, m: &[u64], p: u64) -> Vec<u64> {
+  let b = fp_div_rem(b, m, p).1;
+  let mut r = vec![1];
+  for i in (0..e.bits()).rev() {
+    r = fp_div_rem(&fp_mul(&r, &r, p), m, p).1;
+    if e.bit(i) {
+      r = fp_div_rem(&fp_mul(&r, &b, p), m, p).1;
+    }
+  }
+  r
+}
+
+/// Factor a monic square-free polynomial over 𝔽_p (p odd) into monic irreducible factors.
+fn fp_factor(f: Vec<u64>, p: u64) -> Vec<Vec<u64>> {
+  // distinct degree factorization
+  let x = vec![0, 1];
+  let mut f = f;
+  let mut h = x.clone();
+  let mut out = vec![];
+  let mut d = 0;
+  while fp_deg(&f) >= 2 * (d + 1) {
+    d += 1;
+    // ```h = x^(p^d) (mod f)```
+    h = fp_pow_mod(&h, &Natural::from(p), &f, p);
+    let g = fp_gcd(&f, &fp_sub(&h, &x, p), p);
+    if fp_deg(&g) > 0 {
+      f = fp_div_rem(&f, &g, p).0;
+      h = fp_div_rem(&h, &f, p).1;
+      out.extend(fp_split(g, d, p));
+    }
+  }
+  if fp_deg(&f) > 0 {
+    out.push(f);
+  }
+
+  out
+}
+
+// Equal degree factorization of a product of irreducible factors of degree d (Cantor-Zassenhaus), trying the
+// splitting polynomials ```a^((p^d - 1)/2) - 1``` in a fixed order.
+fn fp_split(f: Vec<u64>, d: usize, p: u64) -> Vec<Vec<u64>> {
+  let n = fp_deg(&f);
+  if n <= d {
+    return vec![f];
+  }
+
+  let e = (Natural::from(p).pow(d as u64) - Natural::ONE) >> 1;
+  for c in p.. {
+    // base p digits of c
+    let mut a = vec![];
+    let mut c = c;
+    while c > 0 {
+      a.push(c % p);
+      c /= p;
+    }
+
+    let b = fp_sub(&fp_pow_mod(&a, &e, &f, p), &[1], p);
+    let g = fp_gcd(&f, &b, p);
+    if (1..n).contains(&fp_deg(&g)) {
+      let h = fp_div_rem(&f, &g, p).0;
+      let mut out = fp_split(g, d, p);
+      out.extend(fp_split(h, d, p));
+      return out;
+    }
+  }
+
+  unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn poly(coef: &[i64]) -> Poly {
+    Poly::from_integers(coef.iter().map(|&c| Integer::from(c)).collect())
+  }
+
+  fn q(n: i64, d: i64) -> Rational {
+    Rational::new(Integer::from(n), Integer::from(d))
+  }
+
+  #[test]
+  fn arithmetic() {
+    // ```(x + 1)*(x - 1) = x^2 - 1```
+    assert_eq!(poly(&[1, 1]) * poly(&[-1, 1]), poly(&[-1, 0, 1]));
+    assert_eq!(poly(&[1, 1]) - poly(&[1, 1]), Poly::zero());
+    assert_eq!(poly(&[-1, 0, 1]).div_rem(&poly(&[-1, 1])), Some((poly(&[1, 1]), Poly::zero())));
+    assert_eq!(poly(&[1, 0, 1]).div_rem(&poly(&[0, 2])), Some((Poly::new(vec![q(0, 1), q(1, 2)]), poly(&[1]))));
+    assert_eq!(poly(&[1]).div_rem(&Poly::zero()), None);
+    assert_eq!(Poly::gcd(&poly(&[-1, 0, 1]), &poly(&[2, 2])), poly(&[1, 1]));
+    assert_eq!(poly(&[1, 2, 3]).eval(&q(1, 2)), q(11, 4));
+    assert_eq!(poly(&[0, 0, 1]).compose(&poly(&[1, 1])), poly(&[1, 2, 1]));
+    assert_eq!(format!("{}", poly(&[-1, -1, 0, 0, 0, 1])), "x^5 + -1*x + -1");
+  }
+
+  #[test]
+  fn roots() {
+    // ```x^3 - 2*x = x*(x^2 - 2)```
+    let p = poly(&[0, -2, 0, 1]);
+    assert_eq!(p.count_roots(&q(-2, 1), &q(2, 1)), 3);
+    assert_eq!(p.count_roots(&q(0, 1), &q(2, 1)), 1);
+    assert_eq!(p.count_roots(&q(-1, 1), &q(0, 1)), 1);
+
+    let roots = p.real_roots();
+    assert_eq!(roots.len(), 3);
+    assert!(roots.windows(2).all(|w| w[0].1 <= w[1].0));
+    // double roots are counted once
+    assert_eq!((poly(&[-1, 1]) * poly(&[-1, 1]) * poly(&[1, 0, 1])).real_roots().len(), 1);
+    assert_eq!(poly(&[-1, -1, 0, 0, 0, 1]).real_roots().len(), 1);
+  }
+
+  #[test]
+  fn factorization() {
+    // ```2*x^3 - 2 = 2*(x - 1)*(x^2 + x + 1)```
+    assert_eq!(poly(&[-2, 0, 0, 2]).factor(), (q(2, 1), vec![(poly(&[-1, 1]), 1), (poly(&[1, 1, 1]), 1)]));
+    // ```(x^2 - 2)^2*(x + 1/2)```
+    let p = poly(&[-2, 0, 1]) * poly(&[-2, 0, 1]) * Poly::new(vec![q(1, 2), q(1, 1)]);
+    assert_eq!(p.factor(), (q(1, 1), vec![(Poly::new(vec![q(1, 2), q(1, 1)]), 1), (poly(&[-2, 0, 1]), 2)]));
+    // irreducible
+    assert_eq!(poly(&[-1, -1, 0, 0, 0, 1]).factor().1, vec![(poly(&[-1, -1, 0, 0, 0, 1]), 1)]);
+    assert_eq!(poly(&[1, 0, 0, 0, 1]).factor().1, vec![(poly(&[1, 0, 0, 0, 1]), 1)]);
+    // ```x^8 - 1```
+    let f = poly(&[-1, 0, 0, 0, 0, 0, 0, 0, 1]).factor().1;
+    assert_eq!(f, vec![(poly(&[-1, 1]), 1), (poly(&[1, 1]), 1), (poly(&[1, 0, 1]), 1), (poly(&[1, 0, 0, 0, 1]), 1)]);
+    // ```(x^2 - 2)*(x^2 - 3)*(x^2 + 5*x - 7)``` with many modular factors
+    let p = poly(&[-2, 0, 1]) * poly(&[-3, 0, 1]) * poly(&[-7, 5, 1]);
+    assert_eq!(p.factor().1.len(), 3);
+    // ```x^4 - 10*x^2 + 1``` splits modulo every prime
+    assert_eq!(poly(&[1, 0, -10, 0, 1]).factor().1.len(), 1);
+  }
+
+  #[test]
+  fn finite_fields() {
+    let f5 = FiniteField::prime(Natural::from(5u64)).unwrap();
+    let gf = |c: &[i64]| GfPoly::from_integers(&f5, &c.iter().map(|&c| Integer::from(c)).collect::<Vec<_>>());
+    // ```x^4 - 1 = (x - 1)*(x - 2)*(x - 3)*(x - 4)``` over ```𝔽_5```
+    let f = gf(&[-1, 0, 0, 0, 1]).factor().1;
+    assert_eq!(f, vec![(gf(&[1, 1]), 1), (gf(&[2, 1]), 1), (gf(&[3, 1]), 1), (gf(&[4, 1]), 1)]);
+    // ```(x^5 + 1)*(x^2 + 2)^2 = (x + 1)^5*(x^2 + 2)^2```: powers of p and repeated factors
+    let p = gf(&[1, 0, 0, 0, 0, 1]) * gf(&[2, 0, 1]) * gf(&[2, 0, 1]);
+    assert_eq!(p.factor().1, vec![(gf(&[1, 1]), 5), (gf(&[2, 0, 1]), 2)]);
+    let p = gf(&[1, 1, 1]) * gf(&[2, 1, 0, 1]) * gf(&[3, 0, 0, 0, 1]);
+    let (lead, f) = (p.clone() * gf(&[3])).factor();
+    assert_eq!(f.iter().fold(GfPoly::monomial(lead, 0), |acc, (g, k)| (0..*k).fold(acc, |a, _| a * g.clone())), p * gf(&[3]));
+    assert!(f.iter().all(|(g, _)| g.is_irreducible()));
+
+    // ```GF(4)```: ```x^2 + x + 1``` splits, ```x^2 + x + a``` does not
+    let f2 = FiniteField::prime(Natural::TWO).unwrap();
+    let f4 = FiniteField::galois(Natural::TWO, 2).unwrap();
+    assert_eq!(f4.modulus(), GfPoly::from_integers(&f2, &[Integer::ONE, Integer::ONE, Integer::ONE]));
+    let a = f4.root();
+    let p = GfPoly::new(&f4, vec![f4.one(), f4.one(), f4.one()]);
+    assert_eq!(
+      p.factor().1,
+      vec![(GfPoly::new(&f4, vec![a.clone(), f4.one()]), 1), (GfPoly::new(&f4, vec![a.clone() + f4.one(), f4.one()]), 1)]
+    );
+    assert!(GfPoly::new(&f4, vec![a, f4.one(), f4.one()]).is_irreducible());
+
+    // generation
+    assert_eq!(GfPoly::irreducible(&f2, 3), GfPoly::from_integers(&f2, &[Integer::ONE, Integer::ONE, Integer::ZERO, Integer::ONE]));
+    assert!(!gf(&[2, 0, 0, 1]).is_irreducible());
+    let m = GfPoly::primitive(&f5, 3);
+    assert!(m.is_primitive() && m.deg() == Some(3));
+    assert_eq!(GfPoly::irreducible(&f4, 2).deg(), Some(2));
+    assert_eq!(gf(&[1, 2, 3]).to_string(), "3*x^2 + 2*x + 1");
+  }
+}
@@ -2,7 +2,7 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::iter;
 use std::mem;
-use std::ops::{Add, BitOr, Div, Mul, Neg, Rem, Shl, Shr, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Rem, Shl, Shr, Sub};
 
 pub(crate) type Word = u64;
 pub(crate) type Dual = u128;
@@ -99,12 +99,15 @@ impl Digits {
     let bytes = src.as_bytes();
 
     if bytes.len() <= basis.digits_per_word {
-      Ok(Digits::Fix(parse_word(bytes, radix)?))
+      Ok(Digits::Fix(parse_word(bytes, radix, 0)?))
     } else {
       let chunks = bytes.rchunks(basis.digits_per_word);
       let mut digits = Array::with_capacity(chunks.len());
+      let mut pos = 0;
       for chunk in chunks.rev() {
-        let carry = carrying_mul_word(&mut digits, basis.range_per_word, parse_word(chunk, radix)?);
+        let word = parse_word(chunk, radix, pos)?;
+        pos += chunk.len();
+        let carry = carrying_mul_word(&mut digits, basis.range_per_word, word);
         if carry != 0 {
           digits.push(carry);
         }
@@ -499,7 +502,7 @@ impl Div<Digits> for Digits {
 
         Digits::Fix(o) => {
           let _ = remainding_div_word(&mut s, o);
-          Digits::Arb(s)
+          Digits::from(s)
         }
       },
     }
@@ -523,7 +526,7 @@ impl Rem<Digits> for Digits {
           let s_len = s.len();
           let o_len = o.len();
 
-          if o_len < s_len {
+          if o_len > s_len {
             Digits::from(s)
           } else {
             if let Some(last) = o.last() {
@@ -597,7 +600,7 @@ impl Digits {
 
         Digits::Fix(o) => {
           let r = remainding_div_word(&mut s, o);
-          (Digits::Arb(s), Digits::Fix(r))
+          (Digits::from(s), Digits::Fix(r))
         }
       },
     }
@@ -731,6 +734,130 @@ impl BitOr<Digits> for Digits {
   }
 }
 
+impl BitAnd<Digits> for Digits {
+  type Output = Digits;
+
+  fn bitand(self, o: Digits) -> Self::Output {
+    match (self, o) {
+      (Digits::Fix(lhs), Digits::Fix(rhs)) => Digits::Fix(lhs & rhs),
+
+      (Digits::Arb(mut s), o) | (o, Digits::Arb(mut s)) => match o {
+        Digits::Arb(mut o) => {
+          let s_len = s.len();
+          let o_len = o.len();
+          if o_len < s_len {
+            mem::swap(&mut s, &mut o);
+          }
+
+          for (l, r) in s.iter_mut().zip(o.iter()) {
+            *l &= *r;
+          }
+
+          Digits::from(
+            s, //.
+          )
+        }
+
+        Digits::Fix(o) => Digits::Fix(
+          s.first().map_or(0, |first| first & o), //.
+        ),
+      },
+    }
+  }
+}
+
+impl BitXor<Digits> for Digits {
+  type Output = Digits;
+
+  fn bitxor(self, o: Digits) -> Self::Output {
+    match (self, o) {
+      (Digits::Fix(lhs), Digits::Fix(rhs)) => Digits::Fix(lhs ^ rhs),
+
+      (Digits::Arb(mut s), o) | (o, Digits::Arb(mut s)) => match o {
+        Digits::Arb(mut o) => {
+          let s_len = s.len();
+          let o_len = o.len();
+          if o_len > s_len {
+            mem::swap(&mut s, &mut o);
+          }
+
+          for (l, r) in s.iter_mut().zip(o.iter()) {
+            *l ^= *r;
+          }
+
+          Digits::from(
+            s, //.
+          )
+        }
+
+        Digits::Fix(o) => {
+          if let Some(first) = s.first_mut() {
+            *first ^= o;
+          }
+
+          Digits::from(s)
+        }
+      },
+    }
+  }
+}
+
+impl Digits {
+  #[inline]
+  pub(crate) fn bit(&self, idx: usize) -> bool {
+    let (word, bit) = (idx / WORD_BITS, idx % WORD_BITS);
+    match self {
+      Digits::Fix(w) => word == 0 && (w >> bit) & 1 == 1,
+      Digits::Arb(array) => array.get(word).is_some_and(|w| (w >> bit) & 1 == 1),
+    }
+  }
+
+  pub(crate) fn set_bit(self, idx: usize, bit: bool) -> Digits {
+    let (word, shift) = (idx / WORD_BITS, idx % WORD_BITS);
+    let mut s = match self {
+      Digits::Fix(w) => vec![w],
+      Digits::Arb(s) => s,
+    };
+
+    if s.len() <= word {
+      if !bit {
+        return Digits::from(s);
+      }
+      s.resize(word + 1, 0);
+    }
+
+    if bit {
+      s[word] |= 1 << shift;
+    } else {
+      s[word] &= !(1 << shift);
+    }
+
+    Digits::from(
+      s, //.
+    )
+  }
+
+  #[inline]
+  pub(crate) fn count_ones(&self) -> usize {
+    match self {
+      Digits::Fix(word) => {
+        word.count_ones() as usize //.
+      }
+      Digits::Arb(array) => array.iter().map(|word| word.count_ones() as usize).sum(),
+    }
+  }
+
+  #[inline]
+  pub(crate) fn bits(&self) -> usize {
+    match self {
+      Digits::Fix(word) => {
+        WORD_BITS - word.leading_zeros() as usize //.
+      }
+      Digits::Arb(array) => array.last().map_or(0, |last| (array.len() - 1) * WORD_BITS + WORD_BITS - last.leading_zeros() as usize),
+    }
+  }
+}
+
 impl Shl<usize> for Digits {
   type Output = Digits;
 
@@ -762,7 +889,7 @@ impl Shr<usize> for Digits {
 
   fn shr(self, o: usize) -> Self::Output {
     match self {
-      Digits::Fix(word) => Digits::Fix(word >> o),
+      Digits::Fix(word) => Digits::Fix(if o < WORD_BITS { word >> o } else { 0 }),
 
       Digits::Arb(mut s) => {
         let shift_words = o / WORD_BITS;
@@ -811,17 +938,37 @@ impl Basis {
 /// An arbitrary number parsing error.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
-  InvalidDigit,
+  /// The input contains no digits.
+  Empty,
+  /// An invalid character at the given byte position.
+  InvalidDigit(usize),
+  /// The denominator of a fraction is zero.
+  ZeroDenominator,
+  /// The number is too large to be represented (decimal exponent out of range).
+  Overflow,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ParseError::Empty => write!(f, "cannot parse number from empty string"),
+      ParseError::InvalidDigit(pos) => write!(f, "invalid digit found at position {pos}"),
+      ParseError::ZeroDenominator => write!(f, "zero denominator"),
+      ParseError::Overflow => write!(f, "number too large to be represented"),
+    }
+  }
 }
 
-fn parse_word(src: &[u8], radix: u32) -> Result<Word, ParseError> {
-  src.iter().try_fold(0, |acc, byte| {
-    Ok(acc * (radix as Word) + (digit_from_utf8_byte(*byte, radix).ok_or(ParseError::InvalidDigit)? as Word))
+impl std::error::Error for ParseError {}
+
+fn parse_word(src: &[u8], radix: u32, pos: usize) -> Result<Word, ParseError> {
+  src.iter().enumerate().try_fold(0, |acc, (i, byte)| {
+    Ok(acc * (radix as Word) + (digit_from_utf8_byte(*byte, radix).ok_or(ParseError::InvalidDigit(pos + i))? as Word))
     //.
   })
 }
 
-fn digit_from_utf8_byte(byte: u8, radix: u32) -> Option<u32> {
+pub(crate) fn digit_from_utf8_byte(byte: u8, radix: u32) -> Option<u32> {
   let res = match byte {
     b'0'..=b'9' => (byte - b'0') as u32,
     b'a'..=b'z' => (byte - b'a') as u32 + 10,
@@ -939,7 +1086,11 @@ impl Sign {
       }
 
       (Digits::Arb(mut s), Digits::Fix(word)) | (Digits::Fix(word), Digits::Arb(mut s)) => {
-        borrowing_sub_word(&mut s, word);
+        if let Some((word_0, words_hi)) = s.split_first_mut() {
+          let (a, borrow) = word_0.overflowing_sub(word);
+          *word_0 = a;
+          let _ = borrow && borrowing_sub_word(words_hi, 1);
+        }
         (Sign::from(lhs_len.cmp(&rhs_len)), Digits::from(s))
       }
 
@@ -947,7 +1098,7 @@ impl Sign {
         let mut sgn = lhs_len.cmp(&rhs_len);
         match sgn {
           Ordering::Equal => {
-            sgn = lhs.last().cmp(&rhs.last());
+            sgn = cmp_digits(&lhs, &rhs);
             match sgn {
               Ordering::Greater => {
                 borrowing_sub_lhs(&mut lhs, &rhs);
@@ -958,10 +1109,9 @@ impl Sign {
               }
 
               Ordering::Equal => {
-                lhs[lhs_len - 1] = 0;
-                return Sign::diff(
-                  lhs.into(), //.
-                  rhs.into(),
+                return (
+                  Sign::Positive,
+                  Digits::Fix(0), //.
                 );
               }
             };
@@ -0,0 +1,502 @@
+//! Assumptions on symbols.
+//!
+//! A [`Symbol`] carries the [`Facts`] assumed to hold for it, starting from those implied by its domain. The facts of
+//! an expression are inferred from those of its subexpressions (a sum of positive terms is positive, an even power of
+//! a real expression is nonnegative, ...), so that simplifications only valid under some assumptions
+//! (```sqrt(x^2) = x, x ≥ 0```) can be applied by [`Expr::trivial`].
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::base::alg::{AOp, Algebra, Assoc, BOp, UOp};
+use crate::base::algebra::NumberSystem;
+use crate::base::fun::{EOp, Function, Special};
+use crate::{Constant, Integer, Number, Rational, Symbol, Tree};
+
+// Possible signs of a value, as a set.
+const NEG: u8 = 1;
+const ZERO: u8 = 2;
+const POS: u8 = 4;
+const ALL: u8 = NEG | ZERO | POS;
+
+/// A property assumed to hold for a [`Symbol`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Assume {
+  /// ```x > 0```
+  Positive,
+  /// ```x < 0```
+  Negative,
+  /// ```x ≥ 0```
+  NonNegative,
+  /// ```x ≤ 0```
+  NonPositive,
+  /// ```x ≠ 0```
+  NonZero,
+  /// ```x ∈ ℤ```
+  Integer,
+  /// ```x ∈ ℝ```
+  Real,
+  /// ```lo ≤ x ≤ hi```, unbounded on a side for `None`.
+  Bounded(Option<Rational>, Option<Rational>),
+}
+
+/// The known properties of a value.
+///
+/// Facts are conservative: a property which is not known to hold may still hold.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Facts {
+  /// Possible signs (nonzero values for nonreal values).
+  sign: u8,
+  real: bool,
+  integer: bool,
+  /// Closed real bounds.
+  lo: Option<Rational>,
+  hi: Option<Rational>,
+}
+
+impl Facts {
+  /// Nothing is known.
+  pub const UNKNOWN: Facts = Facts {
+    sign: ALL,
+    real: false,
+    integer: false,
+    lo: None,
+    hi: None,
+  };
+
+  /// Return `true` if the value is known to be ```> 0```.
+  pub fn is_positive(&self) -> bool {
+    self.real && self.sign == POS
+  }
+  /// Return `true` if the value is known to be ```< 0```.
+  pub fn is_negative(&self) -> bool {
+    self.real && self.sign == NEG
+  }
+  /// Return `true` if the value is known to be ```≥ 0```.
+  pub fn is_nonnegative(&self) -> bool {
+    self.real && self.sign & NEG == 0
+  }
+  /// Return `true` if the value is known to be ```≤ 0```.
+  pub fn is_nonpositive(&self) -> bool {
+    self.real && self.sign & POS == 0
+  }
+  /// Return `true` if the value is known to be ```≠ 0```.
+  pub fn is_nonzero(&self) -> bool {
+    self.sign & ZERO == 0
+  }
+  /// Return `true` if the value is known to be in ```ℤ```.
+  pub fn is_integer(&self) -> bool {
+    self.integer
+  }
+  /// Return `true` if the value is known to be in ```ℝ```.
+  pub fn is_real(&self) -> bool {
+    self.real
+  }
+
+  /// Return the sign of the value, if it is known.
+  pub fn sign(&self) -> Option<Ordering> {
+    match self.sign {
+      _ if !self.real => None,
+      NEG => Some(Ordering::Less),
+      ZERO => Some(Ordering::Equal),
+      POS => Some(Ordering::Greater),
+      _ => None,
+    }
+  }
+
+  /// Return the known closed bounds of the value, `None` for an unbounded side.
+  pub fn bounds(&self) -> (Option<&Rational>, Option<&Rational>) {
+    (self.lo.as_ref(), self.hi.as_ref())
+  }
+
+  /// Add an assumption, or return `None` if it contradicts the known facts.
+  pub fn with(mut self, assume: Assume) -> Option<Facts> {
+    match assume {
+      Assume::Positive => self.restrict(POS, true),
+      Assume::Negative => self.restrict(NEG, true),
+      Assume::NonNegative => self.restrict(ZERO | POS, true),
+      Assume::NonPositive => self.restrict(NEG | ZERO, true),
+      Assume::NonZero => self.restrict(NEG | POS, false),
+      Assume::Integer => {
+        self.real = true;
+        self.integer = true;
+      }
+      Assume::Real => self.real = true,
+      Assume::Bounded(lo, hi) => {
+        self.real = true;
+        self.lo = match (self.lo, lo) {
+          (Some(l), Some(r)) => Some(l.max(r)),
+          (l, r) => l.or(r),
+        };
+        self.hi = match (self.hi, hi) {
+          (Some(l), Some(r)) => Some(l.min(r)),
+          (l, r) => l.or(r),
+        };
+      }
+    }
+    self.normalize()
+  }
+
+  fn restrict(&mut self, sign: u8, real: bool) {
+    self.sign &= sign;
+    self.real |= real;
+  }
+
+  // Exact facts of a real number.
+  fn exact(q: Rational, integer: bool) -> Facts {
+    Facts {
+      sign: sign_bit(q.cmp(&Rational::from(Integer::ZERO))),
+      real: true,
+      integer,
+      lo: Some(q.clone()),
+      hi: Some(q),
+    }
+  }
+
+  // Facts of a real value with a given set of signs.
+  fn real(sign: u8) -> Facts {
+    Facts {
+      sign,
+      real: true,
+      integer: false,
+      lo: None,
+      hi: None,
+    }
+  }
+
+  // Propagate the sign to the bounds and back, or `None` if the facts are contradictory.
+  fn normalize(mut self) -> Option<Facts> {
+    self.real |= self.integer;
+    if !self.real {
+      self.lo = None;
+      self.hi = None;
+    } else {
+      let zero = Rational::from(Integer::ZERO);
+      if self.integer {
+        self.lo = self.lo.map(|lo| Rational::from(lo.ceil()));
+        self.hi = self.hi.map(|hi| Rational::from(hi.floor()));
+      }
+      if let Some(lo) = &self.lo {
+        match lo.cmp(&zero) {
+          Ordering::Greater => self.sign &= POS,
+          Ordering::Equal => self.sign &= ZERO | POS,
+          Ordering::Less => {}
+        }
+      }
+      if let Some(hi) = &self.hi {
+        match hi.cmp(&zero) {
+          Ordering::Less => self.sign &= NEG,
+          Ordering::Equal => self.sign &= NEG | ZERO,
+          Ordering::Greater => {}
+        }
+      }
+      if self.sign & NEG == 0 && self.lo.as_ref().is_none_or(|lo| lo < &zero) {
+        self.lo = Some(zero.clone());
+      }
+      if self.sign & POS == 0 && self.hi.as_ref().is_none_or(|hi| hi > &zero) {
+        self.hi = Some(zero);
+      }
+      if matches!((&self.lo, &self.hi), (Some(lo), Some(hi)) if lo > hi) {
+        return None;
+      }
+    }
+    (self.sign != 0).then_some(self)
+  }
+}
+
+impl From<NumberSystem> for Facts {
+  fn from(dom: NumberSystem) -> Facts {
+    let facts = Facts::UNKNOWN;
+    match dom {
+      NumberSystem::N => facts.with(Assume::Integer).and_then(|f| f.with(Assume::NonNegative)),
+      NumberSystem::Z => facts.with(Assume::Integer),
+      NumberSystem::Q | NumberSystem::R => facts.with(Assume::Real),
+      NumberSystem::AS | NumberSystem::C => Some(facts),
+    }
+    .unwrap_or(Facts::UNKNOWN)
+  }
+}
+
+impl Symbol {
+  /// Add an assumption on the symbol, or return `None` if it contradicts its domain or previous assumptions.
+  ///
+  /// Symbols of the same name and domain are equal only under the same assumptions.
+  pub fn assume(mut self, assume: Assume) -> Option<Symbol> {
+    self.facts = Arc::new(Facts::clone(&self.facts).with(assume.clone())?);
+    self.assumed = Some(Arc::new(self.assumed.map_or(Facts::UNKNOWN, |facts| Facts::clone(&facts)).with(assume)?));
+    Some(self)
+  }
+
+  /// Return the facts assumed for the symbol.
+  pub fn facts(&self) -> &Facts {
+    &self.facts
+  }
+}
+
+impl Tree {
+  /// Infer the facts known about the value of the expression.
+  pub fn facts(&self) -> Facts {
+    let facts = match self {
+      Tree::Sym(s) => Facts::clone(&s.facts),
+      Tree::Num(n) => match n {
+        Number::Int(z) => Facts::exact(Rational::from(z.clone()), true),
+        Number::Rat(q) => Facts::exact(q.clone(), false),
+        Number::Flt(x) => Facts::exact(x.to_rational(), false),
+        Number::Alg(a) => Facts::real(sign_bit(a.sgn())),
+        Number::Cpx(_) => Facts { sign: NEG | POS, ..Facts::UNKNOWN },
+      },
+      Tree::Cte(c) => match c {
+        // ```3.14 < π < 3.15```, ```2.71 < e < 2.72```
+        Constant::pi => within((314, 100), (315, 100)),
+        Constant::e => within((271, 100), (272, 100)),
+        Constant::i => Facts { sign: NEG | POS, ..Facts::UNKNOWN },
+        Constant::Infinity(_) => Facts::UNKNOWN,
+      },
+
+      Tree::Alg(Algebra::AssocExpr(Assoc { map, arg })) => {
+        let mut iter = arg.iter().map(|e| e.facts());
+        let init = iter.next().unwrap_or(Facts::UNKNOWN);
+        let mut facts = iter.fold(init, |acc, e| match map {
+          AOp::Add => Facts {
+            sign: lift(acc.sign, e.sign, add_sign),
+            real: acc.real && e.real,
+            integer: false,
+            lo: ext_op(acc.lo, e.lo, Ordering::Less, Ext::add),
+            hi: ext_op(acc.hi, e.hi, Ordering::Greater, Ext::add),
+          },
+          AOp::Mul => mul(acc, e),
+        });
+        facts.integer = Function::integral(self);
+        facts
+      }
+
+      Tree::Alg(Algebra::UExpr { map: UOp::Id, arg }) => arg.facts(),
+      Tree::Alg(Algebra::UExpr { map: UOp::Fact, arg }) => match arg.facts() {
+        // ```n! ≥ 1, n ∈ ℕ```
+        n if n.is_integer() && n.is_nonnegative() => Facts {
+          integer: true,
+          ..bounded(Some(1), None)
+        },
+        _ => Facts::UNKNOWN,
+      },
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, e) }) => pow(b.facts(), e),
+
+      Tree::Fun(Function::ElemExpr { map, arg }) => {
+        let x = arg.facts();
+        match map {
+          _ if !x.real => Facts::UNKNOWN,
+          // ```exp(x) > 0```, ```cosh(x) ≥ 1```
+          EOp::Exp => Facts::real(POS),
+          EOp::Cosh => bounded(Some(1), None),
+          // ```-1 ≤ sin(x), cos(x) ≤ 1```, ```-1 < tanh(x) < 1``` with the sign of ```x```
+          EOp::Sin | EOp::Cos => bounded(Some(-1), Some(1)),
+          EOp::Tanh => Facts {
+            sign: x.sign,
+            ..bounded(Some(-1), Some(1))
+          },
+          // odd and increasing functions
+          EOp::ArcTan | EOp::Sinh | EOp::ArSinh => Facts::real(x.sign),
+          EOp::Log if x.is_positive() => Facts::real(ALL),
+          _ => Facts::UNKNOWN,
+        }
+      }
+
+      Tree::Fun(Function::SpecExpr(Special::Mod(..))) => Facts::UNKNOWN,
+      Tree::Fun(Function::SpecExpr(map)) => {
+        let (Special::Gamma(arg) | Special::Floor(arg) | Special::Ceil(arg) | Special::Round(arg) | Special::Frac(arg) | Special::Mod(arg, _)) = map;
+        let x = arg.facts();
+        match map {
+          _ if !x.real => Facts::UNKNOWN,
+          // ```Γ(x) > 0, x > 0```
+          Special::Gamma(_) if x.is_positive() => Facts::real(POS),
+          Special::Gamma(_) => Facts::UNKNOWN,
+          // ```0 ≤ frac(x) < 1```
+          Special::Frac(_) => bounded(Some(0), Some(1)),
+          _ => {
+            let sign = match map {
+              // ```⌊x⌋ ≥ 0, x ≥ 0```, ```⌈x⌉ > 0, x > 0```
+              Special::Floor(_) | Special::Round(_) if x.is_nonnegative() => ZERO | POS,
+              Special::Ceil(_) | Special::Round(_) if x.is_nonpositive() => NEG | ZERO,
+              Special::Ceil(_) if x.is_positive() => POS,
+              Special::Floor(_) if x.is_negative() => NEG,
+              _ => ALL,
+            };
+            Facts { integer: true, ..Facts::real(sign) }
+          }
+        }
+      }
+
+      Tree::Fun(Function::CombExpr { .. }) => Facts {
+        integer: Function::integral(self),
+        ..Facts::UNKNOWN
+      },
+
+      Tree::Form | Tree::Fun(_) | Tree::Cal(_) | Tree::Sq(_) => Facts::UNKNOWN,
+    };
+    facts.normalize().unwrap_or(Facts::UNKNOWN)
+  }
+}
+
+// ```b^e```, for integer exponents or positive bases.
+fn pow(b: Facts, e: &Tree) -> Facts {
+  match e {
+    Tree::Num(Number::Int(n)) if b.real && (!n.is_negative() || b.is_nonzero()) => {
+      let even = n.clone().rem_euclid(Integer::TWO) == Integer::ZERO;
+      let sign = [(NEG, if even { POS } else { NEG }), (ZERO, ZERO), (POS, POS)]
+        .iter()
+        .filter(|(s, _)| b.sign & s != 0)
+        .fold(0, |acc, (_, t)| acc | t);
+      // ```x^n``` is increasing on nonnegative values
+      let pow = |q: Rational| u64::try_from(n.clone()).ok().map(|k| Rational::new(q.num.pow(k), q.den.pow(k)));
+      let (lo, hi) = match b.is_nonnegative() {
+        true => (b.lo.and_then(pow), b.hi.and_then(pow)),
+        false => (None, None),
+      };
+      Facts {
+        sign,
+        real: true,
+        integer: b.integer && !n.is_negative(),
+        lo,
+        hi,
+      }
+    }
+    e if b.is_positive() && e.facts().real => Facts::real(POS),
+    e if b.is_nonnegative() && e.facts().is_positive() => Facts::real(ZERO | POS),
+    _ => Facts::UNKNOWN,
+  }
+}
+
+fn mul(l: Facts, r: Facts) -> Facts {
+  let real = l.real && r.real;
+  let (lo, hi) = match real {
+    true => {
+      let corners = [
+        (&l.lo, &r.lo, Ordering::Less, Ordering::Less),
+        (&l.lo, &r.hi, Ordering::Less, Ordering::Greater),
+        (&l.hi, &r.lo, Ordering::Greater, Ordering::Less),
+        (&l.hi, &r.hi, Ordering::Greater, Ordering::Greater),
+      ];
+      let prod: Vec<_> = corners
+        .into_iter()
+        .map(|(x, y, sx, sy)| Ext::mul(Ext::from_bound(x.clone(), sx), Ext::from_bound(y.clone(), sy)))
+        .collect();
+      let lo = prod.iter().min().cloned().and_then(Ext::finite);
+      let hi = prod.into_iter().max().and_then(Ext::finite);
+      (lo, hi)
+    }
+    false => (None, None),
+  };
+
+  Facts {
+    sign: lift(l.sign, r.sign, |s, t| sign_bit((s * t).cmp(&0))),
+    real,
+    integer: false,
+    lo,
+    hi,
+  }
+}
+
+fn bounded(lo: Option<i64>, hi: Option<i64>) -> Facts {
+  let lo = lo.map(|lo| Rational::from(Integer::from(lo)));
+  let hi = hi.map(|hi| Rational::from(Integer::from(hi)));
+  Facts { lo, hi, ..Facts::real(ALL) }
+}
+
+fn within(lo: (i64, i64), hi: (i64, i64)) -> Facts {
+  let q = |(n, d)| Rational::new(Integer::from(n), Integer::from(d));
+  Facts {
+    lo: Some(q(lo)),
+    hi: Some(q(hi)),
+    ..Facts::real(ALL)
+  }
+}
+
+fn sign_bit(sgn: Ordering) -> u8 {
+  match sgn {
+    Ordering::Less => NEG,
+    Ordering::Equal => ZERO,
+    Ordering::Greater => POS,
+  }
+}
+
+// Possible signs of ```s + t```.
+fn add_sign(s: i8, t: i8) -> u8 {
+  match (s, t) {
+    (s, 0) => sign_bit(s.cmp(&0)),
+    (0, t) => sign_bit(t.cmp(&0)),
+    (s, t) if s == t => sign_bit(s.cmp(&0)),
+    _ => ALL,
+  }
+}
+
+// Apply `op` to every pair of possible signs.
+fn lift<F>(l: u8, r: u8, op: F) -> u8
+where
+  F: Fn(i8, i8) -> u8,
+{
+  let bits = [(-1, NEG), (0, ZERO), (1, POS)];
+  bits
+    .iter()
+    .filter(|(_, b)| l & b != 0)
+    .fold(0, |acc, &(s, _)| bits.iter().filter(|(_, b)| r & b != 0).fold(acc, |acc, &(t, _)| acc | op(s, t)))
+}
+
+// Combine bounds `l` and `r` on side `side`, both finite.
+fn ext_op<F>(l: Option<Rational>, r: Option<Rational>, side: Ordering, op: F) -> Option<Rational>
+where
+  F: Fn(Ext, Ext) -> Ext,
+{
+  op(Ext::from_bound(l, side), Ext::from_bound(r, side)).finite()
+}
+
+// An extended rational bound.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Ext {
+  NegInf,
+  Fin(Rational),
+  PosInf,
+}
+
+impl Ext {
+  // A missing bound on `side` is infinite.
+  fn from_bound(q: Option<Rational>, side: Ordering) -> Ext {
+    match (q, side) {
+      (Some(q), _) => Ext::Fin(q),
+      (None, Ordering::Less) => Ext::NegInf,
+      (None, _) => Ext::PosInf,
+    }
+  }
+
+  fn finite(self) -> Option<Rational> {
+    match self {
+      Ext::Fin(q) => Some(q),
+      _ => None,
+    }
+  }
+
+  fn sgn(&self) -> Ordering {
+    match self {
+      Ext::NegInf => Ordering::Less,
+      Ext::Fin(q) => q.cmp(&Rational::from(Integer::ZERO)),
+      Ext::PosInf => Ordering::Greater,
+    }
+  }
+
+  fn add(self, o: Ext) -> Ext {
+    match (self, o) {
+      (Ext::Fin(l), Ext::Fin(r)) => Ext::Fin(l + r),
+      // only bounds of a same side are added
+      (Ext::Fin(_), inf) | (inf, _) => inf,
+    }
+  }
+
+  // ```0*∞ = 0``` on closed bounds.
+  fn mul(self, o: Ext) -> Ext {
+    match (self, o) {
+      (Ext::Fin(l), Ext::Fin(r)) => Ext::Fin(l * r),
+      (l, r) if l.sgn().is_eq() || r.sgn().is_eq() => Ext::Fin(Rational::from(Integer::ZERO)),
+      (l, r) if l.sgn() == r.sgn() => Ext::PosInf,
+      _ => Ext::NegInf,
+    }
+  }
+}
@@ -0,0 +1,251 @@
+//! Bounded, cancellable and stack-safe simplification.
+//!
+//! [`Expr::trivial`](crate::Expr::trivial) simplifies the subexpressions of a tree from an explicit work stack, the
+//! children before their parent. The rules of a node then find the simplified children in a table instead of
+//! recursing into them, so that the depth of the tree is not bounded by the depth of the call stack. The
+//! [`Simplifier`](crate::Simplifier), rewriting, substitution, numerical evaluation and the zero test traverse trees
+//! the same way, while [`Cse`](crate::Cse) and the [`Visitor`](crate::Visitor) and [`Fold`](crate::Fold)
+//! traversals still recurse.
+//!
+//! A [`Budget`] bounds a computation: the number of its simplification and evaluation steps, the elapsed time, and a
+//! cancellation flag shared with other threads. Once it is exhausted, simplifications fail with a [`Form`] carrying
+//! the reason as a [`Halt`], evaluations fail, expansions and factorizations stop, so that the computation unwinds
+//! quickly, and the budget reports the reason.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{Form, SymbolicResult, Tree};
+
+// Number of steps between two checks of the clock.
+const CLOCK_STEPS: u64 = 64;
+
+/// The reason a bounded computation stopped.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, PartialOrd, Eq, Ord)]
+pub enum Halt {
+  /// The result is an indeterminate form.
+  Form,
+  /// The step budget is exhausted.
+  Steps,
+  /// The time budget is exhausted.
+  Time,
+  /// The computation was cancelled.
+  Cancelled,
+}
+
+/// A cancellation flag, shared between the threads holding a copy.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+/// The limits of a bounded computation.
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+  steps: Option<u64>,
+  time: Option<Duration>,
+  cancel: Cancel,
+}
+
+// The budget of the running computation.
+struct State {
+  steps: u64,
+  max_steps: Option<u64>,
+  deadline: Option<Instant>,
+  cancel: Cancel,
+  halt: Option<Halt>,
+}
+
+// Restores the budget of the enclosing computation when dropped.
+struct Restore(Option<State>);
+
+thread_local! {
+  static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+  // Simplified subexpressions of the running simplifications.
+  static DONE: RefCell<HashMap<Tree, SymbolicResult<Tree>>> = RefCell::new(HashMap::new());
+}
+
+impl fmt::Display for Halt {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Halt::Form => write!(f, "?"),
+      Halt::Steps => write!(f, "step budget exhausted"),
+      Halt::Time => write!(f, "time budget exhausted"),
+      Halt::Cancelled => write!(f, "cancelled"),
+    }
+  }
+}
+
+impl From<Form> for Halt {
+  fn from(form: Form) -> Self {
+    form.halt().unwrap_or(Halt::Form)
+  }
+}
+
+impl Cancel {
+  /// Create a flag, not raised.
+  pub fn new() -> Cancel {
+    Cancel::default()
+  }
+
+  /// Raise the flag, stopping the computations holding it.
+  pub fn cancel(&self) {
+    self.0.store(true, Ordering::Relaxed);
+  }
+
+  /// Return `true` if the flag is raised.
+  pub fn is_cancelled(&self) -> bool {
+    self.0.load(Ordering::Relaxed)
+  }
+}
+
+impl Budget {
+  /// Create a budget without step and time limits.
+  pub fn new() -> Budget {
+    Budget::default()
+  }
+
+  /// Limit the number of simplification and evaluation steps.
+  pub fn steps(mut self, steps: u64) -> Budget {
+    self.steps = Some(steps);
+    self
+  }
+
+  /// Limit the elapsed time.
+  pub fn time(mut self, time: Duration) -> Budget {
+    self.time = Some(time);
+    self
+  }
+
+  /// Stop the computation when `cancel` is raised.
+  pub fn cancel(mut self, cancel: &Cancel) -> Budget {
+    self.cancel = cancel.clone();
+    self
+  }
+
+  /// Run `f` within the budget, or return the reason it was exhausted. A budget nested in another one replaces it
+  /// until `f` returns.
+  pub fn run<T, F>(&self, f: F) -> Result<T, Halt>
+  where
+    F: FnOnce() -> T,
+  {
+    let state = State {
+      steps: 0,
+      max_steps: self.steps,
+      deadline: self.time.map(|time| Instant::now() + time),
+      cancel: self.cancel.clone(),
+      halt: None,
+    };
+
+    let restore = Restore(STATE.with(|s| s.replace(Some(state))));
+    let out = f();
+    let halt = STATE.with(|s| s.borrow().as_ref().and_then(|s| s.halt));
+    drop(restore);
+
+    match halt {
+      Some(halt) => Err(halt),
+      None => Ok(out),
+    }
+  }
+
+  /// Simplify `expr` trivially within the budget.
+  pub fn trivial(&self, expr: Tree) -> Result<Tree, Halt> {
+    use crate::Expr;
+
+    self.run(|| expr.trivial())?.map_err(Halt::from)
+  }
+}
+
+impl State {
+  fn check(&mut self) -> Option<Halt> {
+    self.steps += 1;
+    if self.cancel.is_cancelled() {
+      Some(Halt::Cancelled)
+    } else if self.max_steps.is_some_and(|max| self.steps > max) {
+      Some(Halt::Steps)
+    } else if self.steps.is_multiple_of(CLOCK_STEPS) && self.deadline.is_some_and(|deadline| Instant::now() > deadline) {
+      Some(Halt::Time)
+    } else {
+      None
+    }
+  }
+}
+
+impl Drop for Restore {
+  fn drop(&mut self) {
+    STATE.with(|s| *s.borrow_mut() = self.0.take());
+  }
+}
+
+/// Take a step of the running computation, or fail once its budget is exhausted.
+pub(crate) fn step() -> SymbolicResult<()> {
+  STATE.with(|s| match s.borrow_mut().as_mut() {
+    None => Ok(()),
+    Some(state) => {
+      if state.halt.is_none() {
+        state.halt = state.check();
+      }
+      state.halt.map_or(Ok(()), |halt| Err(Form::halted(halt)))
+    }
+  })
+}
+
+/// Simplify `tree` with `node`, which simplifies a single node and finds its children already simplified.
+pub(crate) fn simplify(tree: Tree, node: fn(Tree) -> SymbolicResult<Tree>) -> SymbolicResult<Tree> {
+  let cached = |expr: &Tree| DONE.with(|done| done.borrow().get(expr).cloned());
+  if let Some(out) = cached(&tree) {
+    return out;
+  }
+
+  // subexpressions simplified by this call, forgotten once it returns
+  let mut simplified = vec![];
+  let mut stack = vec![(tree.clone(), false)];
+  while let Some((expr, ready)) = stack.pop() {
+    if DONE.with(|done| done.borrow().contains_key(&expr)) {
+      continue;
+    }
+
+    if ready {
+      let out = step().and_then(|()| node(expr.clone()));
+      DONE.with(|done| done.borrow_mut().insert(expr.clone(), out));
+      simplified.push(expr);
+    } else {
+      let children: Vec<Tree> = expr.children().into_iter().cloned().collect();
+      stack.push((expr, true));
+      stack.extend(children.into_iter().map(|e| (e, false)));
+    }
+  }
+
+  let out = cached(&tree).unwrap_or(Err(Form::default()));
+  DONE.with(|done| {
+    let mut done = done.borrow_mut();
+    simplified.iter().for_each(|expr| {
+      done.remove(expr);
+    });
+  });
+  out
+}
+
+/// Simplify the single node `tree` with `node`, its children being already simplified.
+pub(crate) fn simplify_node(tree: Tree, node: fn(Tree) -> SymbolicResult<Tree>) -> SymbolicResult<Tree> {
+  // children marked as simplified for this call only
+  let marked: Vec<Tree> = DONE.with(|done| {
+    let mut done = done.borrow_mut();
+    let children = tree.children().into_iter().filter(|e| **e != Tree::Form && !done.contains_key(*e)).cloned().collect::<Vec<_>>();
+    children.iter().for_each(|e| {
+      done.insert(e.clone(), Ok(e.clone()));
+    });
+    children
+  });
+
+  let out = simplify(tree, node);
+  DONE.with(|done| {
+    let mut done = done.borrow_mut();
+    marked.iter().for_each(|expr| {
+      done.remove(expr);
+    });
+  });
+  out
+}
@@ -1,11 +1,12 @@
 use std::fmt;
 
-use crate::{Edge, Expr, Tree};
+use crate::{Constant, Edge, Expr, Tree};
 use crate::{Symbol, SymbolicResult};
 
 use crate::base::{
   alg::{AOp, Algebra, Assoc, BOp},
-  fun::{EOp, Function},
+  comb,
+  fun::{COp, EOp, Function},
 };
 
 /// A list of calculus operators.
@@ -161,6 +162,24 @@ impl Calculus {
         Tree::chain_rule(comp, diff, part)?.trivial()
       }
 
+      Tree::Fun(Function::CombExpr {
+        //.
+        map: map @ (COp::Fibonacci | COp::Lucas),
+        arg,
+      }) if arg.len() == 1 => {
+        let x = arg[0].clone();
+        let phi = comb::golden_ratio();
+        let osc = Tree::Cte(Constant::pi).mul(Tree::Cte(Constant::pi).mul(x.clone()).sin()).mul(phi.clone().pow(x.clone().neg()));
+        let diff = match map {
+          // ```∂F(f)/∂x = (L(f)*log(φ) + π*sin(π*f)*φ^(-f))/sqrt(5)```
+          COp::Fibonacci => x.clone().lucas().mul(phi.log()).add(osc).div(Tree::from(5).sqrt()),
+          // ```∂L(f)/∂x = sqrt(5)*F(f)*log(φ) - π*sin(π*f)*φ^(-f)```
+          _ => Tree::from(5).sqrt().mul(x.clone().fibonacci()).mul(phi.log()).sub(osc),
+        };
+
+        Tree::chain_rule(x, diff, part)?.trivial()
+      }
+
       Tree::Cal(Calculus {
         //.
         map: CalOp::Der,
@@ -0,0 +1,133 @@
+//! Canonical order and forms.
+//!
+//! The structural order is a total order on trees, consistent with their equality:
+//! * by variant: numbers, constants, symbols, algebraic, functional, calculus and sequential operators, forms,
+//! * by operator: its kind, then its map, name or bound variables,
+//! * lexicographically by children.
+//!
+//! It breaks the ties of the polynomial order of [`Tree`], so that sorting the terms of sums and products gives a unique
+//! result. The canonical form of an expression flattens its nested sums and products and sorts their terms, so that
+//! two expressions equal up to associativity and commutativity have the same canonical form.
+
+use std::cmp::Ordering;
+
+use crate::base::alg::{Algebra, Assoc};
+use crate::base::algebra::Constant;
+use crate::base::traverse::{fold_children, Fold};
+use crate::base::{Function, Special};
+use crate::{Edge, Tree};
+
+impl Tree {
+  /// Compare two trees by structure, only equal trees being equivalent.
+  pub fn cmp_structural(&self, o: &Self) -> Ordering {
+    self.rank().cmp(&o.rank()).then_with(|| self.cmp_head(o)).then_with(|| {
+      let (lhs, rhs) = (self.children(), o.children());
+      lhs
+        .iter()
+        .zip(&rhs)
+        .map(|(l, r)| l.cmp_structural(r))
+        .find(|ord| ord.is_ne())
+        .unwrap_or_else(|| lhs.len().cmp(&rhs.len()))
+    })
+  }
+
+  /// Return the canonical form of the expression: nested sums and products are flattened, and their terms sorted by
+  /// structure.
+  pub fn canonical(&self) -> Tree {
+    Canonical.fold(self.clone())
+  }
+
+  /// Check whether two expressions are equal up to the associativity and commutativity of sums and products.
+  pub fn equivalent(&self, o: &Self) -> bool {
+    self == o || self.canonical() == o.canonical()
+  }
+
+  // Variant, then kind of operator.
+  fn rank(&self) -> (u8, u8) {
+    match self {
+      Tree::Num(_) => (0, 0),
+      Tree::Cte(cte) => (
+        1,
+        match cte {
+          Constant::i => 0,
+          Constant::pi => 1,
+          Constant::e => 2,
+          Constant::Infinity(_) => 3,
+        },
+      ),
+      Tree::Sym(_) => (2, 0),
+      Tree::Alg(alg) => (
+        3,
+        match alg {
+          Algebra::UExpr { .. } => 0,
+          Algebra::BExpr { .. } => 1,
+          Algebra::AssocExpr(_) => 2,
+        },
+      ),
+      Tree::Fun(fun) => (
+        4,
+        match fun {
+          Function::ElemExpr { .. } => 0,
+          Function::SpecExpr(_) => 1,
+          Function::CombExpr { .. } => 2,
+          Function::MapExpr { .. } => 3,
+        },
+      ),
+      Tree::Cal(_) => (5, 0),
+      Tree::Sq(_) => (6, 0),
+      Tree::Form => (7, 0),
+    }
+  }
+
+  // Compare the nodes of same rank, without their children.
+  fn cmp_head(&self, o: &Self) -> Ordering {
+    match (self, o) {
+      (Tree::Num(l), Tree::Num(r)) => l.cmp(r),
+      (Tree::Cte(Constant::Infinity(l)), Tree::Cte(Constant::Infinity(r))) => l.cmp(r),
+      (Tree::Sym(l), Tree::Sym(r)) => l.cmp(r),
+      (Tree::Alg(Algebra::UExpr { map: l, .. }), Tree::Alg(Algebra::UExpr { map: r, .. })) => l.cmp(r),
+      (Tree::Alg(Algebra::BExpr { map: l, .. }), Tree::Alg(Algebra::BExpr { map: r, .. })) => l.cmp(r),
+      (Tree::Alg(Algebra::AssocExpr(Assoc { map: l, .. })), Tree::Alg(Algebra::AssocExpr(Assoc { map: r, .. }))) => l.cmp(r),
+      (Tree::Fun(Function::ElemExpr { map: l, .. }), Tree::Fun(Function::ElemExpr { map: r, .. })) => l.cmp(r),
+      (Tree::Fun(Function::SpecExpr(l)), Tree::Fun(Function::SpecExpr(r))) => special_rank(l).cmp(&special_rank(r)),
+      (Tree::Fun(Function::CombExpr { map: l, .. }), Tree::Fun(Function::CombExpr { map: r, .. })) => l.cmp(r),
+      (Tree::Fun(Function::MapExpr { map: l, .. }), Tree::Fun(Function::MapExpr { map: r, .. })) => l.cmp(r),
+      (Tree::Cal(l), Tree::Cal(r)) => l.map.cmp(&r.map).then_with(|| l.var.cmp(&r.var)),
+      (Tree::Sq(l), Tree::Sq(r)) => l.map.cmp(&r.map).then_with(|| l.idx.cmp(&r.idx)),
+      _ => Ordering::Equal,
+    }
+  }
+}
+
+fn special_rank(spec: &Special) -> u8 {
+  match spec {
+    Special::Gamma(_) => 0,
+    Special::Floor(_) => 1,
+    Special::Ceil(_) => 2,
+    Special::Round(_) => 3,
+    Special::Frac(_) => 4,
+    Special::Mod(..) => 5,
+  }
+}
+
+// Flatten sums and products, and sort their terms.
+struct Canonical;
+
+impl Fold for Canonical {
+  fn fold_alg(&mut self, alg: Algebra) -> Tree {
+    match fold_children(self, Tree::Alg(alg)) {
+      Tree::Alg(Algebra::AssocExpr(Assoc { map, arg })) => {
+        let mut flat: Vec<Edge> = arg.into_iter().fold(vec![], |mut acc, e| {
+          match e.as_ref() {
+            Tree::Alg(Algebra::AssocExpr(Assoc { map: sub_map, arg })) if *sub_map == map => acc.extend(arg.iter().cloned()),
+            _ => acc.push(e),
+          }
+          acc
+        });
+        flat.sort_by(|l, r| l.cmp_structural(r));
+        Tree::Alg(Algebra::AssocExpr(Assoc { map, arg: flat }))
+      }
+      tree => tree,
+    }
+  }
+}
@@ -0,0 +1,258 @@
+//! Combinatorial and number-theoretic functions.
+//!
+//! * Exact evaluation on integer arguments: ```(5 2) = 10```, ```F_-6 = -8```, ```B_4 = -1/30```, ```μ(30) = -1```.
+//! * Identities on symbolic arguments: ```(n 1) = n```, ```multinomial(a, b) = (a + b a)```, ```L_-n = (-1)^n*L_n```.
+//! * The analytic continuation of the Fibonacci and Lucas numbers, which gives their derivatives and numeric values.
+
+use crate::base::alg::{AOp, Algebra, Assoc};
+use crate::base::fun::{COp, Function};
+use crate::{Constant, Edge, Expr, Form, Integer, Natural, Number, Rational, SymbolicResult, Tree};
+
+// Largest index evaluated by linear recurrences (binomial, multinomial, Fibonacci, Lucas, Catalan).
+const MAX_LINEAR: u64 = 1 << 16;
+// Largest index evaluated by quadratic tables (Bernoulli, Euler, Stirling, Bell, partitions).
+const MAX_TABLE: u64 = 1 << 9;
+// Largest argument factored by trial division (divisor function, Möbius).
+const MAX_FACTOR: u64 = 1 << 40;
+
+impl Function {
+  pub(crate) fn comb_trivial(map: COp, arg: Vec<Edge>) -> SymbolicResult<Tree> {
+    if map.arity().is_some_and(|n| n != arg.len()) {
+      return Err(Form::default());
+    }
+    if arg.iter().any(|e| matches!(e.as_ref(), Tree::Num(Number::Cpx(_)) | Tree::Cte(Constant::Infinity(_)))) {
+      return Err(Form::default());
+    }
+
+    let int: Option<Vec<Integer>> = arg
+      .iter()
+      .map(|e| match e.as_ref() {
+        Tree::Num(Number::Int(z)) => Some(z.clone()),
+        _ => None,
+      })
+      .collect();
+    if let Some(value) = int.map(|z| comb_eval(map, &z)).transpose()?.flatten() {
+      return value.trivial();
+    }
+
+    match (map, arg.as_slice()) {
+      // ```(x 0) = 1```
+      // ```(x 1) = x```
+      (COp::Binomial, [_, k]) if **k == Tree::ZERO => Ok(Tree::from(1)),
+      (COp::Binomial, [x, k]) if **k == Tree::ONE => Ok(Tree::from(x.clone())),
+      // ```(q k) = q*(q - 1)*...*(q - k + 1)/k!```
+      (COp::Binomial, [x, k]) => match (x.as_ref(), k.as_ref()) {
+        (Tree::Num(Number::Rat(q)), Tree::Num(Number::Int(k))) if !k.is_negative() && k <= &Integer::from(MAX_LINEAR) => {
+          let mut b = Rational::from(Integer::ONE);
+          let mut i = Integer::ZERO;
+          while &i < k {
+            let j = Rational::from(i.clone());
+            i.incr();
+            b = (b * (q.clone() - j) / Rational::from(i.clone())).reduce();
+          }
+          Tree::from(b).trivial()
+        }
+        // ```(n n) = 1```, ```n ∈ ℕ```
+        _ if x == k && natural(x) => Ok(Tree::from(1)),
+        _ => Ok(Tree::comb(map, arg)),
+      },
+
+      // ```s(n, n) = S(n, n) = 1```, ```n ∈ ℕ```
+      (COp::Stirling1 | COp::Stirling2, [n, k]) if n == k && natural(n) => Ok(Tree::from(1)),
+
+      // ```multinomial(k_1, ..., 0, ..., k_m) = multinomial(k_1, ..., k_m)```
+      (COp::Multinomial, arg) if arg.iter().any(|e| **e == Tree::ZERO) => {
+        let arg = arg.iter().filter(|e| ***e != Tree::ZERO).cloned().collect();
+        Function::comb_trivial(map, arg)
+      }
+      // ```multinomial(k) = 1```
+      // ```multinomial(a, b) = (a + b a)```
+      (COp::Multinomial, [] | [_]) => Ok(Tree::from(1)),
+      (COp::Multinomial, [a, b]) => a.clone().add(b.clone()).binomial(a.clone()).trivial(),
+
+      // ```F_-n = (-1)^(n + 1)*F_n```
+      // ```L_-n = (-1)^n*L_n```
+      (COp::Fibonacci | COp::Lucas, [x]) => match negated(x) {
+        Some(n) if Function::integral(&n) => {
+          let exp = if map == COp::Fibonacci { n.clone().add(Tree::from(1)) } else { n.clone() };
+          Tree::from(-1).pow(exp).mul(Tree::comb(map, vec![n.edge()])).trivial()
+        }
+        _ => Ok(Tree::comb(map, arg)),
+      },
+
+      _ => Ok(Tree::comb(map, arg)),
+    }
+  }
+}
+
+/// The golden ratio ```φ = (1 + sqrt(5))/2```.
+pub(crate) fn golden_ratio() -> Tree {
+  Tree::from(1).add(Tree::from(5).sqrt()).div(Tree::from(2))
+}
+
+/// The analytic continuation of the Fibonacci and Lucas numbers at `x`.
+///
+/// ```F_x = (φ^x - cos(π*x)*φ^(-x))/sqrt(5)```
+/// ```L_x = φ^x + cos(π*x)*φ^(-x)```
+pub(crate) fn continuation(map: COp, x: &Edge) -> Option<Tree> {
+  let phi = golden_ratio();
+  let osc = Tree::Cte(Constant::pi).mul(x.clone()).cos().mul(phi.clone().pow(x.clone().neg()));
+  match map {
+    COp::Fibonacci => Some(phi.pow(x.clone()).sub(osc).div(Tree::from(5).sqrt())),
+    COp::Lucas => Some(phi.pow(x.clone()).add(osc)),
+    _ => None,
+  }
+}
+
+// Value at integer arguments, `None` when out of the evaluated range.
+fn comb_eval(map: COp, z: &[Integer]) -> SymbolicResult<Option<Tree>> {
+  let index = |z: &Integer, max: u64| u64::try_from(z.clone()).ok().filter(|n| *n <= max);
+  let to_natural = |z: &Integer| Natural::try_from(z.clone()).map_err(|_| Form::default());
+
+  Ok(match (map, z) {
+    (COp::Binomial, [n, k]) if k.is_negative() || (!n.is_negative() && k > n) => Some(Tree::from(0)),
+    (COp::Binomial, [n, k]) if !n.is_negative() => {
+      // ```(n k) = (n n - k)```
+      let j = k.clone().min(n.clone() - k.clone());
+      match index(&j, MAX_LINEAR) {
+        Some(_) => Some(Tree::from(Natural::binomial(to_natural(n)?, to_natural(&j)?))),
+        None => None,
+      }
+    }
+    // ```(-n k) = (-1)^k*(n + k - 1 k)```
+    (COp::Binomial, [n, k]) => index(k, MAX_LINEAR).map(|i| {
+      let b = Integer::from(Natural::binomial((k.clone() - n.clone() - Integer::ONE).abs(), k.clone().abs()));
+      Tree::from(if i % 2 == 1 { -b } else { b })
+    }),
+
+    (COp::Multinomial, k) => {
+      let k: Vec<_> = k.iter().map(to_natural).collect::<Result<_, _>>()?;
+      let sum = k.iter().fold(Natural::ZERO, |acc, k| acc + k.clone());
+      (sum <= Natural::from(MAX_LINEAR)).then(|| Tree::from(Natural::multinomial(&k)))
+    }
+
+    (COp::Fibonacci | COp::Lucas, [n]) => index(&Integer::from(n.clone().abs()), MAX_LINEAR).map(|i| {
+      let (value, flip) = match map {
+        COp::Fibonacci => (Natural::fibonacci(i), i % 2 == 0),
+        _ => (Natural::lucas(i), i % 2 == 1),
+      };
+      let value = Integer::from(value);
+      Tree::from(if n.is_negative() && flip { -value } else { value })
+    }),
+
+    (COp::Bernoulli | COp::Euler | COp::Bell | COp::Catalan, [n]) if n.is_negative() => return Err(Form::default()),
+    (COp::Bernoulli, [n]) => index(n, MAX_TABLE).map(|n| Tree::from(Rational::bernoulli(n))),
+    (COp::Euler, [n]) => index(n, MAX_TABLE).map(|n| Tree::from(Integer::euler(n))),
+    (COp::Bell, [n]) => index(n, MAX_TABLE).map(|n| Tree::from(Natural::bell(n))),
+    (COp::Catalan, [n]) => index(n, MAX_LINEAR).map(|n| Tree::from(Natural::catalan(n))),
+    // ```p(n) = 0, n < 0```
+    (COp::Partition, [n]) if n.is_negative() => Some(Tree::from(0)),
+    (COp::Partition, [n]) => index(n, MAX_TABLE).map(|n| Tree::from(Natural::partition(n))),
+
+    (COp::Stirling1 | COp::Stirling2, [n, k]) if n.is_negative() || k.is_negative() => return Err(Form::default()),
+    (COp::Stirling1 | COp::Stirling2, [n, k]) if k > n => Some(Tree::from(0)),
+    (COp::Stirling1, [n, k]) => index(n, MAX_TABLE).map(|n| Tree::from(Integer::stirling1(n, u64::try_from(k.clone()).expect("k <= n")))),
+    (COp::Stirling2, [n, k]) => index(n, MAX_TABLE).map(|n| Tree::from(Natural::stirling2(n, u64::try_from(k.clone()).expect("k <= n")))),
+
+    (COp::DivisorSigma | COp::Mobius, [.., n]) if n.is_negative() || n == &Integer::ZERO => return Err(Form::default()),
+    (COp::DivisorSigma, [k, n]) => match (index(&Integer::from(k.clone().abs()), MAX_TABLE), index(n, MAX_FACTOR)) {
+      (Some(j), Some(_)) => {
+        let sigma = Integer::from(Natural::divisor_sigma(j, to_natural(n)?));
+        // ```σ_-k(n) = σ_k(n)/n^k```
+        Some(if k.is_negative() { Tree::from(Rational::new(sigma, n.clone().pow(j))) } else { Tree::from(sigma) })
+      }
+      _ => None,
+    },
+    (COp::Mobius, [n]) => match index(n, MAX_FACTOR) {
+      Some(_) => Some(Tree::from(Integer::mobius(to_natural(n)?))),
+      None => None,
+    },
+
+    _ => None,
+  })
+}
+
+// Test if the expression is a natural number or a symbol over ```ℕ```.
+fn natural(e: &Tree) -> bool {
+  match e {
+    Tree::Num(Number::Int(z)) => !z.is_negative(),
+    Tree::Sym(s) => s.dom == Number::N,
+    _ => false,
+  }
+}
+
+// ```x``` from ```-x```.
+fn negated(e: &Tree) -> Option<Tree> {
+  let Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Mul, arg })) = e else {
+    return None;
+  };
+  let i = arg.iter().position(|e| **e == Tree::NEG_ONE)?;
+  let mut arg = arg.clone();
+  arg.remove(i);
+  Some(Tree::assoc(AOp::Mul, arg))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Symbol;
+
+  fn seq<F: Fn(Tree) -> Tree>(f: F, n: std::ops::Range<i64>) -> Vec<Tree> {
+    n.map(|i| f(Tree::from(i)).trivial().expect("evaluation")).collect()
+  }
+
+  fn ints(v: &[i64]) -> Vec<Tree> {
+    v.iter().map(|&z| Tree::from(z)).collect()
+  }
+
+  #[test]
+  fn evaluation() {
+    assert_eq!(seq(Tree::fibonacci, -4..8), ints(&[-3, 2, -1, 1, 0, 1, 1, 2, 3, 5, 8, 13]));
+    assert_eq!(seq(Tree::lucas, -3..6), ints(&[-4, 3, -1, 2, 1, 3, 4, 7, 11]));
+    assert_eq!(seq(Tree::euler, 0..9), ints(&[1, 0, -1, 0, 5, 0, -61, 0, 1385]));
+    assert_eq!(seq(Tree::bell, 0..8), ints(&[1, 1, 2, 5, 15, 52, 203, 877]));
+    assert_eq!(seq(Tree::catalan, 0..8), ints(&[1, 1, 2, 5, 14, 42, 132, 429]));
+    assert_eq!(seq(Tree::partition, -1..10), ints(&[0, 1, 1, 2, 3, 5, 7, 11, 15, 22, 30]));
+    assert_eq!(seq(Tree::mobius, 1..11), ints(&[1, -1, -1, 0, -1, 1, -1, 0, 0, 1]));
+    assert_eq!(seq(|n| Tree::from(1).divisor_sigma(n), 1..9), ints(&[1, 3, 4, 7, 6, 12, 8, 15]));
+    assert_eq!(seq(|n| Tree::from(5).stirling1(n), 0..6), ints(&[0, 24, -50, 35, -10, 1]));
+    assert_eq!(seq(|n| Tree::from(5).stirling2(n), 0..6), ints(&[0, 1, 15, 25, 10, 1]));
+    assert_eq!(seq(|k| Tree::from(-4).binomial(k), -1..4), ints(&[0, 1, -4, 10, -20]));
+    let b: Vec<_> = [(1, 1), (-1, 2), (1, 6), (0, 1), (-1, 30)]
+      .iter()
+      .map(|&(n, d)| Tree::from(Rational::new(Integer::from(n), Integer::from(d))).trivial().expect("a rational"))
+      .collect();
+    assert_eq!(seq(Tree::bernoulli, 0..5), b);
+
+    assert_eq!(Tree::multinomial(ints(&[2, 3, 4])).trivial(), Ok(Tree::from(1260)));
+    assert_eq!(Tree::from(100).fibonacci().trivial(), Ok(Tree::from(354224848179261915075u128)));
+    assert_eq!(Tree::from(0).mobius().trivial(), Err(Form::default()));
+    assert_eq!(Tree::from(-1).bell().trivial(), Err(Form::default()));
+  }
+
+  #[test]
+  fn identities() -> Result<(), Form> {
+    let x = Tree::Sym(Symbol::new("x", Number::R).expect("failed to declare symbol `x`"));
+    let n = Tree::Sym(Symbol::new("n", Number::N).expect("failed to declare symbol `n`"));
+    assert_eq!(x.clone().binomial(Tree::from(1)).trivial()?, x);
+    assert_eq!(n.clone().binomial(n.clone()).trivial()?, Tree::from(1));
+    assert_eq!(x.clone().binomial(x.clone()).trivial()?, x.clone().binomial(x.clone()));
+    assert_eq!(n.clone().stirling2(n.clone()).trivial()?, Tree::from(1));
+    assert_eq!(
+      Tree::multinomial(vec![x.clone(), Tree::from(0), n.clone()]).trivial()?,
+      x.clone().add(n.clone()).binomial(x.clone()).trivial()?
+    );
+    // ```L_-n = (-1)^n*L_n```
+    assert_eq!(n.clone().neg().lucas().trivial()?, Tree::from(-1).pow(n.clone()).mul(n.clone().lucas()).trivial()?);
+    assert!(matches!(x.clone().neg().lucas().trivial()?, Tree::Fun(Function::CombExpr { map: COp::Lucas, .. })));
+
+    // ```F_x``` between integers, and ```F'(x)``` at ```x = 2```: ```(3*log(φ) + 0)/sqrt(5)```
+    let f = x.clone().fibonacci();
+    assert!(Tree::from(Rational::new(Integer::ONE, Integer::TWO)).fibonacci().evalf(10)?.to_string().starts_with("0.568864481"));
+    let mut df = f.derivative(vec![Symbol::try_from(x)?]).trivial()?;
+    df.subs(&Tree::Sym(Symbol::new("x", Number::R).expect("failed to declare symbol `x`")), &Tree::from(2));
+    let expected = Tree::from(3).mul(golden_ratio().log()).div(Tree::from(5).sqrt());
+    assert_eq!(df.evalf(15)?, expected.evalf(15)?);
+    Ok(())
+  }
+}
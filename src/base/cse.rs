@@ -0,0 +1,147 @@
+//! Common subexpression elimination.
+//!
+//! Every subexpression occurring more than once is bound to a temporary symbol, defined in terms of the previous
+//! ones, so that ```sin(x + 1)^2 + sin(x + 1)*(x + 1)``` becomes
+//! ```text
+//! t_1 = x + 1
+//! t_2 = sin(t_1)
+//! t_2^2 + t_2*t_1
+//! ```
+//! Subexpressions depending on a variable bound by a sequence or a calculus operator are kept in its scope.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::base::traverse::{fold_children, walk_alg, walk_cal, walk_fun, walk_sq, Fold, Visitor};
+use crate::{Symbol, Tree};
+
+/// An expression reduced by common subexpression elimination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cse {
+  /// Temporaries, each defined in terms of the previous ones.
+  pub temps: Vec<(Symbol, Tree)>,
+  /// The expression in terms of the temporaries.
+  pub expr: Tree,
+}
+
+impl Cse {
+  /// Return the original expression, with the temporaries substituted back.
+  pub fn inline(&self) -> Tree {
+    self.temps.iter().rev().fold(self.expr.clone(), |mut expr, (sym, def)| {
+      expr.subs(&Tree::Sym(sym.clone()), def);
+      expr
+    })
+  }
+}
+
+impl fmt::Display for Cse {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (sym, def) in &self.temps {
+      writeln!(f, "{sym} = {def}")?;
+    }
+    write!(f, "{}", self.expr)
+  }
+}
+
+// Occurrences of the subexpressions, counting those of a repeated one once.
+#[derive(Default)]
+struct Count {
+  seen: HashMap<Tree, usize>,
+  scope: Vec<Symbol>,
+}
+
+// Replacement of the repeated subexpressions.
+struct Reduce<'c> {
+  repeated: HashSet<&'c Tree>,
+  temps: Vec<(Symbol, Tree)>,
+  names: HashMap<Tree, Symbol>,
+  used: HashSet<Arc<str>>,
+  scope: Vec<Symbol>,
+}
+
+impl Visitor for Count {
+  fn visit(&mut self, tree: &Tree) {
+    if !is_leaf(tree) && !in_scope(tree, &self.scope) {
+      let n = self.seen.entry(tree.clone()).or_default();
+      *n += 1;
+      if *n > 1 {
+        return;
+      }
+    }
+
+    match tree {
+      Tree::Alg(alg) => walk_alg(self, alg),
+      Tree::Fun(fun) => walk_fun(self, fun),
+      Tree::Cal(cal) => walk_cal(self, cal),
+      Tree::Sq(sq) => walk_sq(self, sq),
+      Tree::Sym(_) | Tree::Cte(_) | Tree::Num(_) | Tree::Form => {}
+    }
+  }
+
+  fn enter(&mut self, bound: &[Symbol]) {
+    self.scope.extend_from_slice(bound);
+  }
+  fn exit(&mut self, bound: &[Symbol]) {
+    self.scope.truncate(self.scope.len() - bound.len());
+  }
+}
+
+impl Fold for Reduce<'_> {
+  fn fold(&mut self, tree: Tree) -> Tree {
+    if !self.repeated.contains(&tree) || in_scope(&tree, &self.scope) {
+      return fold_children(self, tree);
+    }
+    if let Some(sym) = self.names.get(&tree) {
+      return Tree::Sym(sym.clone());
+    }
+
+    let def = fold_children(self, tree.clone());
+    let name = (1..).map(|i| format!("t_{i}")).find(|name| !self.used.contains(name.as_str())).expect("fresh name");
+    self.used.insert(Arc::from(name.as_str()));
+    let sym = Symbol::new(&name, tree.dom()).expect("failed to declare temporary");
+    self.names.insert(tree, sym.clone());
+    self.temps.push((sym.clone(), def));
+    Tree::Sym(sym)
+  }
+
+  fn enter(&mut self, bound: &[Symbol]) {
+    self.scope.extend_from_slice(bound);
+  }
+  fn exit(&mut self, bound: &[Symbol]) {
+    self.scope.truncate(self.scope.len() - bound.len());
+  }
+}
+
+fn is_leaf(tree: &Tree) -> bool {
+  matches!(tree, Tree::Sym(_) | Tree::Cte(_) | Tree::Num(_) | Tree::Form)
+}
+
+// Whether the subexpression depends on a bound variable.
+fn in_scope(tree: &Tree, scope: &[Symbol]) -> bool {
+  scope.iter().any(|var| !tree.free(&Tree::Sym(var.clone())))
+}
+
+impl Tree {
+  /// Bind the subexpressions occurring more than once to temporaries `t_1, t_2, ...`.
+  pub fn cse(&self) -> Cse {
+    let mut count = Count::default();
+    count.visit(self);
+
+    let mut reduce = Reduce {
+      repeated: count.seen.iter().filter(|(_, n)| **n > 1).map(|(e, _)| e).collect(),
+      temps: vec![],
+      names: HashMap::new(),
+      used: self
+        .pre_order()
+        .filter_map(|e| match e {
+          Tree::Sym(s) => Some(s.name.clone()),
+          _ => None,
+        })
+        .collect(),
+      scope: vec![],
+    };
+    let expr = reduce.fold(self.clone());
+    Cse { temps: reduce.temps, expr }
+  }
+}
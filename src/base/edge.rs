@@ -0,0 +1,260 @@
+//! Hash-consed expression edges.
+//!
+//! Every [`Edge`] is interned in a global table of weak references, so that structurally equal subexpressions share
+//! a single allocation and expressions form a DAG. Hashes are computed once, on interning, and equality of edges is
+//! decided by pointer in the common case, so that comparing or hashing a [`Tree`] only looks at its root.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::mem;
+use std::ops::Deref;
+use std::ptr;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError, Weak};
+
+use crate::base::algebra::Number;
+use crate::base::{Algebra, Assoc, Calculus, Function, Sequence, Special, Tree};
+
+// Minimum number of entries before the table is swept from its dead references.
+const MIN_SWEEP: usize = 1 << 10;
+
+static TABLE: LazyLock<Mutex<Table>> = LazyLock::new(|| {
+  Mutex::new(Table {
+    map: HashMap::new(),
+    len: 0,
+    sweep: MIN_SWEEP,
+  })
+});
+
+/// A recursive [`Tree`] expression, shared by all structurally equal subexpressions.
+#[derive(Clone)]
+pub struct Edge(Arc<Node>);
+
+struct Node {
+  hash: u64,
+  tree: Tree,
+}
+
+struct Table {
+  map: HashMap<u64, Vec<Weak<Node>>>,
+  /// Number of (live or dead) references.
+  len: usize,
+  /// Length triggering the next sweep.
+  sweep: usize,
+}
+
+impl Edge {
+  /// Return `true` if both edges point to the same node, which holds for all structurally equal edges (up to the
+  /// precision of floating point numbers).
+  pub fn ptr_eq(a: &Edge, b: &Edge) -> bool {
+    Arc::ptr_eq(&a.0, &b.0)
+  }
+
+  /// Return the address of the node, an identifier of the subexpression while it is alive.
+  pub fn id(&self) -> usize {
+    Arc::as_ptr(&self.0) as usize
+  }
+
+  /// Return the number of live interned nodes.
+  pub fn interned() -> usize {
+    let table = TABLE.lock().unwrap_or_else(PoisonError::into_inner);
+    table.map.values().flatten().filter(|node| node.strong_count() > 0).count()
+  }
+
+  /// Apply `f` to a copy of the node, then intern the result.
+  pub fn modify<R, F>(&mut self, f: F) -> R
+  where
+    F: FnOnce(&mut Tree) -> R,
+  {
+    let mut tree = self.0.tree.clone();
+    let r = f(&mut tree);
+    *self = Edge::from(tree);
+    r
+  }
+}
+
+impl Table {
+  fn intern(&mut self, hash: u64, tree: Tree) -> Edge {
+    let bucket = self.map.entry(hash).or_default();
+    let before = bucket.len();
+    bucket.retain(|node| node.strong_count() > 0);
+    self.len -= before - bucket.len();
+
+    if let Some(node) = bucket.iter().filter_map(Weak::upgrade).find(|node| identical(&node.tree, &tree)) {
+      return Edge(node);
+    }
+
+    let node = Arc::new(Node { hash, tree });
+    bucket.push(Arc::downgrade(&node));
+    self.len += 1;
+    if self.len >= self.sweep {
+      self.map.retain(|_, bucket| {
+        bucket.retain(|node| node.strong_count() > 0);
+        !bucket.is_empty()
+      });
+      self.len = self.map.values().map(Vec::len).sum();
+      self.sweep = (2 * self.len).max(MIN_SWEEP);
+    }
+
+    Edge(node)
+  }
+}
+
+// Structural equality, which also tells apart floating point numbers of different precisions, in which case the
+// subexpressions are distinct nodes.
+fn identical(a: &Tree, b: &Tree) -> bool {
+  a == b
+    && match (a, b) {
+      (Tree::Num(Number::Flt(x)), Tree::Num(Number::Flt(y))) => x.prec == y.prec && x.rnd == y.rnd,
+      // arguments of maps are not interned
+      (Tree::Fun(Function::MapExpr { arg: x, .. }), Tree::Fun(Function::MapExpr { arg: y, .. })) => x.iter().zip(y).all(|(x, y)| identical(x, y)),
+      _ => (0..).map_while(|i| a.child(i).zip(b.child(i))).all(|(x, y)| ptr::eq(x, y)),
+    }
+}
+
+// Dropping a node releases its descendants owned by no other node from a stack, rather than recursively, so that
+// dropping a deep expression does not overflow the call stack.
+impl Drop for Node {
+  fn drop(&mut self) {
+    let mut stack = vec![];
+    release(&mut self.tree, &mut stack);
+    while let Some(edge) = stack.pop() {
+      if let Some(mut node) = Arc::into_inner(edge.0) {
+        release(&mut node.tree, &mut stack);
+      }
+    }
+  }
+}
+
+// Move the edges of the tree to the stack.
+fn release(tree: &mut Tree, stack: &mut Vec<Edge>) {
+  match mem::replace(tree, Tree::Form) {
+    Tree::Alg(Algebra::UExpr { arg, .. }) | Tree::Fun(Function::ElemExpr { arg, .. }) | Tree::Cal(Calculus { arg, .. }) => stack.push(arg),
+    Tree::Alg(Algebra::BExpr { arg: (lhs, rhs), .. }) => stack.extend([lhs, rhs]),
+    Tree::Alg(Algebra::AssocExpr(Assoc { arg, .. })) | Tree::Fun(Function::CombExpr { arg, .. }) => stack.extend(arg),
+    Tree::Fun(Function::SpecExpr(spec)) => match spec {
+      Special::Gamma(arg) | Special::Floor(arg) | Special::Ceil(arg) | Special::Round(arg) | Special::Frac(arg) => stack.push(arg),
+      Special::Mod(lhs, rhs) => stack.extend([lhs, rhs]),
+    },
+    Tree::Fun(Function::MapExpr { arg, .. }) => arg.into_iter().for_each(|mut e| release(&mut e, stack)),
+    Tree::Sq(Sequence { arg, lo, up, .. }) => stack.extend([arg, lo, up]),
+    Tree::Sym(_) | Tree::Cte(_) | Tree::Num(_) | Tree::Form => {}
+  }
+}
+
+impl From<Tree> for Edge {
+  fn from(tree: Tree) -> Edge {
+    let mut state = DefaultHasher::new();
+    tree.hash(&mut state);
+    let hash = state.finish();
+    TABLE.lock().unwrap_or_else(PoisonError::into_inner).intern(hash, tree)
+  }
+}
+
+impl From<Edge> for Tree {
+  #[inline]
+  fn from(edge: Edge) -> Tree {
+    Arc::try_unwrap(edge.0).map_or_else(|node| node.tree.clone(), |mut node| mem::replace(&mut node.tree, Tree::Form))
+  }
+}
+
+impl Deref for Edge {
+  type Target = Tree;
+
+  fn deref(&self) -> &Tree {
+    &self.0.tree
+  }
+}
+
+impl AsRef<Tree> for Edge {
+  fn as_ref(&self) -> &Tree {
+    &self.0.tree
+  }
+}
+
+impl Borrow<Tree> for Edge {
+  fn borrow(&self) -> &Tree {
+    &self.0.tree
+  }
+}
+
+impl Eq for Edge {}
+impl PartialEq for Edge {
+  fn eq(&self, o: &Edge) -> bool {
+    // distinct nodes are only equal for floating point numbers of different precisions, or on hash collisions
+    Edge::ptr_eq(self, o) || (self.0.hash == o.0.hash && self.0.tree == o.0.tree)
+  }
+}
+
+impl PartialOrd for Edge {
+  fn partial_cmp(&self, o: &Edge) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(o))
+  }
+}
+
+impl Ord for Edge {
+  fn cmp(&self, o: &Edge) -> std::cmp::Ordering {
+    if Edge::ptr_eq(self, o) {
+      std::cmp::Ordering::Equal
+    } else {
+      self.0.tree.cmp(&o.0.tree)
+    }
+  }
+}
+
+impl Hash for Edge {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    state.write_u64(self.0.hash);
+  }
+}
+
+impl fmt::Debug for Edge {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Debug::fmt(&self.0.tree, f)
+  }
+}
+
+impl fmt::Display for Edge {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Display::fmt(&self.0.tree, f)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::base::algebra::{Float, Integer, Rational, Round};
+  use crate::base::Algebra;
+  use crate::{Assume, Expr, Symbol};
+
+  #[test]
+  fn sharing() {
+    let x = Tree::Sym(Symbol::new("x", Number::R).unwrap());
+    let a = x.clone().add(Tree::ONE).pow(Tree::TWO).edge();
+    let b = x.clone().add(Tree::ONE).pow(Tree::TWO).edge();
+    assert!(Edge::ptr_eq(&a, &b) && a == b);
+    assert_eq!(a.id(), b.id());
+    assert!(a != x.clone().add(Tree::TWO).pow(Tree::TWO).edge());
+
+    // shared subexpressions of a modified edge
+    let mut c = a.clone();
+    c.modify(|t| *t = t.clone().mul(Tree::TWO));
+    assert!(!Edge::ptr_eq(&a, &c));
+    let Tree::Alg(Algebra::AssocExpr(mul)) = c.as_ref() else { panic!("expected a product") };
+    assert!(mul.arg.iter().any(|e| Edge::ptr_eq(e, &a)));
+
+    // floating point numbers of different precisions are equal, but not shared
+    let f = |prec| Tree::Num(Number::Flt(Float::from_rational(Rational::from(Integer::from(3)), prec, Round::Nearest))).edge();
+    assert!(f(53) == f(128) && !Edge::ptr_eq(&f(53), &f(128)));
+    assert!(Edge::ptr_eq(&f(53), &f(53)));
+    let g = |prec| Tree::from(f(prec)).add(x.clone()).edge();
+    assert!(g(53) == g(128) && !Edge::ptr_eq(&g(53), &g(128)));
+
+    // symbols with different domains or assumptions are distinct
+    let y = Tree::Sym(Symbol::new("x", Number::R).and_then(|x| x.assume(Assume::Positive)).unwrap());
+    assert!(a != y.add(Tree::ONE).pow(Tree::TWO).edge());
+    let z = Tree::Sym(Symbol::new("x", Number::C).unwrap());
+    assert!(a != z.add(Tree::ONE).pow(Tree::TWO).edge());
+  }
+}
@@ -0,0 +1,15 @@
+//! Equality checking.
+
+use crate::base::zero::Zero;
+use crate::{Expr, Tree};
+
+impl Tree {
+  /// Test whether two expressions are equal: structurally up to associativity and commutativity, or by the zero test
+  /// of their difference.
+  pub fn equals(&self, o: &Tree) -> Zero {
+    if self.equivalent(o) {
+      return Zero::Yes;
+    }
+    self.clone().sub(o.clone()).is_zero()
+  }
+}
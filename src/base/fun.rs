@@ -1,7 +1,9 @@
 use std::cmp::Ordering;
 use std::fmt;
 
-use crate::{Constant, Form, Symbol, SymbolicResult};
+use crate::base::alg::{AOp, Algebra, Assoc, BOp};
+use crate::base::algebra::Round;
+use crate::{Complex, Constant, Form, Integer, Number, Symbol, SymbolicResult};
 use crate::{Edge, Expr, Tree};
 
 /// A list of elementary operations.
@@ -39,11 +41,82 @@ pub enum EOp {
   Log,
 }
 
+/// A list of combinatorial and number-theoretic operations.
+#[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord, Copy)]
+pub enum COp {
+  /// Binomial coefficient (n k).
+  Binomial,
+  /// Multinomial coefficient (k_1 + ... + k_m)!/(k_1!*...*k_m!).
+  Multinomial,
+  /// Fibonacci number F_n.
+  Fibonacci,
+  /// Lucas number L_n.
+  Lucas,
+  /// Bernoulli number B_n.
+  Bernoulli,
+  /// Euler number E_n.
+  Euler,
+  /// Stirling number of the first kind s(n, k).
+  Stirling1,
+  /// Stirling number of the second kind S(n, k).
+  Stirling2,
+  /// Bell number B_n.
+  Bell,
+  /// Catalan number C_n.
+  Catalan,
+  /// Partition function p(n).
+  Partition,
+  /// Divisor function σ_k(n).
+  DivisorSigma,
+  /// Möbius function μ(n).
+  Mobius,
+}
+
+impl COp {
+  /// Return the number of arguments, or `None` if the operation is variadic.
+  pub fn arity(&self) -> Option<usize> {
+    match self {
+      COp::Multinomial => None,
+      COp::Binomial | COp::Stirling1 | COp::Stirling2 | COp::DivisorSigma => Some(2),
+      _ => Some(1),
+    }
+  }
+
+  /// Return the name of the operation.
+  pub fn name(&self) -> &'static str {
+    match self {
+      COp::Binomial => "binomial",
+      COp::Multinomial => "multinomial",
+      COp::Fibonacci => "fibonacci",
+      COp::Lucas => "lucas",
+      COp::Bernoulli => "bernoulli",
+      COp::Euler => "euler",
+      COp::Stirling1 => "stirling1",
+      COp::Stirling2 => "stirling2",
+      COp::Bell => "bell",
+      COp::Catalan => "catalan",
+      COp::Partition => "partition",
+      COp::DivisorSigma => "divisor_sigma",
+      COp::Mobius => "mobius",
+    }
+  }
+}
+
 /// A list of special functions.
 #[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord)]
 pub enum Special {
   /// Gamma Γ(x).
   Gamma(Edge),
+  /// Floor ⌊x⌋.
+  Floor(Edge),
+  /// Ceiling ⌈x⌉.
+  Ceil(Edge),
+  /// Nearest integer (ties to even).
+  Round(Edge),
+  /// Fractional part x - ⌊x⌋.
+  Frac(Edge),
+  /// Modulo x - y*⌊x/y⌋.
+  Mod(Edge, Edge),
   // Polygamma ψ(n, z).
   //Polygamma(Edge, Edge),
   // Beta Β(x, y).
@@ -72,6 +145,11 @@ pub enum Function {
     Special, //.
   ),
 
+  CombExpr {
+    map: COp,
+    arg: Vec<Edge>,
+  },
+
   MapExpr {
     map: Symbol,
     arg: Vec<Tree>,
@@ -95,13 +173,22 @@ impl Function {
         ))
       }
 
+      Function::CombExpr {
+        //.
+        map,
+        arg,
+      } => {
+        let arg: Result<Vec<_>, _> = arg.into_iter().map(|sub| Ok(sub.trivial()?.edge())).collect();
+        Function::comb_trivial(map, arg?)
+      }
+
       Function::ElemExpr {
         //.
         map,
         arg,
       } => {
         match (map, arg.trivial()?) {
-          (_, Tree::Cte(Constant::Infinity(Ordering::Equal))) => Err(Form {}),
+          (_, Tree::Cte(Constant::Infinity(Ordering::Equal))) => Err(Form::default()),
 
           // [Trigonometric identities](https://en.wikipedia.org/wiki/List_of_trigonometric_identities)
 
@@ -134,9 +221,9 @@ impl Function {
           // ```sin(i) = i*sinh(1)```
           // ```cos(i) = cosh(1)```
           // ```tan(i) = i*tanh(1)```
-          (EOp::Sin, Tree::Cte(Constant::i)) => Ok(Tree::Cte(Constant::i).mul(Tree::from(1).sinh())),
-          (EOp::Cos, Tree::Cte(Constant::i)) => Ok(Tree::from(1).cosh()),
-          (EOp::Tan, Tree::Cte(Constant::i)) => Ok(Tree::Cte(Constant::i).mul(Tree::from(1).tanh())),
+          (EOp::Sin, Tree::Num(Number::Cpx(z))) if z == Complex::I => Ok(Tree::from(Complex::I).mul(Tree::from(1).sinh())),
+          (EOp::Cos, Tree::Num(Number::Cpx(z))) if z == Complex::I => Ok(Tree::from(1).cosh()),
+          (EOp::Tan, Tree::Num(Number::Cpx(z))) if z == Complex::I => Ok(Tree::from(Complex::I).mul(Tree::from(1).tanh())),
 
           // ```sin(arcsin(x)) = x```
           // ```sin(arccos(x)) = sqrt(1 - x^2)```
@@ -224,6 +311,15 @@ impl Function {
           // ```log(1) = 0```
           (EOp::Log, Tree::ONE) => Ok(Tree::from(0)),
 
+          // ```log(x_1*x_2*...*x_n) = log(x_1) + log(x_2) + ... + log(x_n), x_i > 0```
+          (EOp::Log, Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Mul, arg }))) if arg.iter().all(|e| e.facts().is_positive()) => {
+            Tree::assoc(AOp::Add, arg.into_iter().map(|e| e.log().edge()).collect()).trivial()
+          }
+          // ```log(x^y) = y*log(x), x > 0, y ∈ ℝ```
+          (EOp::Log, Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, e) })) if b.facts().is_positive() && e.facts().is_real() => e.mul(b.log()).trivial(),
+          // ```log(exp(x)) = x, x ∈ ℝ```
+          (EOp::Log, Tree::Fun(Function::ElemExpr { map: EOp::Exp, arg })) if arg.facts().is_real() => Ok(Tree::from(arg)),
+
           (map, arg) => Ok(Tree::elem(
             map, //.
             arg.edge(),
@@ -233,9 +329,106 @@ impl Function {
 
       Function::SpecExpr(map) => match map {
         Special::Gamma(arg) => Ok(arg.trivial()?.gamma()),
+
+        // [Floor and ceiling functions](https://en.wikipedia.org/wiki/Floor_and_ceiling_functions)
+        Special::Floor(arg) => Function::integer_part(Round::Floor, arg.trivial()?),
+        Special::Ceil(arg) => Function::integer_part(Round::Ceil, arg.trivial()?),
+        Special::Round(arg) => Function::integer_part(Round::Nearest, arg.trivial()?),
+
+        Special::Frac(arg) => match arg.trivial()? {
+          Tree::Num(Number::Cpx(_)) | Tree::Cte(Constant::Infinity(_)) => Err(Form::default()),
+          Tree::Num(Number::Rat(q)) => Ok(Tree::from(q.fract())),
+          // ```frac(n) = 0, n ∈ ℤ```
+          arg if Function::integral(&arg) => Ok(Tree::from(0)),
+          // ```frac(x + n) = frac(x), n ∈ ℤ```
+          arg => match Function::split_shift(&arg, false) {
+            Some((arg, _)) => arg.frac().trivial(),
+            // ```frac(x) = x - ⌊x⌋```
+            None => match arg.round_constant(Round::Floor) {
+              Some(floor) => arg.sub(Tree::from(floor)).trivial(),
+              None => Ok(arg.frac()),
+            },
+          },
+        },
+
+        Special::Mod(lhs, rhs) => match (lhs.trivial()?, rhs.trivial()?) {
+          (_, Tree::ZERO) => Err(Form::default()),
+          (Tree::Num(Number::Cpx(_)), _) | (_, Tree::Num(Number::Cpx(_))) => Err(Form::default()),
+          (Tree::ZERO, _) => Ok(Tree::from(0)),
+          (lhs, rhs) if lhs == rhs => Ok(Tree::from(0)),
+          // ```mod(x, 1) = frac(x)```
+          (lhs, Tree::ONE) => lhs.frac().trivial(),
+          // ```mod(x, y) = x - y*⌊x/y⌋```
+          (lhs, rhs) => match lhs.clone().div(rhs.clone()).trivial()?.round_constant(Round::Floor) {
+            Some(floor) => lhs.sub(rhs.mul(Tree::from(floor))).trivial(),
+            None => Ok(lhs.modulo(rhs)),
+          },
+        },
       },
     }
   }
+
+  // ```⌊x⌋```, ```⌈x⌉``` and ```round(x)```, by rounding mode.
+  fn integer_part(rnd: Round, arg: Tree) -> SymbolicResult<Tree> {
+    let wrap = |arg: Tree| match rnd {
+      Round::Floor => arg.floor(),
+      Round::Ceil => arg.ceil(),
+      _ => arg.round(),
+    };
+
+    match arg {
+      Tree::Num(Number::Cpx(_)) | Tree::Cte(Constant::Infinity(Ordering::Equal)) => Err(Form::default()),
+      // ```⌊z∞⌋ = z∞```
+      Tree::Cte(Constant::Infinity(_)) => Ok(arg),
+      Tree::Num(Number::Rat(q)) => Ok(Tree::from(match rnd {
+        Round::Floor => q.floor(),
+        Round::Ceil => q.ceil(),
+        _ => q.round(),
+      })),
+      Tree::Num(Number::Flt(x)) => Ok(Tree::from(x.to_integer(rnd))),
+      // ```⌊n⌋ = n, n ∈ ℤ```
+      arg if Function::integral(&arg) => Ok(arg),
+      // ```⌊x + n⌋ = ⌊x⌋ + n, n ∈ ℤ```, only even shifts for ties to even
+      arg => match Function::split_shift(&arg, rnd == Round::Nearest) {
+        Some((arg, shift)) => wrap(arg).add(shift).trivial(),
+        None => match arg.round_constant(rnd) {
+          Some(z) => Ok(Tree::from(z)),
+          None => Ok(wrap(arg)),
+        },
+      },
+    }
+  }
+
+  // Test if the expression takes integer values: integers, symbols assumed integer, integer parts,
+  // integer-valued combinatorial functions, and sums, products and natural powers of those.
+  pub(crate) fn integral(arg: &Tree) -> bool {
+    match arg {
+      Tree::Num(Number::Int(_)) => true,
+      Tree::Sym(s) => s.facts().is_integer(),
+      Tree::Fun(Function::SpecExpr(Special::Floor(_) | Special::Ceil(_) | Special::Round(_))) => true,
+      Tree::Fun(Function::CombExpr { map, arg }) if !matches!(map, COp::Bernoulli | COp::DivisorSigma) => arg.iter().all(|e| Function::integral(e)),
+      Tree::Alg(Algebra::AssocExpr(Assoc { arg, .. })) => arg.iter().all(|e| Function::integral(e)),
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, e) }) => Function::integral(b) && matches!(e.as_ref(), Tree::Num(Number::Int(n)) if !n.is_negative()),
+      _ => false,
+    }
+  }
+
+  // Split a sum ```x + n``` into ```(x, n)```, where ```n``` gathers the integer terms (even integers if `even`).
+  fn split_shift(arg: &Tree, even: bool) -> Option<(Tree, Tree)> {
+    let Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Add, arg })) = arg else {
+      return None;
+    };
+
+    let (shift, rest): (Vec<_>, Vec<_>) = arg.iter().cloned().partition(|e| match e.as_ref() {
+      Tree::Num(Number::Int(n)) if even => n.clone().rem_euclid(Integer::TWO) == Integer::ZERO,
+      _ if even => false,
+      e => Function::integral(e),
+    });
+    if shift.is_empty() || rest.is_empty() {
+      return None;
+    }
+    Some((Tree::assoc(AOp::Add, rest), Tree::assoc(AOp::Add, shift)))
+  }
 }
 
 impl fmt::Display for Function {
@@ -251,8 +444,22 @@ impl fmt::Display for Function {
 
       Function::SpecExpr(map) => match map {
         Special::Gamma(arg) => write!(f, "gamma({arg})"),
+        Special::Floor(arg) => write!(f, "floor({arg})"),
+        Special::Ceil(arg) => write!(f, "ceil({arg})"),
+        Special::Round(arg) => write!(f, "round({arg})"),
+        Special::Frac(arg) => write!(f, "frac({arg})"),
+        Special::Mod(lhs, rhs) => write!(f, "mod({lhs}, {rhs})"),
       },
 
+      Function::CombExpr {
+        //.
+        map,
+        arg,
+      } => {
+        let arg: Vec<_> = arg.iter().map(|e| format!("{e}")).collect();
+        write!(f, "{}({})", map.name(), arg.join(", "))
+      }
+
       Function::MapExpr {
         //.
         map,
@@ -281,4 +488,12 @@ impl Tree {
       arg,
     })
   }
+
+  pub(crate) fn comb(map: COp, arg: Vec<Edge>) -> Tree {
+    Tree::Fun(Function::CombExpr {
+      //.
+      map,
+      arg,
+    })
+  }
 }
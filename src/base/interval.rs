@@ -0,0 +1,813 @@
+//! Interval arithmetic.
+
+use crate::base::alg::{AOp, Algebra, Assoc, BOp, UOp};
+use crate::base::algebra::Round;
+use crate::base::budget;
+use crate::base::comb;
+use crate::base::fun::{COp, EOp, Function, Special};
+use crate::base::numeric::{Ball, Fault};
+use crate::base::sq::{Sequence, SqOp};
+use crate::{Constant, Float, Form, Integer, Natural, Number, Rational, Symbol, SymbolicResult};
+use crate::{Expr, Tree};
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+type Enclosure = Result<Interval, Fault>;
+
+// Bisections allowed while isolating roots.
+const MAX_STEPS: usize = 4096;
+
+/// A closed real interval `[lo, hi]` with floating point bounds.
+///
+/// Every operation rounds outward (the lower bound toward `-∞`, the upper bound toward `+∞`), so that the result
+/// encloses the exact result for every choice of points in the operands.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Interval {
+  lo: Float,
+  hi: Float,
+}
+
+impl Interval {
+  /// Create the interval `[lo, hi]`, or `None` if `lo > hi`.
+  pub fn new(lo: Float, hi: Float) -> Option<Interval> {
+    (lo <= hi).then(|| Interval {
+      lo: lo.with_rnd(Round::Floor),
+      hi: hi.with_rnd(Round::Ceil),
+    })
+  }
+
+  /// Create the degenerate interval `[x, x]`.
+  pub fn point(x: Float) -> Interval {
+    Interval {
+      lo: x.clone().with_rnd(Round::Floor),
+      hi: x.with_rnd(Round::Ceil),
+    }
+  }
+
+  /// Enclose the rational `q` with precision `prec`.
+  pub fn from_rational(q: Rational, prec: usize) -> Interval {
+    Interval {
+      lo: Float::from_rational(q.clone(), prec, Round::Floor),
+      hi: Float::from_rational(q, prec, Round::Ceil),
+    }
+  }
+
+  /// Return the lower bound.
+  pub fn lo(&self) -> &Float {
+    &self.lo
+  }
+
+  /// Return the upper bound.
+  pub fn hi(&self) -> &Float {
+    &self.hi
+  }
+
+  /// Return the precision (in bits) of the bounds.
+  pub fn prec(&self) -> usize {
+    self.lo.prec().max(self.hi.prec())
+  }
+
+  /// Return the midpoint (rounded to nearest).
+  pub fn mid(&self) -> Float {
+    let sum = self.lo.clone().with_rnd(Round::Nearest).with_prec(self.prec() + 1) + self.hi.clone();
+    sum.ldexp(-1).with_prec(self.prec())
+  }
+
+  /// Return an upper bound of the width.
+  pub fn width(&self) -> Float {
+    self.hi.clone() - self.lo.clone()
+  }
+
+  /// Return `true` if `x` lies in the interval.
+  pub fn contains(&self, x: &Float) -> bool {
+    &self.lo <= x && x <= &self.hi
+  }
+
+  /// Compare every point of the interval with zero, if they agree.
+  pub fn sgn(&self) -> Option<Ordering> {
+    let zero = Float::zero(self.prec(), Round::Nearest);
+    if self.lo > zero {
+      Some(Ordering::Greater)
+    } else if self.hi < zero {
+      Some(Ordering::Less)
+    } else if self.lo.is_zero() && self.hi.is_zero() {
+      Some(Ordering::Equal)
+    } else {
+      None
+    }
+  }
+
+  /// Compare every point of `self` with every point of `o`, if they agree.
+  pub fn compare(&self, o: &Interval) -> Option<Ordering> {
+    (self.clone() - o.clone()).sgn()
+  }
+
+  /// Return the smallest interval containing both intervals.
+  pub fn hull(&self, o: &Interval) -> Interval {
+    Interval {
+      lo: self.lo.clone().min(o.lo.clone()),
+      hi: self.hi.clone().max(o.hi.clone()),
+    }
+  }
+
+  /// Return the intersection, or `None` if the intervals are disjoint.
+  pub fn intersect(&self, o: &Interval) -> Option<Interval> {
+    Interval::new(self.lo.clone().max(o.lo.clone()), self.hi.clone().min(o.hi.clone()))
+  }
+
+  /// Split the interval at its midpoint.
+  pub fn bisect(&self) -> (Interval, Interval) {
+    let mid = self.mid();
+    (
+      Interval {
+        lo: self.lo.clone(),
+        hi: mid.clone().with_rnd(Round::Ceil),
+      },
+      Interval {
+        lo: mid.with_rnd(Round::Floor),
+        hi: self.hi.clone(),
+      },
+    )
+  }
+
+  /// Return the reciprocal.
+  pub fn inv(&self) -> SymbolicResult<Interval> {
+    self.try_inv().map_err(|_| Form::default())
+  }
+
+  /// Raise the interval to an integer power.
+  pub fn powi(&self, n: &Integer) -> SymbolicResult<Interval> {
+    self.try_powi(n).map_err(|_| Form::default())
+  }
+
+  /// Return the square root (of the nonnegative part).
+  pub fn sqrt(&self) -> SymbolicResult<Interval> {
+    self.try_sqrt().map_err(|_| Form::default())
+  }
+
+  /// Apply an elementary function.
+  pub fn elem(&self, map: EOp) -> SymbolicResult<Interval> {
+    self.try_elem(map).map_err(|_| Form::default())
+  }
+
+  /// Apply the gamma function.
+  pub fn gamma(&self) -> SymbolicResult<Interval> {
+    self.try_gamma().map_err(|_| Form::default())
+  }
+
+  // Helpers
+  fn from_ball(b: Ball) -> Interval {
+    Interval {
+      lo: b.mid.clone().with_rnd(Round::Floor) - b.rad.clone(),
+      hi: b.mid.with_rnd(Round::Ceil) + b.rad,
+    }
+  }
+
+  fn from_integer(z: i64, w: usize) -> Interval {
+    Interval::point(Float::from_integer(Integer::from(z), w, Round::Nearest))
+  }
+
+  // Enclosure of the integer part by `rnd`, which is increasing.
+  fn integer_part(&self, rnd: Round) -> Interval {
+    let w = self.prec();
+    Interval {
+      lo: Float::from_integer(self.lo.to_integer(rnd), w, Round::Floor),
+      hi: Float::from_integer(self.hi.to_integer(rnd), w, Round::Ceil),
+    }
+  }
+
+  // Enclosure of ```x - ⌊x⌋```, `[0, 1]` across an integer.
+  fn frac(&self) -> Interval {
+    let floor = self.integer_part(Round::Floor);
+    if floor.lo == floor.hi {
+      self.clone() - floor
+    } else {
+      Interval::new(Float::zero(self.prec(), Round::Floor), Float::one(self.prec(), Round::Ceil)).expect("0 <= 1")
+    }
+  }
+
+  // Enclosure of `f(x)` for a point `x`.
+  fn at<F>(x: &Float, f: F) -> Enclosure
+  where
+    F: FnOnce(Ball) -> Result<Ball, Fault>,
+  {
+    f(Ball::exact(x.clone().with_rnd(Round::Nearest))).map(Interval::from_ball)
+  }
+
+  // Enclosure of an increasing function.
+  fn increasing<F>(&self, f: F) -> Enclosure
+  where
+    F: Fn(Ball) -> Result<Ball, Fault>,
+  {
+    Ok(Interval {
+      lo: Interval::at(&self.lo, &f)?.lo,
+      hi: Interval::at(&self.hi, &f)?.hi,
+    })
+  }
+
+  // Enclosure of a decreasing function.
+  fn decreasing<F>(&self, f: F) -> Enclosure
+  where
+    F: Fn(Ball) -> Result<Ball, Fault>,
+  {
+    Ok(Interval {
+      lo: Interval::at(&self.hi, &f)?.lo,
+      hi: Interval::at(&self.lo, &f)?.hi,
+    })
+  }
+
+  // Restrict to the domain `[lo, hi]` of a function, failing if the interval lies outside.
+  fn clamp(&self, lo: Option<i64>, hi: Option<i64>) -> Enclosure {
+    let w = self.prec();
+    let lo = lo.map_or(self.lo.clone(), |lo| self.lo.clone().max(Float::from_integer(Integer::from(lo), w, Round::Floor)));
+    let hi = hi.map_or(self.hi.clone(), |hi| self.hi.clone().min(Float::from_integer(Integer::from(hi), w, Round::Ceil)));
+    Interval::new(lo, hi).ok_or(Fault::Form)
+  }
+
+  fn try_inv(&self) -> Enclosure {
+    match self.sgn() {
+      Some(Ordering::Equal) => Err(Fault::Form),
+      None => Err(Fault::Precision),
+      Some(_) => {
+        let w = self.prec();
+        Ok(Interval {
+          lo: Float::one(w, Round::Floor) / self.hi.clone(),
+          hi: Float::one(w, Round::Ceil) / self.lo.clone(),
+        })
+      }
+    }
+  }
+
+  fn try_powi(&self, n: &Integer) -> Enclosure {
+    if n.is_negative() {
+      return self.try_powi(&-n.clone())?.try_inv();
+    }
+
+    // ```|x|^n```, rounded with `rnd`
+    let pow = |x: &Float, rnd: Round| {
+      let x = x.clone().abs().with_rnd(rnd);
+      let mut k = n.clone().abs();
+      let (mut acc, mut base) = (Float::one(x.prec(), rnd), x);
+      while k != Natural::ZERO {
+        if k.bit(0) {
+          acc = acc * base.clone();
+        }
+        k >>= 1;
+        base = base.clone() * base;
+      }
+      acc
+    };
+
+    let even = n.clone().rem_euclid(Integer::TWO) == Integer::ZERO;
+    Ok(match (even, self.sgn()) {
+      (_, Some(Ordering::Greater | Ordering::Equal)) => Interval {
+        lo: pow(&self.lo, Round::Floor),
+        hi: pow(&self.hi, Round::Ceil),
+      },
+      (true, Some(Ordering::Less)) => Interval {
+        lo: pow(&self.hi, Round::Floor),
+        hi: pow(&self.lo, Round::Ceil),
+      },
+      (true, None) => Interval {
+        lo: Float::zero(self.prec(), Round::Floor),
+        hi: pow(&self.lo, Round::Ceil).max(pow(&self.hi, Round::Ceil)),
+      },
+      // odd powers are increasing
+      (false, _) => Interval {
+        lo: if self.lo.is_negative() { -pow(&self.lo, Round::Ceil) } else { pow(&self.lo, Round::Floor) },
+        hi: if self.hi.is_negative() { -pow(&self.hi, Round::Floor) } else { pow(&self.hi, Round::Ceil) },
+      },
+    })
+  }
+
+  fn try_sqrt(&self) -> Enclosure {
+    self.clamp(Some(0), None)?.increasing(Ball::sqrt)
+  }
+
+  // Enclosure of `c + k*π` for an integer `k`.
+  fn multiple_pi(c: &Interval, k: &Integer, w: usize) -> Interval {
+    c.clone() + Interval::from_ball(Ball::pi(w)) * Interval::point(Float::from_integer(k.clone(), w, Round::Nearest))
+  }
+
+  // Integers `k` such that `c + k*π` may lie in the interval, or `None` if the interval spans a period.
+  fn periods(&self, c: &Interval) -> Option<Vec<Integer>> {
+    let w = self.prec();
+    let pi = Interval::from_ball(Ball::pi(w));
+    if self.width() >= pi.lo.clone().ldexp(1) {
+      return None;
+    }
+
+    let k = |x: &Float| ((x.clone().with_rnd(Round::Nearest) - c.mid()) / pi.mid()).to_integer(Round::Floor);
+    let (mut k, up) = (k(&self.lo) - Integer::ONE, k(&self.hi) + Integer::ONE);
+    let mut ks = vec![];
+    while k <= up {
+      if Interval::multiple_pi(c, &k, w).intersect(self).is_some() {
+        ks.push(k.clone());
+      }
+      k.incr();
+    }
+    Some(ks)
+  }
+
+  // sin and cos reach their extrema ```(-1)^k``` at ```c + k*π```.
+  fn periodic(&self, map: EOp, c: Interval) -> Enclosure {
+    let w = self.prec();
+    let unit = Interval {
+      lo: Float::from_integer(Integer::NEG_ONE, w, Round::Floor),
+      hi: Float::one(w, Round::Ceil),
+    };
+    let Some(ks) = self.periods(&c) else {
+      return Ok(unit);
+    };
+
+    let mut enc = Interval::at(&self.lo, |b| b.elem(map))?.hull(&Interval::at(&self.hi, |b| b.elem(map))?);
+    for k in ks {
+      if k.clone().rem_euclid(Integer::TWO) == Integer::ZERO {
+        enc.hi = unit.hi.clone();
+      } else {
+        enc.lo = unit.lo.clone();
+      }
+    }
+    enc.intersect(&unit).ok_or(Fault::Precision)
+  }
+
+  fn try_elem(&self, map: EOp) -> Enclosure {
+    let w = self.prec();
+    let half_pi = Interval::from_ball(Ball::pi(w)) * Interval::point(Float::one(w, Round::Nearest).ldexp(-1));
+    let f = |b: Ball| b.elem(map);
+    match map {
+      EOp::Sin => self.periodic(map, half_pi),
+      EOp::Cos => self.periodic(map, Interval::from_integer(0, w)),
+      EOp::Tan => match self.periods(&half_pi) {
+        // poles at ```π/2 + k*π```
+        Some(ks) if ks.is_empty() => self.increasing(f),
+        _ => Err(Fault::Precision),
+      },
+      EOp::ArcSin => self.clamp(Some(-1), Some(1))?.increasing(f),
+      EOp::ArcCos => self.clamp(Some(-1), Some(1))?.decreasing(f),
+      EOp::ArcTan | EOp::Sinh | EOp::Tanh | EOp::ArSinh | EOp::Exp => self.increasing(f),
+
+      EOp::Cosh => match self.sgn() {
+        Some(Ordering::Greater | Ordering::Equal) => self.increasing(f),
+        Some(Ordering::Less) => self.decreasing(f),
+        // ```cosh(0) = 1```
+        None => Ok(Interval {
+          lo: Float::one(w, Round::Floor),
+          hi: Interval::at(&self.lo, f)?.hi.max(Interval::at(&self.hi, f)?.hi),
+        }),
+      },
+      EOp::ArCosh => self.clamp(Some(1), None)?.increasing(f),
+      EOp::ArTanh | EOp::Log => {
+        let (lo, hi) = if map == EOp::Log { (Some(0), None) } else { (Some(-1), Some(1)) };
+        let dom = self.clamp(lo, hi)?;
+        // unbounded near the open ends of the domain
+        if dom != *self
+          || lo.is_some_and(|lo| dom.lo == Float::from_integer(Integer::from(lo), w, Round::Floor))
+          || hi.is_some_and(|hi| dom.hi == Float::from_integer(Integer::from(hi), w, Round::Ceil))
+        {
+          return if dom.lo == dom.hi { Err(Fault::Form) } else { Err(Fault::Precision) };
+        }
+        self.increasing(f)
+      }
+    }
+  }
+
+  fn try_gamma(&self) -> Enclosure {
+    if self.lo == self.hi {
+      return Interval::at(&self.lo, Ball::gamma);
+    }
+    if self.sgn() != Some(Ordering::Greater) {
+      return Err(Fault::Precision);
+    }
+
+    // Γ decreases on ```]0, x₀]``` and increases on ```[x₀, ∞[```, ```x₀ = 1.4616...```, ```Γ(x₀) = 0.8856...```
+    let w = self.prec();
+    let q = |n: i64, d: i64| Interval::from_rational(Rational::new(Integer::from(n), Integer::from(d)), w);
+    if self.hi <= q(146, 100).lo {
+      self.decreasing(Ball::gamma)
+    } else if self.lo >= q(147, 100).hi {
+      self.increasing(Ball::gamma)
+    } else {
+      Ok(Interval {
+        lo: q(8856, 10000).lo,
+        hi: Interval::at(&self.lo, Ball::gamma)?.hi.max(Interval::at(&self.hi, Ball::gamma)?.hi),
+      })
+    }
+  }
+
+  fn pow(self, o: Interval) -> Enclosure {
+    // ```x^y = exp(y*log(x))```
+    (o * self.try_elem(EOp::Log)?).try_elem(EOp::Exp)
+  }
+}
+
+impl Add for Interval {
+  type Output = Interval;
+
+  fn add(self, o: Interval) -> Interval {
+    Interval {
+      lo: self.lo + o.lo,
+      hi: self.hi + o.hi,
+    }
+  }
+}
+
+impl Sub for Interval {
+  type Output = Interval;
+
+  fn sub(self, o: Interval) -> Interval {
+    self + -o
+  }
+}
+
+impl Mul for Interval {
+  type Output = Interval;
+
+  fn mul(self, o: Interval) -> Interval {
+    let prod = |x: &Float, y: &Float, rnd: Round| x.clone().with_rnd(rnd) * y.clone();
+    let ends = [(&self.lo, &o.lo), (&self.lo, &o.hi), (&self.hi, &o.lo), (&self.hi, &o.hi)];
+    let lo = ends.iter().map(|(x, y)| prod(x, y, Round::Floor)).min();
+    let hi = ends.iter().map(|(x, y)| prod(x, y, Round::Ceil)).max();
+    Interval {
+      lo: lo.expect("four products"),
+      hi: hi.expect("four products"),
+    }
+  }
+}
+
+impl Neg for Interval {
+  type Output = Interval;
+
+  fn neg(self) -> Interval {
+    Interval {
+      lo: (-self.hi).with_rnd(Round::Floor),
+      hi: (-self.lo).with_rnd(Round::Ceil),
+    }
+  }
+}
+
+impl fmt::Display for Interval {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "[{}, {}]", self.lo, self.hi)
+  }
+}
+
+impl Tree {
+  /// Enclose the value of a real expression in an [`Interval`] computed with precision `prec`.
+  pub fn interval(&self, prec: usize) -> SymbolicResult<Interval> {
+    self.enclose(&[], prec).map_err(Fault::into_form)
+  }
+
+  /// Enclose the values of a real expression for every `x` in `at`.
+  pub fn interval_at(&self, x: &Symbol, at: &Interval) -> SymbolicResult<Interval> {
+    self.enclose(&[(x, at)], at.prec()).map_err(Fault::into_form)
+  }
+
+  /// Determine the sign of a real constant expression, or of an expression whose sign follows from the assumptions on
+  /// its symbols, or `None` if it cannot be decided.
+  ///
+  /// The precision is increased until the enclosure excludes zero, so an expression equal to zero but not
+  /// trivially so (```sin(π)```) has no decided sign.
+  pub fn sign(&self) -> Option<Ordering> {
+    match self {
+      Tree::Num(Number::Cpx(_)) => None,
+      Tree::Num(n) => Some(n.sgn()),
+      _ => {
+        // decided by the assumptions on symbols
+        if let Some(sgn) = self.facts().sign() {
+          return Some(sgn);
+        }
+        for w in [64, 256, 1024, 4096] {
+          match self.enclose(&[], w) {
+            Ok(enc) if enc.sgn().is_some() => return enc.sgn(),
+            Err(Fault::Form | Fault::Halt(_)) => return None,
+            _ => {}
+          }
+        }
+        None
+      }
+    }
+  }
+
+  // Integer part by `rnd` of a real constant expression, when the enclosure decides it.
+  pub(crate) fn round_constant(&self, rnd: Round) -> Option<Integer> {
+    for w in [64, 256, 1024, 4096] {
+      match self.enclose(&[], w) {
+        Ok(enc) => {
+          let (lo, hi) = (enc.lo.to_integer(rnd), enc.hi.to_integer(rnd));
+          if lo == hi {
+            return Some(lo);
+          }
+        }
+        Err(Fault::Form | Fault::Halt(_)) => return None,
+        Err(Fault::Precision) => {}
+      }
+    }
+    None
+  }
+
+  /// Isolate the real roots of the expression in `x` on `at`.
+  ///
+  /// Each returned interval contains exactly one root (by a sign change on an interval where the derivative has a
+  /// constant sign), or is a single point where the expression vanishes. Fails when a root cannot be isolated
+  /// (multiple roots, roots on a pole, ...).
+  pub fn isolate_roots(&self, x: &Symbol, at: &Interval) -> SymbolicResult<Vec<Interval>> {
+    let df = self.clone().derivative(vec![x.clone()]).trivial()?;
+    let w = at.prec();
+    let eval = |e: &Tree, iv: &Interval| e.enclose(&[(x, iv)], w);
+
+    let mut roots = vec![];
+    let mut stack = vec![at.clone()];
+    for _ in 0..MAX_STEPS {
+      let Some(iv) = stack.pop() else {
+        roots.sort_by(|l: &Interval, r: &Interval| l.lo.cmp(&r.lo));
+        roots.dedup();
+        return Ok(roots);
+      };
+
+      match eval(self, &iv) {
+        Ok(f) if matches!(f.sgn(), Some(Ordering::Less | Ordering::Greater)) => continue,
+        Err(fault @ (Fault::Form | Fault::Halt(_))) => return Err(fault.into_form()),
+        _ => {}
+      }
+
+      if let Ok(Some(Ordering::Less | Ordering::Greater)) = eval(&df, &iv).map(|d| d.sgn()) {
+        // monotone: one root at most, found by the signs at the ends
+        let sgn = |p: &Float| eval(self, &Interval::point(p.clone())).ok().and_then(|f| f.sgn());
+        match (sgn(&iv.lo), sgn(&iv.hi)) {
+          (Some(Ordering::Equal), _) => roots.push(Interval::point(iv.lo.clone())),
+          (_, Some(Ordering::Equal)) => roots.push(Interval::point(iv.hi.clone())),
+          (Some(l), Some(h)) if l != h => roots.push(iv),
+          (Some(_), Some(_)) => {}
+          _ => return Err(Form::default()),
+        }
+        continue;
+      }
+
+      let (l, h) = iv.bisect();
+      if l == iv || h == iv {
+        // the interval cannot be split further at this precision
+        return Err(Form::default());
+      }
+      stack.push(h);
+      stack.push(l);
+    }
+
+    Err(Form::default())
+  }
+
+  fn enclose(&self, vars: &[(&Symbol, &Interval)], w: usize) -> Enclosure {
+    // enclosures of the subexpressions, the children before their parent
+    let mut done: HashMap<&Tree, Enclosure> = HashMap::new();
+    let mut stack = vec![(self, false)];
+    while let Some((e, ready)) = stack.pop() {
+      if done.contains_key(e) {
+        continue;
+      }
+
+      if ready {
+        let sub = e.operands().into_iter().map(|c| done[c].clone()).collect();
+        done.insert(e, e.enclose_node(vars, w, sub));
+      } else {
+        stack.push((e, true));
+        stack.extend(e.operands().into_iter().map(|c| (c, false)));
+      }
+    }
+    done.remove(self).unwrap_or(Err(Fault::Form))
+  }
+
+  // Enclose the value of the node, from the enclosures `sub` of its operands.
+  fn enclose_node(&self, vars: &[(&Symbol, &Interval)], w: usize, sub: Vec<Enclosure>) -> Enclosure {
+    budget::step()?;
+    let mut sub = sub.into_iter();
+    let mut next = move || sub.next().unwrap_or(Err(Fault::Form));
+    match self {
+      Tree::Num(Number::Cpx(_)) => Err(Fault::Form),
+      Tree::Num(Number::Int(z)) => Ok(Interval::from_rational(Rational::from(z.clone()), w)),
+      Tree::Num(Number::Rat(q)) => Ok(Interval::from_rational(q.clone(), w)),
+      Tree::Num(Number::Flt(x)) => Ok(Interval::point(x.clone())),
+      Tree::Num(n) => Ok(Interval::from_ball(Ball::from_number(n, w))),
+
+      Tree::Sym(s) => vars.iter().find(|(x, _)| *x == s).map(|(_, iv)| (*iv).clone()).ok_or(Fault::Form),
+
+      Tree::Cte(Constant::pi) => Ok(Interval::from_ball(Ball::pi(w))),
+      Tree::Cte(Constant::e) => Interval::at(&Float::one(w, Round::Nearest), |b| b.elem(EOp::Exp)),
+      Tree::Cte(Constant::i | Constant::Infinity(_)) => Err(Fault::Form),
+
+      Tree::Alg(Algebra::AssocExpr(Assoc { map, arg })) => {
+        let mut acc = match map {
+          AOp::Add => Interval::from_integer(0, w),
+          AOp::Mul => Interval::from_integer(1, w),
+        };
+        for _ in arg {
+          let e = next()?;
+          acc = match map {
+            AOp::Add => acc + e,
+            AOp::Mul => acc * e,
+          };
+        }
+        Ok(acc)
+      }
+
+      Tree::Alg(Algebra::UExpr { map: UOp::Id, .. }) => next(),
+      // ```x! = Γ(x + 1)```
+      Tree::Alg(Algebra::UExpr { map: UOp::Fact, .. }) => (next()? + Interval::from_integer(1, w)).try_gamma(),
+
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (_, rhs) }) => {
+        let (base, exp) = (next()?, next());
+        match rhs.as_ref() {
+          Tree::Num(Number::Int(n)) => base.try_powi(n),
+          Tree::Num(Number::Rat(q)) if q.den == Integer::TWO => base.try_sqrt()?.try_powi(&q.num),
+          _ => {
+            let exp = exp?;
+            match (base.sgn(), exp.sgn()) {
+              // ```0^y = 0, y > 0```
+              (Some(Ordering::Equal), Some(Ordering::Greater)) => Ok(base),
+              (Some(Ordering::Equal), Some(_)) => Err(Fault::Form),
+              _ => base.pow(exp),
+            }
+          }
+        }
+      }
+
+      Tree::Fun(Function::ElemExpr { map, .. }) => next()?.try_elem(*map),
+      Tree::Fun(Function::SpecExpr(Special::Gamma(_))) => next()?.try_gamma(),
+      Tree::Fun(Function::SpecExpr(Special::Floor(_))) => Ok(next()?.integer_part(Round::Floor)),
+      Tree::Fun(Function::SpecExpr(Special::Ceil(_))) => Ok(next()?.integer_part(Round::Ceil)),
+      Tree::Fun(Function::SpecExpr(Special::Round(_))) => Ok(next()?.integer_part(Round::Nearest)),
+      Tree::Fun(Function::SpecExpr(Special::Frac(_))) => Ok(next()?.frac()),
+      // ```mod(x, y) = x - y*⌊x/y⌋```
+      Tree::Fun(Function::SpecExpr(Special::Mod(..))) => {
+        let (x, y) = (next()?, next()?);
+        let q = (x.clone() * y.try_inv()?).integer_part(Round::Floor);
+        Ok(x - y * q)
+      }
+
+      Tree::Sq(Sequence { map, idx, lo, up, arg }) => {
+        let (Tree::Num(Number::Int(lo)), Tree::Num(Number::Int(up))) = (lo.as_ref().clone().trivial()?, up.as_ref().clone().trivial()?) else {
+          return Err(Fault::Form);
+        };
+
+        let mut acc = match map {
+          SqOp::Sum => Interval::from_integer(0, w),
+          SqOp::Prod => Interval::from_integer(1, w),
+        };
+        let mut k = lo;
+        while k <= up {
+          let mut e = arg.as_ref().clone();
+          e.subs(&Tree::Sym(idx.clone()), &Tree::from(k.clone()));
+          let e = e.enclose(vars, w)?;
+          acc = match map {
+            SqOp::Sum => acc + e,
+            SqOp::Prod => acc * e,
+          };
+          k.incr();
+        }
+        Ok(acc)
+      }
+
+      // exact at integers, through the analytic continuation otherwise
+      Tree::Fun(fun @ Function::CombExpr { map, arg }) => match fun.clone().fun_trivial()? {
+        Tree::Fun(Function::CombExpr { .. }) => match (arg.as_slice(), map) {
+          ([x], COp::Fibonacci | COp::Lucas) => comb::continuation(*map, x).ok_or(Fault::Form)?.enclose(vars, w),
+          _ => Err(Fault::Form),
+        },
+        e => e.enclose(vars, w),
+      },
+
+      Tree::Fun(Function::MapExpr { .. }) | Tree::Cal(_) | Tree::Form => Err(Fault::Form),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn q(n: i64, d: i64) -> Rational {
+    Rational::new(Integer::from(n), Integer::from(d))
+  }
+
+  // Whether `iv` meets the decimal `digits` up to its last digit.
+  fn encloses(iv: &Interval, digits: &str) -> bool {
+    let frac = digits.split_once('.').map_or(0, |(_, f)| f.len());
+    let n: Integer = digits.replace('.', "").parse().expect("a decimal");
+    let ulp = Rational::new(Integer::ONE, Integer::from(10).pow(frac as u64));
+    let x = Rational::new(n, Integer::from(10).pow(frac as u64));
+    let near = Interval::from_rational(x.clone() - ulp.clone(), 64).hull(&Interval::from_rational(x + ulp, 64));
+    iv.intersect(&near).is_some()
+  }
+
+  #[test]
+  fn arithmetic() -> Result<(), Form> {
+    let third = Interval::from_rational(q(1, 3), 53);
+    assert!(third.lo() < third.hi());
+    // ```3*(1/3) ∋ 1```
+    let one = third.clone() * Interval::from_rational(q(3, 1), 53);
+    assert!(one.contains(&Float::one(53, Round::Nearest)));
+    assert_eq!(one.sgn(), Some(Ordering::Greater));
+
+    let x = Interval::new(Float::from_integer(Integer::from(-2), 53, Round::Nearest), Float::from_integer(Integer::from(3), 53, Round::Nearest)).expect("-2 <= 3");
+    let sq = x.powi(&Integer::TWO)?;
+    assert_eq!(
+      (sq.lo().clone(), sq.hi().clone()),
+      (Float::zero(53, Round::Nearest), Float::from_integer(Integer::from(9), 53, Round::Nearest))
+    );
+    assert_eq!(x.sgn(), None);
+    assert_eq!(x.inv(), Err(Form::default()));
+
+    let (l, h) = x.bisect();
+    assert_eq!(l.hull(&h), x);
+    assert_eq!(l.compare(&Interval::from_rational(q(1, 1), 53)), Some(Ordering::Less));
+    assert_eq!(h.compare(&Interval::from_rational(q(1, 1), 53)), None);
+    assert_eq!(l.intersect(&Interval::from_rational(q(10, 1), 53)), None);
+    Ok(())
+  }
+
+  #[test]
+  fn functions() -> Result<(), Form> {
+    let x = Interval::new(Float::from_integer(Integer::ZERO, 64, Round::Nearest), Float::from_integer(Integer::from(4), 64, Round::Nearest)).expect("0 <= 4");
+    // ```sin([0, 4]) = [sin(4), 1]```
+    let s = x.elem(EOp::Sin)?;
+    assert!(encloses(&s, "-0.7568024953079282") && encloses(&s, "1"));
+    assert!(s.hi() <= &Float::one(64, Round::Nearest));
+    // ```cos([0, 4]) = [-1, 1]```
+    let c = x.elem(EOp::Cos)?;
+    assert!(encloses(&c, "-1") && encloses(&c, "1"));
+    // ```tan``` has a pole at ```π/2```
+    assert_eq!(x.elem(EOp::Tan), Err(Form::default()));
+    assert_eq!(x.elem(EOp::Log), Err(Form::default()));
+    let e = x.elem(EOp::Exp)?;
+    assert!(encloses(&e, "1") && encloses(&e, "54.598150033144236"));
+
+    // ```Γ([1, 2]) = [0.8856..., 1]```
+    let g = Interval::new(Float::one(64, Round::Nearest), Float::from_integer(Integer::TWO, 64, Round::Nearest))
+      .expect("1 <= 2")
+      .gamma()?;
+    assert!(encloses(&g, "0.8856031944108887") && encloses(&g, "1"));
+    Ok(())
+  }
+
+  #[test]
+  fn expressions() -> Result<(), Form> {
+    // ```π - 355/113 < 0```
+    let x = Tree::Cte(Constant::pi).sub(Tree::from(q(355, 113)));
+    assert_eq!(x.sign(), Some(Ordering::Less));
+    assert!(encloses(&x.interval(128)?, "-0.000000266764189062422"));
+    // ```e^π - π^e > 0```
+    let y = Tree::Cte(Constant::e).pow(Tree::Cte(Constant::pi)).sub(Tree::Cte(Constant::pi).pow(Tree::Cte(Constant::e)));
+    assert_eq!(y.sign(), Some(Ordering::Greater));
+    assert_eq!(Tree::Cte(Constant::pi).sin().sign(), None);
+    // ```(3 - π)*∞ = -∞```, ```(π/4)^∞ = 0```
+    let inf = Tree::Cte(Constant::Infinity(Ordering::Greater));
+    assert_eq!(Tree::from(3).sub(Tree::Cte(Constant::pi)).mul(inf.clone()).trivial()?, Tree::Cte(Constant::Infinity(Ordering::Less)));
+    assert_eq!(Tree::Cte(Constant::pi).div(Tree::from(4)).pow(inf).trivial()?, Tree::from(0));
+
+    // ```x^2 - 2``` on ```[-2, 2]```
+    let s = Symbol::new("x", Number::R).expect("failed to declare symbol `x`");
+    let x = Tree::Sym(s.clone());
+    let at = Interval::new(Float::from_integer(Integer::from(-2), 64, Round::Nearest), Float::from_integer(Integer::TWO, 64, Round::Nearest)).expect("-2 <= 2");
+    let roots = x.clone().pow(Tree::from(2)).sub(Tree::from(2)).isolate_roots(&s, &at)?;
+    assert_eq!(roots.len(), 2);
+    assert!(encloses(&roots[0], "-1.4142135623730951") && encloses(&roots[1], "1.4142135623730951"));
+
+    // ```cos(x) - x``` on ```[-2, 2]```
+    let roots = x.clone().cos().sub(x.clone()).isolate_roots(&s, &at)?;
+    assert_eq!(roots.len(), 1);
+    assert!(encloses(&roots[0], "0.7390851332151607"));
+
+    // ```x^2``` has a double root
+    assert_eq!(x.pow(Tree::from(2)).isolate_roots(&s, &at), Err(Form::default()));
+    Ok(())
+  }
+
+  #[test]
+  fn integer_parts() -> Result<(), Form> {
+    let pi = Tree::Cte(Constant::pi);
+    assert_eq!(Tree::from(q(-7, 2)).floor().trivial()?, Tree::from(-4));
+    assert_eq!(Tree::from(q(5, 2)).round().trivial()?, Tree::from(2));
+    assert_eq!(pi.clone().floor().trivial()?, Tree::from(3));
+    assert_eq!(Tree::from(2).sqrt().ceil().trivial()?, Tree::from(2));
+    assert_eq!(Tree::from(-7).modulo(Tree::from(3)).trivial()?, Tree::from(2));
+    assert_eq!(pi.clone().modulo(Tree::from(1)).trivial()?, pi.clone().sub(Tree::from(3)).trivial()?);
+    assert_eq!(Tree::from(1).modulo(Tree::from(0)).trivial(), Err(Form::default()));
+
+    // ```⌊n + x + 3⌋ = ⌊x⌋ + n + 3```, ```frac(n) = 0```, ```round(x + 3)``` is kept
+    let n = Tree::Sym(Symbol::new("n", Number::Z).expect("failed to declare symbol `n`"));
+    let x = Tree::Sym(Symbol::new("x", Number::R).expect("failed to declare symbol `x`"));
+    let floor = n.clone().add(x.clone()).add(Tree::from(3)).floor().trivial()?;
+    assert_eq!(floor, x.clone().floor().add(n.clone().add(Tree::from(3))).trivial()?);
+    assert_eq!(n.clone().mul(n.clone()).frac().trivial()?, Tree::from(0));
+    assert_eq!(n.clone().ceil().trivial()?, n);
+    assert_eq!(x.clone().add(Tree::from(3)).round().trivial()?, x.clone().add(Tree::from(3)).round());
+
+    // ```mod(e^π, π) = e^π - 7π```
+    let m = Tree::Cte(Constant::e).pow(pi.clone()).modulo(pi);
+    assert!(encloses(&m.interval(128)?, "1.149544057651"));
+    assert!(m.evalf(12).is_ok());
+    Ok(())
+  }
+}
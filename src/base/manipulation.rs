@@ -1,6 +1,12 @@
+use crate::base::alg::{AOp, Algebra, Assoc, BOp};
+use crate::base::algebra::{Natural, Number};
+use crate::base::budget;
 use crate::SymbolicResult;
 use crate::{Edge, Expr, Tree};
 
+// Maximum number of terms of an expansion, beyond which products and powers are kept.
+const MAX_TERMS: usize = 1 << 10;
+
 #[derive(Debug, Clone)]
 pub struct Evaluate {
   arg: Tree,
@@ -82,7 +88,64 @@ impl Expr for Expand {
 
 impl Expand {
   fn expand_all(self) -> SymbolicResult<Tree> {
-    self.arg.trivial()
+    Expand::distribute(self.arg.trivial()?).trivial()
+  }
+
+  // Distribute the products over the sums, from the leaves.
+  fn distribute(expr: Tree) -> Tree {
+    expr.map_post_order(|_, node| Expand::distribute_node(node))
+  }
+
+  // Distribute the node over its distributed children.
+  fn distribute_node(expr: Tree) -> Tree {
+    match expr {
+      Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Mul, arg })) => match arg.iter().try_fold(Tree::ONE, |acc, e| Expand::product(&acc, e)) {
+        Some(expr) => expr,
+        None => Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Mul, arg })),
+      },
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (base, exp) }) => {
+        let n = match (Expand::terms(&base).len(), exp.as_ref()) {
+          (k @ 2.., Tree::Num(Number::Int(n))) => Natural::try_from(n.clone())
+            .ok()
+            .and_then(|n| u64::try_from(n).ok())
+            .filter(|n| Expand::power_terms(k as u64, *n).is_some()),
+          _ => None,
+        };
+        // ```(a + b)^n = (a + b)*(a + b)^(n - 1)```, collected at each step
+        let expr = n.and_then(|n| {
+          (0..n).try_fold(Tree::ONE, |acc, _| {
+            let acc = Expand::product(&acc, &base)?;
+            Some(acc.clone().trivial().unwrap_or(acc))
+          })
+        });
+        expr.unwrap_or(Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (base, exp) }))
+      }
+      expr => expr,
+    }
+  }
+
+  // ```(a + b)*(c + d) = a*c + a*d + b*c + b*d```, or `None` if there are too many terms or the budget is exhausted.
+  fn product(lhs: &Tree, rhs: &Tree) -> Option<Tree> {
+    budget::step().ok()?;
+    let (lhs, rhs) = (Expand::terms(lhs), Expand::terms(rhs));
+    if lhs.len() * rhs.len() > MAX_TERMS {
+      return None;
+    }
+    let arg = lhs.iter().flat_map(|l| rhs.iter().map(|r| Tree::assoc(AOp::Mul, vec![l.clone(), r.clone()]).edge())).collect();
+    Some(Tree::assoc(AOp::Add, arg))
+  }
+
+  // Bound on the number of terms of a power ```(a_1 + ... + a_k)^n```, ```binomial(n + k - 1, k - 1)```, or `None` if
+  // there are too many.
+  fn power_terms(k: u64, n: u64) -> Option<u64> {
+    (1..k).try_fold(1u64, |c, i| c.checked_mul(n.checked_add(i)?).map(|c| c / i).filter(|c| *c <= MAX_TERMS as u64))
+  }
+
+  fn terms(expr: &Tree) -> Vec<Edge> {
+    match expr {
+      Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Add, arg })) => arg.clone(),
+      expr => vec![expr.clone().edge()],
+    }
   }
 }
 
@@ -1,8 +1,24 @@
 pub mod alg;
+mod assume;
+mod budget;
 pub mod cal;
+mod canon;
+mod comb;
+mod cse;
+mod edge;
+mod eq;
 pub mod fun;
+mod interval;
 pub mod manipulation;
+mod numeric;
+mod radical;
+mod random;
+pub mod rewrite;
+pub mod simplify;
 pub mod sq;
+mod subs;
+pub mod traverse;
+mod zero;
 
 pub mod algebra;
 
@@ -10,27 +26,41 @@ pub mod array;
 pub mod graph;
 
 use std::any;
-use std::borrow::{Borrow, BorrowMut};
+use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::fmt;
 use std::hash::{Hash, Hasher};
-use std::iter;
 use std::sync::Arc;
 
-use algebra::{Constant, Form, Integer, Number, NumberSystem, Rational, SymbolicResult};
+use algebra::{Complex, Constant, Form, Integer, Number, NumberSystem, Rational, SymbolicResult};
 
 pub use alg::{Algebra, Assoc};
+pub use assume::{Assume, Facts};
+pub use budget::{Budget, Cancel, Halt};
 pub use cal::Calculus;
+pub use cse::Cse;
+pub use edge::Edge;
 pub use fun::{Function, Special};
+pub use interval::Interval;
+pub use random::{Random, TreeShape};
+pub use rewrite::{Rule, RuleSet};
+pub use simplify::{Complexity, Cost, Simplifier, Strategy};
 pub use sq::Sequence;
+pub use subs::Substitution;
+pub use traverse::{Fold, Path, Visitor};
+pub use zero::{Zero, ZeroTest};
 
 /// An arbitrary variable.
-#[derive(Debug, Clone, PartialOrd, Ord)]
+#[derive(Debug, Clone)]
 pub struct Symbol {
   /// Name of the symbol.
   name: Arc<str>,
   /// Domain on which its structure applies.
   dom: NumberSystem,
+  /// Assumed properties, including those of its domain.
+  facts: Arc<Facts>,
+  /// Properties assumed beyond its domain, telling the symbol apart from others of the same name and domain.
+  assumed: Option<Arc<Facts>>,
 }
 
 impl Symbol {
@@ -50,6 +80,8 @@ impl Symbol {
         // any indice-valid character
         name: Arc::from(name),
         dom,
+        facts: Arc::new(Facts::from(dom)),
+        assumed: None,
       })
     }
   }
@@ -77,9 +109,6 @@ pub enum Tree {
   Form,
 }
 
-/// A recursive [`Tree`] expression.
-pub type Edge = Arc<Tree>;
-
 /// A marker trait for expression nodes.
 pub trait Node: Sized {
   /// The associated node type.
@@ -266,6 +295,103 @@ pub trait Expr: Sized {
     Tree::Fun(Function::SpecExpr(Special::Gamma(self.edge())))
   }
 
+  /// ```⌊x⌋```
+  fn floor(self) -> Tree {
+    Tree::Fun(Function::SpecExpr(Special::Floor(self.edge())))
+  }
+  /// ```⌈x⌉```
+  fn ceil(self) -> Tree {
+    Tree::Fun(Function::SpecExpr(Special::Ceil(self.edge())))
+  }
+  /// ```round(x)```
+  fn round(self) -> Tree {
+    Tree::Fun(Function::SpecExpr(Special::Round(self.edge())))
+  }
+  /// ```frac(x)```
+  fn frac(self) -> Tree {
+    Tree::Fun(Function::SpecExpr(Special::Frac(self.edge())))
+  }
+  /// ```mod(x, y)```
+  fn modulo<T: Expr>(
+    //.
+    self,
+    o: T,
+  ) -> Tree {
+    Tree::Fun(Function::SpecExpr(Special::Mod(self.edge(), o.edge())))
+  }
+
+  /// ```(n k)```
+  fn binomial<T: Expr>(
+    //.
+    self,
+    k: T,
+  ) -> Tree {
+    Tree::comb(fun::COp::Binomial, vec![self.edge(), k.edge()])
+  }
+  /// ```(k_1 + ... + k_m)!/(k_1!*...*k_m!)```
+  fn multinomial(
+    //.
+    arg: Vec<Tree>,
+  ) -> Tree {
+    Tree::comb(fun::COp::Multinomial, arg.into_iter().map(Tree::edge).collect())
+  }
+  /// ```F_n```
+  fn fibonacci(self) -> Tree {
+    Tree::comb(fun::COp::Fibonacci, vec![self.edge()])
+  }
+  /// ```L_n```
+  fn lucas(self) -> Tree {
+    Tree::comb(fun::COp::Lucas, vec![self.edge()])
+  }
+  /// ```B_n```
+  fn bernoulli(self) -> Tree {
+    Tree::comb(fun::COp::Bernoulli, vec![self.edge()])
+  }
+  /// ```E_n```
+  fn euler(self) -> Tree {
+    Tree::comb(fun::COp::Euler, vec![self.edge()])
+  }
+  /// ```s(n, k)```
+  fn stirling1<T: Expr>(
+    //.
+    self,
+    k: T,
+  ) -> Tree {
+    Tree::comb(fun::COp::Stirling1, vec![self.edge(), k.edge()])
+  }
+  /// ```S(n, k)```
+  fn stirling2<T: Expr>(
+    //.
+    self,
+    k: T,
+  ) -> Tree {
+    Tree::comb(fun::COp::Stirling2, vec![self.edge(), k.edge()])
+  }
+  /// ```B_n``` (Bell)
+  fn bell(self) -> Tree {
+    Tree::comb(fun::COp::Bell, vec![self.edge()])
+  }
+  /// ```C_n```
+  fn catalan(self) -> Tree {
+    Tree::comb(fun::COp::Catalan, vec![self.edge()])
+  }
+  /// ```p(n)```
+  fn partition(self) -> Tree {
+    Tree::comb(fun::COp::Partition, vec![self.edge()])
+  }
+  /// ```σ_k(n)```, with `self` the power `k`
+  fn divisor_sigma<T: Expr>(
+    //.
+    self,
+    n: T,
+  ) -> Tree {
+    Tree::comb(fun::COp::DivisorSigma, vec![self.edge(), n.edge()])
+  }
+  /// ```μ(n)```
+  fn mobius(self) -> Tree {
+    Tree::comb(fun::COp::Mobius, vec![self.edge()])
+  }
+
   /// ```map(x_1, ..., x_n)```
   fn map(
     //.
@@ -342,38 +468,16 @@ pub trait Expr: Sized {
 
 impl<T> Expr for T
 where
-  T: Borrow<Tree> + BorrowMut<Tree>,
+  T: Borrow<Tree> + Clone,
   T: Into<Edge>,
-  T: Into<Tree>,
+  T: Into<Tree> + From<Tree>,
 {
   fn edge(self) -> Edge {
     self.into()
   }
 
   fn trivial(self) -> SymbolicResult<Tree> {
-    let tree = self.into();
-    match tree {
-      Tree::Form => Err(Form {}),
-      Tree::Sym(_) | Tree::Cte(_) => Ok(
-        tree, //.
-      ),
-      Tree::Num(
-        n, //.
-      ) => Ok(Tree::Num(n.trivial()?)),
-
-      Tree::Alg(
-        a, //.
-      ) => a.alg_trivial(),
-      Tree::Fun(
-        f, //.
-      ) => f.fun_trivial(),
-      Tree::Cal(
-        c, //.
-      ) => c.cal_trivial(),
-      Tree::Sq(
-        s, //.
-      ) => s.sq_trivial(),
-    }
+    budget::simplify(self.into(), Tree::node_trivial)
   }
 
   fn visit<B, F>(
@@ -425,9 +529,21 @@ where
             // n
             map,
           ) => match map {
-            Special::Gamma(arg) => f(init, arg),
+            Special::Gamma(arg) | Special::Floor(arg) | Special::Ceil(arg) | Special::Round(arg) | Special::Frac(arg) => f(init, arg),
+            Special::Mod(lhs, rhs) => f(f(init, lhs), rhs),
           },
 
+          Function::CombExpr {
+            // n
+            map: _,
+            arg,
+          } => {
+            arg.iter().fold(
+              init, //.
+              |acc, e| f(acc, e),
+            )
+          }
+
           Function::MapExpr {
             // n
             map: _,
@@ -442,8 +558,7 @@ where
       }
 
       Tree::Sq(sq) => f(f(f(init, &sq.arg), &sq.lo), &sq.up),
-      // change
-      Tree::Cal(_) => init,
+      Tree::Cal(cal) => f(init, &cal.arg),
 
       lit => {
         f(
@@ -461,22 +576,23 @@ where
   ) where
     F: Fn(&mut Tree),
   {
-    match self.borrow_mut() {
+    let mut root: Tree = self.clone().into();
+    match &mut root {
       Tree::Alg(alg) => {
         match alg {
           Algebra::UExpr {
             // 1
             map: _,
             arg,
-          } => f(arg.borrow_mut()),
+          } => arg.modify(&f),
 
           Algebra::BExpr {
             // 2
             map: _,
             arg,
           } => {
-            f(arg.0.borrow_mut());
-            f(arg.1.borrow_mut());
+            arg.0.modify(&f);
+            arg.1.modify(&f);
           }
 
           Algebra::AssocExpr(Assoc {
@@ -485,7 +601,7 @@ where
             arg,
           }) => {
             arg.iter_mut().for_each(
-              |e| f(e.borrow_mut()), //.
+              |e| e.modify(&f), //.
             )
           }
         }
@@ -497,15 +613,29 @@ where
             // 1
             map: _,
             arg,
-          } => f(arg.borrow_mut()),
+          } => arg.modify(&f),
 
           Function::SpecExpr(
             // n
             map,
           ) => match map {
-            Special::Gamma(arg) => f(arg.borrow_mut()),
+            Special::Gamma(arg) | Special::Floor(arg) | Special::Ceil(arg) | Special::Round(arg) | Special::Frac(arg) => arg.modify(&f),
+            Special::Mod(lhs, rhs) => {
+              lhs.modify(&f);
+              rhs.modify(&f)
+            }
           },
 
+          Function::CombExpr {
+            // n
+            map: _,
+            arg,
+          } => {
+            arg.iter_mut().for_each(
+              |e| e.modify(&f), //.
+            )
+          }
+
           Function::MapExpr {
             // n
             map: _,
@@ -519,18 +649,49 @@ where
       }
 
       Tree::Sq(sq) => {
-        f(sq.arg.borrow_mut());
-        f(sq.lo.borrow_mut());
-        f(sq.up.borrow_mut());
+        sq.arg.modify(&f);
+        sq.lo.modify(&f);
+        sq.up.modify(&f);
       }
 
-      // change
-      Tree::Cal(_) => {}
+      Tree::Cal(cal) => cal.arg.modify(&f),
 
       lit => {
         f(lit) //.
       }
     }
+    *self = T::from(root);
+  }
+}
+
+impl Tree {
+  // Simplify the node, its children being simplified first by the work stack of `budget::simplify`.
+  fn node_trivial(self) -> SymbolicResult<Tree> {
+    match self {
+      Tree::Form => Err(Form::default()),
+      Tree::Cte(
+        Constant::i, // ```i = 0 + 1*i```
+      ) => Ok(Tree::from(Complex::I)),
+      Tree::Sym(_) | Tree::Cte(_) => Ok(
+        self, //.
+      ),
+      Tree::Num(
+        n, //.
+      ) => Ok(Tree::Num(n.trivial()?)),
+
+      Tree::Alg(
+        a, //.
+      ) => a.alg_trivial(),
+      Tree::Fun(
+        f, //.
+      ) => f.fun_trivial(),
+      Tree::Cal(
+        c, //.
+      ) => c.cal_trivial(),
+      Tree::Sq(
+        s, //.
+      ) => s.sq_trivial(),
+    }
   }
 }
 
@@ -553,6 +714,7 @@ impl Tree {
       Tree::Sym(s) => s.dom,
       Tree::Cte(c) => c.dom(),
       Tree::Num(n) => n.dom(),
+      Tree::Fun(Function::SpecExpr(Special::Floor(_) | Special::Ceil(_) | Special::Round(_))) => NumberSystem::Z,
       Tree::Form //.rec
     | Tree::Alg(_)
     | Tree::Fun(_)
@@ -564,47 +726,45 @@ impl Tree {
   }
 
   /// Test if the expression is free of expression `expr`.
+  ///
+  /// Occurrences in the scope of a variable bound by a sequence or a calculus operator do not count when `expr`
+  /// depends on that variable, but a calculus operator depends on its own variables.
   pub fn free(&self, expr: &Tree) -> bool {
-    if self.eq(expr) {
-      return false;
-    }
-
-    match self {
-      Tree::Form | Tree::Cte(_) | Tree::Sym(_) | Tree::Num(_) => true,
-      Tree::Alg(_) //.rec
-    | Tree::Fun(_)
-    | Tree::Cal(_)
-    | Tree::Sq(_) => {
-        self.iter().fold(true, |acc, e| acc && e.free(expr))
+    let mut stack = vec![self];
+    while let Some(e) = stack.pop() {
+      if e == expr {
+        return false;
       }
-    }
-  }
 
-  /// Substitute in-place expression `expr` by `replace`.
-  pub fn subs(&mut self, expr: &Tree, replace: &Tree) -> &mut Self {
-    if expr.eq(self) {
-      *self = replace.clone();
-    } else if self.free(expr) {
-      *self = self.clone();
-    } else {
-      match self {
-        Tree::Form | Tree::Cte(_) | Tree::Sym(_) | Tree::Num(_) => *self = replace.clone(),
+      match e {
+        Tree::Form | Tree::Cte(_) | Tree::Sym(_) | Tree::Num(_) => {}
+        Tree::Cal(cal) if cal.var.iter().any(|var| !expr.free(&Tree::Sym(var.clone()))) => return false,
         Tree::Alg(_) //.rec
       | Tree::Fun(_)
       | Tree::Cal(_)
       | Tree::Sq(_) => {
-          self.iter_mut().for_each(|e| {
-            e.subs(
-              expr, //.
-              replace,
-            );
-          })
+          stack.extend(e.children().into_iter().enumerate().filter(|(i, _)| !expr.binds(e.bound(*i))).map(|(_, c)| c));
         }
       }
     }
+    true
+  }
+
+  /// Substitute in-place expression `expr` by `replace`.
+  ///
+  /// Occurrences in the scope of a variable bound by a sequence or a calculus operator are kept when `expr` depends on
+  /// that variable, and bound variables are renamed to avoid capturing those of `replace` (see [`Substitution`]). In
+  /// particular, ```∂(x^2)/∂x``` is kept as is by ```x -> 2```, its derivative is to be evaluated first.
+  pub fn subs(&mut self, expr: &Tree, replace: &Tree) -> &mut Self {
+    *self = Substitution::new([(expr.clone(), replace.clone())]).apply(self);
     self
   }
 
+  // Whether the expression depends on one of the variables `bound`.
+  pub(crate) fn binds(&self, bound: &[Symbol]) -> bool {
+    bound.iter().any(|var| !self.free(&Tree::Sym(var.clone())))
+  }
+
   /// Check if the current expression is a litteral.
   pub fn is_literal(&self) -> bool {
     match self {
@@ -638,23 +798,20 @@ impl Tree {
 
   // Helpers
   pub fn helper_len(&self) -> u64 {
-    match self {
-      Tree::Form => 0,
-      Tree::Sym(_) | Tree::Cte(_) => 1,
-      Tree::Num(n) => n.helper_len(),
-      Tree::Alg(_) //.rec
-    | Tree::Fun(_)
-    | Tree::Cal(_)
-    | Tree::Sq(_) => {
-        self.iter().fold(0, |acc, e| acc + e.helper_len())
-      }
-    }
+    self
+      .pre_order()
+      .map(|e| match e {
+        Tree::Sym(_) | Tree::Cte(_) => 1,
+        Tree::Num(n) => n.helper_len(),
+        _ => 0,
+      })
+      .sum()
   }
 
   pub fn helper_prec(&self) -> u64 {
     match self {
       Tree::Sym(_) | Tree::Cte(_) => 0,
-      Tree::Num(n) => n.helper_len(),
+      Tree::Num(n) => n.helper_prec(),
       Tree::Alg(a) => a.helper_prec(),
       Tree::Form //.rec
     | Tree::Fun(_)
@@ -673,50 +830,85 @@ impl PartialOrd for Tree {
 }
 
 impl Ord for Tree {
+  /// Order the trees as the terms of a polynomial: by their non-numeric factors, each by base then by decreasing
+  /// exponent, the bases being ordered by variant (constants, symbols, operators). The ties, such as between distinct
+  /// constants or sequences, are broken by [`Tree::cmp_structural`], so that only equal trees compare equal.
   fn cmp(&self, o: &Self) -> Ordering {
+    self.cmp_poly(o).then_with(|| self.cmp_structural(o))
+  }
+}
+
+impl Tree {
+  // Polynomial order of the terms: sums after monomials, and monomials by their non-numeric factors, numbers being
+  // the monomials of degree 0.
+  fn cmp_poly(&self, o: &Self) -> Ordering {
     match (self, o) {
       (Tree::Num(l), Tree::Num(r)) => l.cmp(r),
-      (Tree::Sym(l), Tree::Sym(r)) => l.cmp(r),
 
       (
         Tree::Alg(Algebra::AssocExpr(Assoc {
           //.
-          map: lhs_map,
+          map: alg::AOp::Add,
           arg: lhs,
         })),
         Tree::Alg(Algebra::AssocExpr(Assoc {
           //.
-          map: rhs_map,
+          map: alg::AOp::Add,
           arg: rhs,
         })),
-      ) => rhs_map.cmp(lhs_map).then(algebra::poly::order_expr(lhs.iter().map(|l| l.as_ref()), rhs.iter().map(|r| r.as_ref()))),
+      ) => algebra::poly::order_expr(lhs.iter().map(|l| l.as_ref()), rhs.iter().map(|r| r.as_ref())),
+      (Tree::Alg(Algebra::AssocExpr(Assoc { map: alg::AOp::Add, .. })), _) => Ordering::Greater,
+      (_, Tree::Alg(Algebra::AssocExpr(Assoc { map: alg::AOp::Add, .. }))) => Ordering::Less,
+
+      // ```x^2 < x*y < x < 1```
+      (lhs, rhs) => {
+        let (mut lhs, mut rhs) = (lhs.factors(), rhs.factors());
+        loop {
+          match (lhs.next(), rhs.next()) {
+            (Some(l), Some(r)) => match l.cmp_factor(r) {
+              Ordering::Equal => continue,
+              ord => return ord,
+            },
+            (l, r) => return r.is_some().cmp(&l.is_some()),
+          }
+        }
+      }
+    }
+  }
 
-      (
-        Tree::Alg(Algebra::BExpr {
-          //.
-          map: _,
-          arg: (lhs_term, lhs_exp),
-        }),
-        Tree::Alg(Algebra::BExpr {
-          //.
-          map: _,
-          arg: (rhs_term, rhs_exp),
-        }),
-      ) => lhs_term.cmp(rhs_term).then(rhs_exp.cmp(lhs_exp)),
+  // Non-numeric factors of a monomial.
+  fn factors(&self) -> impl Iterator<Item = &Tree> {
+    let (arg, tree) = match self {
+      Tree::Alg(Algebra::AssocExpr(Assoc { map: alg::AOp::Mul, arg })) => (arg.as_slice(), None),
+      tree => (&[][..], Some(tree)),
+    };
+    arg.iter().map(Edge::as_ref).chain(tree).filter(|e| !e.is_value())
+  }
 
-      (
-        Tree::Alg(Algebra::UExpr {
-          //.
-          map: _,
-          arg: lhs,
-        }),
-        Tree::Alg(Algebra::UExpr {
-          //.
-          map: _,
-          arg: rhs,
-        }),
-      ) => lhs.cmp(rhs),
+  // Order of the factors of a monomial, by base then decreasing exponent.
+  fn cmp_factor(&self, o: &Self) -> Ordering {
+    let one = Tree::ONE;
+    let ((lhs, lhs_exp), (rhs, rhs_exp)) = (self.base_exp(), o.base_exp());
+    lhs.cmp_base(rhs).then_with(|| rhs_exp.unwrap_or(&one).cmp(lhs_exp.unwrap_or(&one)))
+  }
 
+  // Base and exponent of a power, which is `None` for other trees.
+  fn base_exp(&self) -> (&Tree, Option<&Tree>) {
+    match self {
+      Tree::Alg(Algebra::BExpr { map: _, arg: (base, exp) }) => (base, Some(exp)),
+      tree => (tree, None),
+    }
+  }
+
+  // Order of the bases of powers: atoms by variant, then by name, map and arguments.
+  fn cmp_base(&self, o: &Self) -> Ordering {
+    let atom = |e: &Tree| !matches!(e, Tree::Num(_) | Tree::Alg(Algebra::AssocExpr(_) | Algebra::BExpr { .. }));
+    if !atom(self) || !atom(o) {
+      return self.cmp(o);
+    }
+    self.atom_rank().cmp(&o.atom_rank()).then_with(|| match (self, o) {
+      (Tree::Sym(l), Tree::Sym(r)) => l.cmp(r),
+      (Tree::Alg(Algebra::UExpr { map: _, arg: lhs }), Tree::Alg(Algebra::UExpr { map: _, arg: rhs })) => lhs.cmp(rhs),
       (
         Tree::Fun(Function::ElemExpr {
           //.
@@ -729,7 +921,6 @@ impl Ord for Tree {
           arg: rhs_arg,
         }),
       ) => lhs_map.cmp(rhs_map).then(lhs_arg.cmp(rhs_arg)),
-
       (
         Tree::Fun(Function::MapExpr {
           //.
@@ -742,23 +933,20 @@ impl Ord for Tree {
           arg: rhs_arg,
         }),
       ) => lhs_map.cmp(rhs_map).then(lhs_arg.iter().cmp(rhs_arg.iter())),
-
-      (Tree::Alg(Algebra::AssocExpr(Assoc { map: lhs_map, arg: lhs })), rhs) => lhs_map.cmp_poly(algebra::poly::order_expr(lhs.iter().map(|l| l.as_ref()), iter::once(rhs)), Ordering::Greater),
-      (lhs, Tree::Alg(Algebra::AssocExpr(Assoc { map: rhs_map, arg: rhs }))) => rhs_map.cmp_poly(algebra::poly::order_expr(iter::once(lhs), rhs.iter().map(|r| r.as_ref())), Ordering::Less),
-
-      (Tree::Alg(Algebra::BExpr { map: _, arg: (term, exp) }), rhs) => term.as_ref().cmp(rhs).then(Tree::ONE.cmp(exp.as_ref())),
-      (lhs, Tree::Alg(Algebra::BExpr { map: _, arg: (term, exp) })) => lhs.cmp(term.as_ref()).then(exp.as_ref().cmp(&Tree::ONE)),
-
-      (Tree::Cte(_), Tree::Sym(_)) => Ordering::Less,
-      (Tree::Sym(_), Tree::Cte(_)) => Ordering::Greater,
-
-      (Tree::Cte(_), Tree::Num(_)) => Ordering::Greater,
-      (Tree::Num(_), Tree::Cte(_)) => Ordering::Less,
-
-      (Tree::Sym(_), _) => Ordering::Less,
-      (_, Tree::Sym(_)) => Ordering::Greater,
-
       _ => Ordering::Equal,
+    })
+  }
+
+  // Variant of an atom: constants, symbols, then operators.
+  fn atom_rank(&self) -> u8 {
+    match self {
+      Tree::Cte(_) => 0,
+      Tree::Sym(_) => 1,
+      Tree::Num(_) | Tree::Alg(_) => 2,
+      Tree::Fun(_) => 3,
+      Tree::Cal(_) => 4,
+      Tree::Sq(_) => 5,
+      Tree::Form => 6,
     }
   }
 }
@@ -860,13 +1048,27 @@ impl<'v> IterMut<'v> {
 impl Eq for Symbol {}
 impl PartialEq for Symbol {
   fn eq(&self, o: &Self) -> bool {
-    self.name.eq(&o.name)
+    self.name.eq(&o.name) && self.dom.eq(&o.dom) && self.assumed.eq(&o.assumed)
+  }
+}
+
+impl PartialOrd for Symbol {
+  fn partial_cmp(&self, o: &Self) -> Option<Ordering> {
+    Some(self.cmp(o))
+  }
+}
+
+impl Ord for Symbol {
+  fn cmp(&self, o: &Self) -> Ordering {
+    self.name.cmp(&o.name).then_with(|| self.dom.cmp(&o.dom)).then_with(|| self.assumed.cmp(&o.assumed))
   }
 }
 
 impl Hash for Symbol {
   fn hash<H: Hasher>(&self, state: &mut H) {
     self.name.hash(state);
+    self.dom.hash(state);
+    self.assumed.hash(state);
   }
 }
 
@@ -878,9 +1080,10 @@ impl fmt::Display for Symbol {
 
 impl fmt::Display for Tree {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let form = Form::default();
     fmt::Display::fmt(
       match self.borrow() {
-        Tree::Form => &Form {} as &dyn fmt::Display,
+        Tree::Form => &form as &dyn fmt::Display,
         Tree::Sym(s) => s,
         Tree::Cte(c) => c,
         Tree::Num(
@@ -907,12 +1110,6 @@ impl fmt::Display for Tree {
 
 // Conversions
 
-impl BorrowMut<Tree> for Edge {
-  fn borrow_mut(&mut self) -> &mut Tree {
-    Arc::make_mut(self)
-  }
-}
-
 impl TryFrom<Tree> for Symbol {
   type Error = Form;
 
@@ -921,7 +1118,7 @@ impl TryFrom<Tree> for Symbol {
       Ok(symbol)
     } else {
       Err(
-        Form {}, //.
+        Form::default(), //.
       )
     }
   }
@@ -942,9 +1139,8 @@ impl From<Rational> for Tree {
   }
 }
 
-impl From<Edge> for Tree {
-  #[inline]
-  fn from(edge: Edge) -> Tree {
-    Arc::try_unwrap(edge).unwrap_or_else(|tree| (*tree).clone())
+impl From<Complex> for Tree {
+  fn from(z: Complex) -> Self {
+    Tree::Num(Number::Cpx(z))
   }
 }
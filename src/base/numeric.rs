@@ -0,0 +1,734 @@
+use crate::base::alg::{AOp, Algebra, Assoc, BOp, UOp};
+use crate::base::algebra::Round;
+use crate::base::budget;
+use crate::base::comb;
+use crate::base::fun::{COp, EOp, Function, Special};
+use crate::base::sq::{Sequence, SqOp};
+use crate::{Constant, Float, Form, Halt, Integer, Natural, Number, Rational, SymbolicResult};
+use crate::{Expr, Tree};
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Precision (in bits) of the error radius.
+const RAD_PREC: usize = 32;
+
+/// The reason a numerical evaluation failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Fault {
+  /// The expression has no real value (pole, domain error, free symbol, ...).
+  Form,
+  /// The working precision is insufficient to bound the result.
+  Precision,
+  /// The budget of the running computation is exhausted.
+  Halt(Halt),
+}
+
+pub(super) type Approx = Result<Ball, Fault>;
+
+impl From<Form> for Fault {
+  fn from(form: Form) -> Self {
+    form.halt().map_or(Fault::Form, Fault::Halt)
+  }
+}
+
+impl Fault {
+  /// The form reported for the failure, carrying the reason the budget stopped the evaluation.
+  pub(super) fn into_form(self) -> Form {
+    match self {
+      Fault::Halt(halt) => Form::halted(halt),
+      Fault::Form | Fault::Precision => Form::default(),
+    }
+  }
+}
+
+/// A real ball `[mid - rad, mid + rad]` enclosing an exact value.
+#[derive(Debug, Clone)]
+pub(super) struct Ball {
+  /// Midpoint, at working precision.
+  pub(super) mid: Float,
+  /// Radius, rounded up.
+  pub(super) rad: Float,
+}
+
+/// Upper bound of `|x|`.
+fn up(x: &Float) -> Float {
+  x.clone().abs().with_rnd(Round::Ceil).with_prec(RAD_PREC)
+}
+
+/// Lower bound of `|x|`.
+fn lo(x: &Float) -> Float {
+  x.clone().abs().with_rnd(Round::Floor).with_prec(RAD_PREC)
+}
+
+/// Bound of the error of `x` computed with an error of at most `2^k` ulps.
+fn err(x: &Float, k: i64) -> Float {
+  up(x).ldexp(k - x.prec() as i64)
+}
+
+impl Ball {
+  pub(super) fn exact(mid: Float) -> Ball {
+    Ball {
+      mid, //.
+      rad: Float::zero(RAD_PREC, Round::Ceil),
+    }
+  }
+
+  /// A ball around the correctly rounded value `mid`.
+  fn rounded(mid: Float) -> Ball {
+    let rad = err(&mid, 0);
+    Ball { mid, rad }
+  }
+
+  /// A ball around the value `mid` computed with an error below 2 ulps, widened by `rad`.
+  pub(super) fn approx(mid: Float, rad: Float) -> Ball {
+    let rad = rad + err(&mid, 1);
+    Ball { mid, rad }
+  }
+
+  pub(super) fn from_number(n: &Number, w: usize) -> Ball {
+    let mid = match n {
+      Number::Int(z) => Float::from_integer(z.clone(), w, Round::Nearest),
+      Number::Rat(q) => Float::from_rational(q.clone(), w, Round::Nearest),
+      Number::Flt(x) => x.clone().with_rnd(Round::Nearest).with_prec(w),
+      Number::Cpx(_) => unreachable!("complex numbers are rejected by `Tree::ball`"),
+      // irrational, so never exact
+      Number::Alg(a) => return Ball::rounded(a.to_float(w, Round::Nearest)),
+    };
+
+    if Some(mid.to_rational()) == n.to_rational() {
+      Ball::exact(mid)
+    } else {
+      Ball::rounded(mid)
+    }
+  }
+
+  fn from_integer(z: i64, w: usize) -> Ball {
+    Ball::from_number(&Number::Int(Integer::from(z)), w)
+  }
+
+  fn is_exact(&self) -> bool {
+    self.rad.is_zero()
+  }
+
+  fn prec(&self) -> usize {
+    self.mid.prec()
+  }
+
+  /// Compare the ball with zero, if it does not contain it.
+  pub(super) fn sgn(&self) -> Option<Ordering> {
+    if self.is_exact() && self.mid.is_zero() {
+      Some(Ordering::Equal)
+    } else if lo(&self.mid) > self.rad {
+      Some(if self.mid.is_negative() { Ordering::Less } else { Ordering::Greater })
+    } else {
+      None
+    }
+  }
+
+  /// Whether every value in the ball is below ```2^k``` in absolute value.
+  pub(super) fn below(&self, k: i64) -> bool {
+    up(&self.mid) + self.rad.clone() < Float::one(RAD_PREC, Round::Ceil).ldexp(k)
+  }
+
+  fn neg(self) -> Ball {
+    Ball { mid: -self.mid, ..self }
+  }
+
+  fn add(self, o: Ball) -> Ball {
+    let mid = self.mid + o.mid;
+    let rad = self.rad + o.rad + err(&mid, 0);
+    Ball { mid, rad }
+  }
+
+  fn sub(self, o: Ball) -> Ball {
+    self.add(o.neg())
+  }
+
+  fn mul(self, o: Ball) -> Ball {
+    // ```|xy - x₀y₀| <= |x₀|*r_y + |y₀|*r_x + r_x*r_y```
+    let rad = up(&self.mid) * o.rad.clone() + up(&o.mid) * self.rad.clone() + self.rad * o.rad;
+    let mid = self.mid * o.mid;
+    let rad = rad + err(&mid, 0);
+    Ball { mid, rad }
+  }
+
+  fn inv(self) -> Approx {
+    match self.sgn() {
+      Some(Ordering::Equal) => Err(Fault::Form),
+      None => Err(Fault::Precision),
+      Some(_) => {
+        // ```|1/y - 1/y₀| <= r/(|y₀|*(|y₀| - r))```
+        let m = lo(&self.mid);
+        let den = (m.clone() - self.rad.clone()) * m;
+        let mid = Float::one(self.prec(), Round::Nearest) / self.mid;
+        let rad = self.rad / den;
+        let rad = rad + err(&mid, 0);
+        Ok(Ball { mid, rad })
+      }
+    }
+  }
+
+  fn div(self, o: Ball) -> Approx {
+    Ok(self.mul(o.inv()?))
+  }
+
+  fn powi(self, n: &Integer) -> Approx {
+    let mut k = n.clone().abs();
+    let mut base = self.clone();
+    let mut acc = Ball::from_integer(1, self.prec());
+    while k != Natural::ZERO {
+      if k.bit(0) {
+        acc = acc.mul(base.clone());
+      }
+      k >>= 1;
+      if k != Natural::ZERO {
+        base = base.clone().mul(base);
+      }
+    }
+
+    if n.is_negative() {
+      acc.inv()
+    } else {
+      Ok(acc)
+    }
+  }
+
+  pub(super) fn sqrt(self) -> Approx {
+    match self.sgn() {
+      Some(Ordering::Equal) => Ok(self),
+      Some(Ordering::Less) => Err(Fault::Form),
+      None if self.mid.clone() + self.rad.clone() < Float::zero(RAD_PREC, Round::Nearest) => Err(Fault::Form),
+      None => Err(Fault::Precision),
+      Some(Ordering::Greater) => {
+        // ```|sqrt(x) - sqrt(x₀)| <= r/sqrt(x₀)```
+        let rad = self.rad / lo(&self.mid).sqrt()?;
+        let mid = self.mid.sqrt()?;
+        let rad = rad + err(&mid, 0);
+        Ok(Ball { mid, rad })
+      }
+    }
+  }
+
+  /// Apply `f` with a Lipschitz constant `lip` on the ball.
+  fn lipschitz<F>(self, f: F, lip: Option<Float>) -> Approx
+  where
+    F: FnOnce(Float) -> SymbolicResult<Float>,
+  {
+    let rad = match lip {
+      Some(lip) => self.rad * lip,
+      None => self.rad,
+    };
+    Ok(Ball::approx(f(self.mid)?, rad))
+  }
+
+  /// Upper bound of `|x|` over the ball.
+  fn hi(&self) -> Float {
+    up(&self.mid) + self.rad.clone()
+  }
+
+  fn exp(self) -> Approx {
+    if self.rad > Float::one(RAD_PREC, Round::Ceil) {
+      return Err(Fault::Precision);
+    }
+    // ```exp(x)``` overflows the exponent range, ```x >= 2^47```, and underflows it, ```x <= -2^47```, where
+    // ```0 < exp(x) < 2^-2^47```
+    let limit = Float::one(RAD_PREC, Round::Ceil).ldexp(47);
+    let upper = if self.mid.is_negative() {
+      self.rad.clone() - lo(&self.mid)
+    } else {
+      up(&self.mid) + self.rad.clone()
+    };
+    if upper >= limit {
+      return Err(Fault::Form);
+    }
+    if upper <= -limit {
+      let half = Float::one(RAD_PREC, Round::Ceil).ldexp(-(1 << 47) - 1);
+      return Ok(Ball { mid: half.clone(), rad: half });
+    }
+
+    // ```|exp(x) - exp(x₀)| <= exp(x₀)*(exp(r) - 1) <= 2*exp(x₀)*r, r <= 1```
+    let mid = self.mid.exp()?;
+    let rad = up(&mid).ldexp(1) * self.rad;
+    Ok(Ball::approx(mid, rad))
+  }
+
+  fn log(self) -> Approx {
+    match self.sgn() {
+      Some(Ordering::Equal | Ordering::Less) => Err(Fault::Form),
+      None if self.mid.is_negative() || self.mid.is_zero() => Err(Fault::Form),
+      None => Err(Fault::Precision),
+      Some(Ordering::Greater) => {
+        // ```|log(x) - log(x₀)| <= r/(x₀ - r)```
+        let lip = Float::one(RAD_PREC, Round::Ceil) / (lo(&self.mid) - self.rad.clone());
+        self.lipschitz(Float::log, Some(lip))
+      }
+    }
+  }
+
+  /// Lipschitz constant `1/sqrt(1 - (|x₀| + r)^2)` of arcsin and arccos, if the ball is inside `]-1, 1[`.
+  fn lip_arcsin(&self) -> Option<Float> {
+    let hi = self.hi();
+    let den = Float::one(RAD_PREC, Round::Floor) - hi.clone() * hi;
+    if den.is_zero() || den.is_negative() {
+      None
+    } else {
+      Some(Float::one(RAD_PREC, Round::Ceil) / den.sqrt().ok()?)
+    }
+  }
+
+  /// Apply an inverse function defined on `[-1, 1]` (arcsin, arccos).
+  fn unit<F>(self, f: F) -> Approx
+  where
+    F: FnOnce(Float) -> SymbolicResult<Float>,
+  {
+    let one = Float::one(RAD_PREC, Round::Nearest);
+    if self.is_exact() {
+      return self.lipschitz(f, None);
+    }
+    if self.mid.clone().abs() - self.rad.clone() > one {
+      return Err(Fault::Form);
+    }
+
+    match self.lip_arcsin() {
+      Some(lip) => self.lipschitz(f, Some(lip)),
+      None => Err(Fault::Precision),
+    }
+  }
+
+  fn sinh(self) -> Approx {
+    // ```|sinh'(x)| = cosh(x) <= cosh(|x₀| + r)```
+    let lip = up(&self.hi().with_rnd(Round::Nearest).with_prec(self.prec()).cosh()?).ldexp(1);
+    self.lipschitz(Float::sinh, Some(lip))
+  }
+
+  fn cosh(self) -> Approx {
+    // ```|cosh'(x)| = |sinh(x)| <= cosh(|x₀| + r)```
+    let lip = up(&self.hi().with_rnd(Round::Nearest).with_prec(self.prec()).cosh()?).ldexp(1);
+    self.lipschitz(Float::cosh, Some(lip))
+  }
+
+  fn arcosh(self) -> Approx {
+    let one = Float::one(RAD_PREC, Round::Nearest);
+    if self.is_exact() {
+      return self.lipschitz(Float::arcosh, None);
+    }
+    if self.mid.clone() + self.rad.clone() < one {
+      return Err(Fault::Form);
+    }
+
+    // ```|arcosh'(x)| <= 1/sqrt((x₀ - r)^2 - 1)```
+    let low = (self.mid.clone().with_rnd(Round::Floor) - self.rad.clone()).with_prec(RAD_PREC);
+    if low.is_negative() {
+      return Err(Fault::Precision);
+    }
+    let den = low.clone() * low - Float::one(RAD_PREC, Round::Floor);
+    if den.is_zero() || den.is_negative() {
+      return Err(Fault::Precision);
+    }
+    let lip = Float::one(RAD_PREC, Round::Ceil) / den.sqrt()?;
+    self.lipschitz(Float::arcosh, Some(lip))
+  }
+
+  fn artanh(self) -> Approx {
+    let one = Float::one(RAD_PREC, Round::Nearest);
+    if self.mid.clone().abs() - self.rad.clone() >= one {
+      return Err(Fault::Form);
+    }
+
+    // ```|artanh'(x)| <= 1/(1 - (|x₀| + r)^2)```
+    let hi = self.hi();
+    let den = Float::one(RAD_PREC, Round::Floor) - hi.clone() * hi;
+    if den.is_zero() || den.is_negative() {
+      return Err(Fault::Precision);
+    }
+    let lip = Float::one(RAD_PREC, Round::Ceil) / den;
+    self.lipschitz(Float::artanh, Some(lip))
+  }
+
+  pub(super) fn elem(self, map: EOp) -> Approx {
+    let w = self.prec();
+    match map {
+      EOp::Sin => self.lipschitz(|x| Ok(x.sin()), None),
+      EOp::Cos => self.lipschitz(|x| Ok(x.cos()), None),
+      EOp::Tan => self.clone().elem(EOp::Sin)?.div(self.elem(EOp::Cos)?),
+      EOp::ArcSin => self.unit(Float::arcsin),
+      EOp::ArcCos => self.unit(Float::arccos),
+      EOp::ArcTan => self.lipschitz(|x| Ok(x.arctan()), None),
+
+      EOp::Sinh => self.sinh(),
+      EOp::Cosh => self.cosh(),
+      EOp::Tanh => self.lipschitz(|x| Ok(x.tanh()), None),
+      EOp::ArSinh => self.lipschitz(|x| Ok(x.arsinh()), None),
+      EOp::ArCosh => self.arcosh(),
+      EOp::ArTanh => self.artanh(),
+
+      EOp::Exp => self.exp(),
+      EOp::Log => self.log(),
+    }
+    .map(|b| Ball { mid: b.mid.with_prec(w), rad: b.rad })
+  }
+
+  /// Integer part by `rnd`, exact once every point of the ball agrees.
+  fn integer_part(self, rnd: Round) -> Approx {
+    let low = (self.mid.clone().with_rnd(Round::Floor) - self.rad.clone()).to_integer(rnd);
+    let high = (self.mid.clone().with_rnd(Round::Ceil) + self.rad.clone()).to_integer(rnd);
+    if low == high {
+      Ok(Ball::from_number(&Number::Int(low), self.prec()))
+    } else {
+      Err(Fault::Precision)
+    }
+  }
+
+  pub(super) fn pi(w: usize) -> Ball {
+    Ball::approx(Float::pi(w, Round::Nearest), Float::zero(RAD_PREC, Round::Ceil))
+  }
+
+  /// Return `true` if the ball is exactly a nonpositive integer.
+  fn pole(&self) -> bool {
+    let n = self.mid.to_integer(Round::Zero);
+    self.is_exact() && !n.is_positive() && Float::from_integer(n, self.prec(), Round::Nearest) == self.mid
+  }
+
+  pub(super) fn gamma(self) -> Approx {
+    let w = self.prec();
+    if self.pole() {
+      return Err(Fault::Form);
+    }
+
+    let half = Float::one(w, Round::Nearest).ldexp(-1);
+    if self.mid < half {
+      // ```Γ(x) = π/(sin(π*x)*Γ(1 - x))```
+      let pi = Ball::pi(w);
+      let sin = pi.clone().mul(self.clone()).elem(EOp::Sin)?;
+      let refl = Ball::from_integer(1, w).sub(self).gamma()?;
+      return pi.div(sin.mul(refl));
+    }
+
+    // ```Γ(x) = Γ(x + n)/(x*(x + 1)*...*(x + n - 1))```
+    let shift = Float::from_integer(Integer::from(w as u64), w, Round::Nearest) - self.mid.clone();
+    let n = if shift.is_negative() {
+      0
+    } else {
+      u64::try_from(shift.to_integer(Round::Ceil).abs()).map_err(|_| Fault::Precision)?
+    };
+    let mut den = Ball::from_integer(1, w);
+    let mut z = self;
+    for _ in 0..n {
+      den = den.mul(z.clone());
+      z = z.add(Ball::from_integer(1, w));
+    }
+
+    z.stirling()?.exp()?.div(den)
+  }
+
+  /// Compute `log(Γ(z))` for a large positive `z` with the Stirling series.
+  fn stirling(self) -> Approx {
+    // ```log(Γ(z)) = (z - 1/2)*log(z) - z + log(2π)/2 + ∑{k=1->K} B_2k/(2k*(2k - 1)*z^(2k - 1)) + R_K```
+    let w = self.prec();
+    let half = Ball::from_number(&Number::Rat(Rational::new(Integer::ONE, Integer::TWO)), w);
+    let log2pi = Ball::pi(w).mul(Ball::from_integer(2, w)).log()?;
+    let mut lgamma = self.clone().sub(half.clone()).mul(self.clone().log()?).sub(self.clone()).add(half.mul(log2pi));
+
+    let inv = self.inv()?;
+    let inv2 = inv.clone().mul(inv.clone());
+    let mut pow = inv;
+    let mut bernoulli = Bernoulli::default();
+    let bound = Float::one(RAD_PREC, Round::Nearest).ldexp(-(w as i64) - 4);
+
+    for k in 1.. {
+      let b = bernoulli.next_even()?.to_rational().ok_or(Fault::Form)?;
+      let coef = Number::Rat(Rational::new(b.num, b.den * Integer::from(2 * k * (2 * k - 1)))).trivial()?;
+      let term = Ball::from_number(&coef, w).mul(pow.clone());
+
+      // ```|R_K| <= |B_2(K + 1)/(2(K + 1)*(2(K + 1) - 1)*z^(2K + 1))|``` for real ```z > 0```
+      if term.hi() < bound.clone() * up(&lgamma.mid).max(Float::one(RAD_PREC, Round::Ceil)) {
+        lgamma.rad = lgamma.rad + term.hi();
+        return Ok(lgamma);
+      }
+      if k > 4 * w as u64 {
+        return Err(Fault::Precision);
+      }
+
+      lgamma = lgamma.add(term);
+      pow = pow.mul(inv2.clone());
+    }
+
+    unreachable!()
+  }
+}
+
+/// Iterator over the even Bernoulli numbers `B_2, B_4, ...`.
+#[derive(Debug, Default)]
+struct Bernoulli {
+  /// All Bernoulli numbers `B_0, B_1, ...` computed so far.
+  seq: Vec<Number>,
+}
+
+impl Bernoulli {
+  fn next_even(&mut self) -> Result<Number, Fault> {
+    if self.seq.is_empty() {
+      self.seq.push(Number::Int(Integer::ONE));
+      self.seq.push(Number::Rat(Rational::new(Integer::NEG_ONE, Integer::TWO)));
+    }
+
+    // ```B_m = -1/(m + 1)*∑{j=0->m-1} C(m + 1, j)*B_j```
+    let m = self.seq.len() as u64;
+    let mut binom = Integer::ONE;
+    let mut sum = Number::Int(Integer::ZERO);
+    for (j, b) in self.seq.iter().enumerate() {
+      sum = (sum + (b.clone() * Number::Int(binom.clone()))?)?;
+      binom = binom * Integer::from(m + 1 - j as u64) / Integer::from(j as u64 + 1);
+    }
+    let b = (sum * Number::Rat(Rational::new(Integer::NEG_ONE, Integer::from(m + 1))))?;
+
+    // ```B_2k+1 = 0, k > 0```
+    self.seq.push(b.clone());
+    self.seq.push(Number::Int(Integer::ZERO));
+    Ok(b)
+  }
+}
+
+impl Tree {
+  /// Numerically evaluate the expression, with `digits` guaranteed significant decimal digits.
+  ///
+  /// The evaluation uses ball arithmetic (a midpoint with a rigorous error radius) and increases the working
+  /// precision until every value in the ball rounds to the same decimal number. The result is the binary
+  /// float closest to that decimal, whose precision displays exactly `digits` digits. A value that cannot be
+  /// separated from zero at the highest working precision fails, unless it is exactly zero.
+  pub fn evalf(&self, digits: usize) -> SymbolicResult<Float> {
+    let digits = digits.max(1);
+
+    // largest precision displaying `digits` digits
+    // ```⌊prec*log10(2)⌋ = digits```
+    let mut prec = digits * 100000 / 30103;
+    while (prec + 1) * 30103 / 100000 <= digits {
+      prec += 1;
+    }
+
+    let max = 16 * prec + 4096;
+    let mut w = prec + 32;
+    loop {
+      let fault = match self.ball(w) {
+        Ok(ball) => {
+          if ball.is_exact() && ball.mid.is_zero() {
+            return Ok(Float::zero(prec, Round::Nearest));
+          }
+
+          let low = ball.mid.clone().with_rnd(Round::Floor) - ball.rad.clone();
+          let high = ball.mid.clone().with_rnd(Round::Ceil) + ball.rad.clone();
+          if low.is_negative() == high.is_negative() && !low.is_zero() && !high.is_zero() && low.decimal(digits) == high.decimal(digits) {
+            let (n, d) = low.decimal(digits);
+            let scale = d - digits as i64 + 1;
+            let ten = Integer::from(10).pow(scale.unsigned_abs());
+            let n = if low.is_negative() { -Integer::from(n) } else { Integer::from(n) };
+            let q = if scale >= 0 { Rational::from(n * ten) } else { Rational::new(n, ten) };
+            return Ok(Float::from_rational(q, prec, Round::Nearest));
+          }
+          Fault::Precision
+        }
+        Err(fault) => fault,
+      };
+
+      match fault {
+        Fault::Precision if w < max => w = (2 * w).min(max),
+        fault => return Err(fault.into_form()),
+      }
+    }
+  }
+
+  pub(super) fn ball(&self, w: usize) -> Approx {
+    // balls of the subexpressions, the children before their parent
+    let mut done: HashMap<&Tree, Approx> = HashMap::new();
+    let mut stack = vec![(self, false)];
+    while let Some((e, ready)) = stack.pop() {
+      if done.contains_key(e) {
+        continue;
+      }
+
+      if ready {
+        let sub = e.operands().into_iter().map(|c| done[c].clone()).collect();
+        done.insert(e, e.ball_node(w, sub));
+      } else {
+        stack.push((e, true));
+        stack.extend(e.operands().into_iter().map(|c| (c, false)));
+      }
+    }
+    done.remove(self).unwrap_or(Err(Fault::Form))
+  }
+
+  // The children whose enclosures are combined into the enclosure of the node, the others being evaluated on their
+  // own.
+  pub(super) fn operands(&self) -> Vec<&Tree> {
+    match self {
+      Tree::Sq(_) | Tree::Fun(Function::CombExpr { .. } | Function::MapExpr { .. }) | Tree::Cal(_) => vec![],
+      e => e.children(),
+    }
+  }
+
+  // Enclose the value of the node, from the balls `sub` of its operands.
+  fn ball_node(&self, w: usize, sub: Vec<Approx>) -> Approx {
+    budget::step()?;
+    let mut sub = sub.into_iter();
+    let mut next = move || sub.next().unwrap_or(Err(Fault::Form));
+    match self {
+      Tree::Num(Number::Cpx(_)) => Err(Fault::Form),
+      Tree::Num(n) => Ok(Ball::from_number(n, w)),
+
+      Tree::Cte(Constant::pi) => Ok(Ball::pi(w)),
+      Tree::Cte(Constant::e) => Ok(Ball::approx(Float::e(w, Round::Nearest), Float::zero(RAD_PREC, Round::Ceil))),
+      Tree::Cte(Constant::i | Constant::Infinity(_)) => Err(Fault::Form),
+
+      Tree::Alg(Algebra::AssocExpr(Assoc { map, arg })) => {
+        let mut acc = match map {
+          AOp::Add => Ball::exact(Float::zero(w, Round::Nearest)),
+          AOp::Mul => Ball::exact(Float::one(w, Round::Nearest)),
+        };
+        for _ in arg {
+          let e = next()?;
+          acc = match map {
+            AOp::Add => acc.add(e),
+            AOp::Mul => acc.mul(e),
+          };
+        }
+        Ok(acc)
+      }
+
+      Tree::Alg(Algebra::UExpr { map: UOp::Id, .. }) => next(),
+      // ```x! = Γ(x + 1)```
+      Tree::Alg(Algebra::UExpr { map: UOp::Fact, .. }) => next()?.add(Ball::from_integer(1, w)).gamma(),
+
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (_, rhs) }) => {
+        let (base, exp) = (next()?, next());
+        match rhs.as_ref() {
+          Tree::Num(Number::Int(n)) => base.powi(n),
+          Tree::Num(Number::Rat(q)) if q.den == Integer::TWO => base.sqrt()?.powi(&q.num),
+          _ => match base.sgn() {
+            // ```0^y = 0, y > 0```
+            Some(Ordering::Equal) => match exp?.sgn() {
+              Some(Ordering::Greater) => Ok(base),
+              Some(_) => Err(Fault::Form),
+              None => Err(Fault::Precision),
+            },
+            // ```x^y = exp(y*log(x))```
+            _ => exp?.mul(base.log()?).exp(),
+          },
+        }
+      }
+
+      Tree::Fun(Function::ElemExpr { map, .. }) => next()?.elem(*map),
+      Tree::Fun(Function::SpecExpr(Special::Gamma(_))) => next()?.gamma(),
+      Tree::Fun(Function::SpecExpr(Special::Floor(_))) => next()?.integer_part(Round::Floor),
+      Tree::Fun(Function::SpecExpr(Special::Ceil(_))) => next()?.integer_part(Round::Ceil),
+      Tree::Fun(Function::SpecExpr(Special::Round(_))) => next()?.integer_part(Round::Nearest),
+      // ```frac(x) = x - ⌊x⌋```
+      Tree::Fun(Function::SpecExpr(Special::Frac(_))) => {
+        let x = next()?;
+        Ok(x.clone().sub(x.integer_part(Round::Floor)?))
+      }
+      // ```mod(x, y) = x - y*⌊x/y⌋```
+      Tree::Fun(Function::SpecExpr(Special::Mod(..))) => {
+        let (x, y) = (next()?, next()?);
+        let q = x.clone().mul(y.clone().inv()?).integer_part(Round::Floor)?;
+        Ok(x.sub(y.mul(q)))
+      }
+
+      Tree::Sq(Sequence { map, idx, lo, up, arg }) => {
+        let (Tree::Num(Number::Int(lo)), Tree::Num(Number::Int(up))) = (lo.as_ref().clone().trivial()?, up.as_ref().clone().trivial()?) else {
+          return Err(Fault::Form);
+        };
+
+        let mut acc = match map {
+          SqOp::Sum => Ball::exact(Float::zero(w, Round::Nearest)),
+          SqOp::Prod => Ball::exact(Float::one(w, Round::Nearest)),
+        };
+        let mut k = lo;
+        while k <= up {
+          let mut e = arg.as_ref().clone();
+          e.subs(&Tree::Sym(idx.clone()), &Tree::from(k.clone()));
+          let e = e.ball(w)?;
+          acc = match map {
+            SqOp::Sum => acc.add(e),
+            SqOp::Prod => acc.mul(e),
+          };
+          k.incr();
+        }
+        Ok(acc)
+      }
+
+      // exact at integers, through the analytic continuation otherwise
+      Tree::Fun(fun @ Function::CombExpr { map, arg }) => match fun.clone().fun_trivial()? {
+        Tree::Fun(Function::CombExpr { .. }) => match (arg.as_slice(), map) {
+          ([x], COp::Fibonacci | COp::Lucas) => comb::continuation(*map, x).ok_or(Fault::Form)?.ball(w),
+          _ => Err(Fault::Form),
+        },
+        e => e.ball(w),
+      },
+
+      Tree::Sym(_) | Tree::Fun(Function::MapExpr { .. }) | Tree::Cal(_) | Tree::Form => Err(Fault::Form),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Symbol;
+
+  fn evalf(expr: Tree, digits: usize) -> String {
+    expr.evalf(digits).map_or_else(|_| "?".to_string(), |x| x.to_string())
+  }
+
+  #[test]
+  fn constants() {
+    assert_eq!(evalf(Tree::Cte(Constant::pi), 30), "3.14159265358979323846264338328");
+    assert_eq!(evalf(Tree::Cte(Constant::e), 20), "2.7182818284590452354");
+    assert_eq!(evalf(Tree::from(2).sqrt(), 25), "1.414213562373095048801689");
+    assert_eq!(evalf(Tree::from(1).div(Tree::from(8)), 10), "0.125");
+    assert_eq!(evalf(Tree::from(-2).div(Tree::from(3)), 5), "-0.66667");
+    assert_eq!(evalf(Tree::Cte(Constant::i), 5), "?");
+  }
+
+  #[test]
+  fn functions() {
+    // ```sqrt(2)*π + Γ(1/3)```
+    let expr = Tree::from(2)
+      .sqrt()
+      .mul(Tree::Cte(Constant::pi))
+      .add(Tree::from(Rational::new(Integer::from(1), Integer::from(3))).gamma());
+    assert_eq!(evalf(expr, 30), "7.12182147286611388067157393104");
+
+    assert_eq!(evalf(Tree::from(1).exp(), 20), "2.7182818284590452354");
+    assert_eq!(evalf(Tree::from(10).log(), 20), "2.302585092994045684");
+    assert_eq!(evalf(Tree::from(1).arcsin(), 15), "1.5707963267949");
+    assert_eq!(evalf(Tree::from(5).fact(), 10), "120.0");
+    assert_eq!(evalf(Tree::from(Rational::new(Integer::from(1), Integer::from(2))).gamma(), 20), "1.7724538509055160273");
+    assert_eq!(evalf(Tree::from(Rational::new(Integer::from(-1), Integer::from(2))).gamma(), 20), "-3.5449077018110320546");
+    assert_eq!(evalf(Tree::from(0).gamma(), 10), "?");
+    assert_eq!(evalf(Tree::from(0).log(), 10), "?");
+    assert_eq!(evalf(Tree::from(-1).sqrt(), 10), "?");
+
+    // ```sin(π) = 0``` cannot be separated from zero, nor can ```sin(π) + 10^-2000```
+    assert_eq!(evalf(Tree::Cte(Constant::pi).sin(), 10), "?");
+    assert_eq!(evalf(Tree::Cte(Constant::pi).sin().add(Tree::from(10).pow(Tree::from(-2000))), 10), "?");
+    // ```exp(2^50)``` overflows, ```exp(-2^50)``` underflows toward 0
+    let big = Tree::from(2).pow(Tree::from(50));
+    assert!(big.clone().exp().ball(64).is_err());
+    let tiny = big.neg().exp().ball(64).expect("failed to enclose a tiny value");
+    assert!(tiny.mid.with_rnd(Round::Ceil) + tiny.rad < Float::one(RAD_PREC, Round::Ceil).ldexp(-(1 << 40)));
+    // ```exp(π*sqrt(163))``` is almost an integer
+    let expr = Tree::Cte(Constant::pi).mul(Tree::from(163).sqrt()).exp();
+    assert_eq!(evalf(expr, 32), "262537412640768743.99999999999925");
+  }
+
+  #[test]
+  fn sequences() {
+    let k = Symbol::new("k", Number::Z).expect("failed to declare symbol `k`");
+    // ```∑{k=1->10} 1/k```
+    let expr = Tree::from(1).div(Tree::Sym(k.clone())).sum(k.clone(), Tree::from(1), Tree::from(10));
+    assert_eq!(evalf(expr, 15), "2.92896825396825");
+    // ```∏{k=1->5} k```
+    let expr = Tree::Sym(k.clone()).product(k, Tree::from(1), Tree::from(5));
+    assert_eq!(evalf(expr, 10), "120.0");
+  }
+}
@@ -0,0 +1,382 @@
+//! Radical simplifications.
+//!
+//! * Perfect power extraction: ```12^(1/2) = 2*3^(1/2)```, ```2^(-1/2) = 2^(1/2)/2```.
+//! * Denominator rationalization: ```1/(1 + 2^(1/2)) = 2^(1/2) - 1```.
+//! * Square root denesting: ```(5 + 2*6^(1/2))^(1/2) = 2^(1/2) + 3^(1/2)```.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::Mul;
+
+use crate::base::alg::{AOp, Algebra, Assoc, BOp, UOp};
+use crate::base::budget;
+use crate::{Complex, Expr, Integer, Natural, Number, Rational, SymbolicResult, Tree};
+
+// Largest prime tried by trial division before looking for a perfect power cofactor.
+const TRIAL_BOUND: u64 = 1 << 16;
+
+/// Split `n = a^q*b` with `b` free of `q`-th powers (up to trial division by small primes).
+pub(crate) fn extract_power(mut n: Natural, q: u64) -> (Natural, Natural) {
+  let (mut a, mut b) = (Natural::ONE, Natural::ONE);
+  let mut p = Natural::TWO;
+  while p <= Natural::from(TRIAL_BOUND) && p.clone().pow(q) <= n {
+    let mut k = 0;
+    while n.clone() % p.clone() == Natural::ZERO {
+      n /= p.clone();
+      k += 1;
+    }
+    a *= p.clone().pow(k / q);
+    b *= p.clone().pow(k % q);
+    p += if p == Natural::TWO { Natural::ONE } else { Natural::TWO };
+  }
+
+  // the cofactor has no small prime factor: only a perfect power can be pulled out
+  match root_exact(&n, q) {
+    Some(r) => (a * r, b),
+    None => (a, b * n),
+  }
+}
+
+// ```n^(1/q)``` if it is a natural.
+fn root_exact(n: &Natural, q: u64) -> Option<Natural> {
+  let (mut lo, mut hi) = (Natural::ZERO, Natural::ONE << (n.bits() / q as usize + 1));
+  while lo <= hi {
+    let mid = (lo.clone() + hi.clone()) >> 1;
+    match mid.clone().pow(q).cmp(n) {
+      Ordering::Equal => return Some(mid),
+      Ordering::Less => lo = mid + Natural::ONE,
+      Ordering::Greater if mid == Natural::ZERO => return None,
+      Ordering::Greater => hi = mid - Natural::ONE,
+    }
+  }
+  None
+}
+
+/// Simplify ```z^(p/q)``` for an integer `z` and a reduced `p/q` (`q > 1`) into ```c*b^(r/q)*(-1)^(r/q)```
+/// with `c` rational, `b` free of `q`-th powers and `0 < r < q`.
+pub(crate) fn trivial_root(z: Integer, e: &Rational) -> SymbolicResult<Tree> {
+  let unchanged = || Tree::from(z.clone()).pow(Tree::from(e.clone()));
+  let Ok(q) = Natural::try_from(e.den.clone()).and_then(u64::try_from) else {
+    return Ok(unchanged());
+  };
+
+  // ```|z|^(p/q) = a^p*b^k*b^(r/q), |z| = a^q*b, p = k*q + r```
+  let (a, b) = extract_power(z.clone().abs(), q);
+  let (k, r) = (e.num.clone().div_euclid(e.den.clone()), e.num.clone().rem_euclid(e.den.clone()));
+  if a == Natural::ONE && k == Integer::ZERO && (!z.is_negative() || z == Integer::NEG_ONE) {
+    return Ok(unchanged());
+  }
+
+  // ```(-x)^(p/q) = x^(p/q)*(-1)^k*(-1)^(r/q)```, on the principal branch
+  let sgn = if z.is_negative() && k.clone().rem_euclid(Integer::TWO) == Integer::ONE {
+    Integer::NEG_ONE
+  } else {
+    Integer::ONE
+  };
+  let coeff = Number::Int(Integer::from(a)).powi(e.num.clone())?.mul(Number::Int(Integer::from(b.clone())).powi(k)?)?;
+  let mut prod = vec![Tree::Num(coeff.mul(Number::Int(sgn))?)];
+  let frac = Tree::from(Rational::new(r, e.den.clone()));
+  if b != Natural::ONE {
+    prod.push(Tree::from(Integer::from(b)).pow(frac.clone()));
+  }
+  if z.is_negative() {
+    prod.push(Tree::from(-1).pow(frac));
+  }
+  Tree::assoc(AOp::Mul, prod.into_iter().map(Expr::edge).collect()).trivial()
+}
+
+/// Denest ```x^(1/2)``` for ```x = a + b*r^(1/2)``` with `a, b, r` rational, following
+/// [Borodin et al.](https://doi.org/10.1016/S0747-7171(85)80014-6), or return `None`.
+pub(crate) fn denest(x: &Tree) -> Option<Tree> {
+  let s = Surd::from_tree(x)?;
+  let (a, (r, b)) = match s.0.iter().collect::<Vec<_>>()[..] {
+    [(one, a), (r, b)] if *one == Integer::ONE && r.is_positive() => (a.clone(), (r.clone(), b.clone())),
+    _ => return None,
+  };
+
+  let half = |q: Rational| (q / Rational::from(Integer::TWO)).reduce();
+  let sqrt = |q: Rational| Tree::from(q).sqrt();
+  // ```x < 0: x^(1/2) = i*(-x)^(1/2)```
+  let imaginary = || {
+    let neg = Surd(s.0.iter().map(|(k, v)| (k.clone(), -v.clone())).collect());
+    Some(Tree::from(Complex::I).mul(denest(&neg.to_tree().ok()?)?))
+  };
+
+  // ```a^2 - b^2*r = c^2: x^(1/2) = ((a + c)/2)^(1/2) + sgn(b)*((a - c)/2)^(1/2)```
+  let rq = Rational::from(r.clone());
+  let d = (a.clone() * a.clone() - b.clone() * b.clone() * rq.clone()).reduce();
+  if let Some(c) = square_root(&d) {
+    if a.is_negative() {
+      // ```|b|*r^(1/2) < |a|```
+      return imaginary();
+    }
+    let sgn = Tree::from(if b.is_negative() { -1 } else { 1 });
+    return Some(sqrt(half(a.clone() + c.clone())).add(sgn.mul(sqrt(half(a - c)))));
+  }
+
+  // ```b^2*r - a^2 = r*s^2: x^(1/2) = r^(1/4)*(((b + s)/2)^(1/2) + sgn(a)*((b - s)/2)^(1/2))```
+  if let Some(s) = square_root(&(-d / rq).reduce()) {
+    if b.is_negative() {
+      // ```|a| <= |b|*r^(1/2)```
+      return imaginary();
+    }
+    let sgn = Tree::from(if a.is_negative() { -1 } else { 1 });
+    let quartic = Tree::from(r).pow(Tree::from(Rational::new(Integer::ONE, Integer::from(4))));
+    return Some(quartic.mul(sqrt(half(b.clone() + s.clone())).add(sgn.mul(sqrt(half(b - s))))));
+  }
+
+  None
+}
+
+// ```q^(1/2)``` if it is rational.
+fn square_root(q: &Rational) -> Option<Rational> {
+  if q.is_negative() {
+    return None;
+  }
+  let (n, d) = (Natural::try_from(q.num.clone()).ok()?, Natural::try_from(q.den.clone()).ok()?);
+  Some(Rational::new(Integer::from(root_exact(&n, 2)?), Integer::from(root_exact(&d, 2)?)))
+}
+
+/// A ℚ-linear combination of square roots of square-free integers (```-1``` standing for `i`).
+///
+/// The square roots of distinct square-free integers are linearly independent, so the form is canonical.
+#[derive(Debug, Clone, PartialEq)]
+struct Surd(BTreeMap<Integer, Rational>);
+
+impl Surd {
+  fn rational(q: Rational) -> Surd {
+    Surd(BTreeMap::from([(Integer::ONE, q)])).normalize()
+  }
+
+  // ```z^(1/2) = a*b^(1/2)```
+  fn sqrt(z: Integer) -> Surd {
+    let (a, b) = extract_power(z.clone().abs(), 2);
+    let b = if z.is_negative() { -Integer::from(b) } else { Integer::from(b) };
+    Surd(BTreeMap::from([(b, Rational::from(Integer::from(a)))])).normalize()
+  }
+
+  fn from_tree(expr: &Tree) -> Option<Surd> {
+    match expr {
+      Tree::Num(Number::Int(z)) => Some(Surd::rational(Rational::from(z.clone()))),
+      Tree::Num(Number::Rat(q)) => Some(Surd::rational(q.clone())),
+      Tree::Num(Number::Cpx(z)) => Some(Surd(BTreeMap::from([(Integer::ONE, z.re.clone()), (Integer::NEG_ONE, z.im.clone())])).normalize()),
+
+      Tree::Alg(Algebra::UExpr { map: UOp::Id, arg }) => Surd::from_tree(arg),
+      Tree::Alg(Algebra::AssocExpr(Assoc { map, arg })) => arg.iter().try_fold(Surd::from_tree(&map.id())?, |acc, sub| {
+        let sub = Surd::from_tree(sub)?;
+        Some(match map {
+          AOp::Add => acc.add(&sub),
+          AOp::Mul => acc.mul(&sub),
+        })
+      }),
+
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, e) }) => match (b.as_ref(), e.as_ref()) {
+        // ```z^(p/2) = (z^(1/2))^p```
+        (Tree::Num(Number::Int(z)), Tree::Num(Number::Rat(q))) if q.den == Integer::TWO => Surd::sqrt(z.clone()).powi(q.num.clone()),
+        (b, Tree::Num(Number::Int(n))) => Surd::from_tree(b)?.powi(n.clone()),
+        _ => None,
+      },
+
+      _ => None,
+    }
+  }
+
+  fn to_tree(&self) -> SymbolicResult<Tree> {
+    let sum = self.0.iter().map(|(r, c)| match r {
+      &Integer::ONE => Tree::from(c.clone()).edge(),
+      r => Tree::from(c.clone()).mul(Tree::from(r.clone()).sqrt()).edge(),
+    });
+    Tree::assoc(AOp::Add, sum.collect()).trivial()
+  }
+
+  fn normalize(mut self) -> Surd {
+    self.0.retain(|_, c| c.num != Integer::ZERO);
+    self
+  }
+
+  fn add(&self, o: &Surd) -> Surd {
+    let mut sum = self.0.clone();
+    for (r, c) in &o.0 {
+      let e = sum.entry(r.clone()).or_insert_with(|| Rational::from(Integer::ZERO));
+      *e = (e.clone() + c.clone()).reduce();
+    }
+    Surd(sum).normalize()
+  }
+
+  fn mul(&self, o: &Surd) -> Surd {
+    let mut prod = Surd(BTreeMap::new());
+    for (r, c) in &self.0 {
+      for (s, d) in &o.0 {
+        // ```r^(1/2)*s^(1/2) = g*(r*s/g^2)^(1/2)```, negated when both are imaginary
+        let g = Integer::gcd(r.clone(), s.clone());
+        let sgn = if r.is_negative() && s.is_negative() { Integer::NEG_ONE } else { Integer::ONE };
+        let rad = r.clone() * s.clone() / (g.clone() * g.clone());
+        let term = Surd(BTreeMap::from([(rad, (c.clone() * d.clone() * Rational::from(g * sgn)).reduce())]));
+        prod = prod.add(&term);
+      }
+    }
+    prod
+  }
+
+  fn inv(&self) -> Option<Surd> {
+    // ```1/x = y/(x*y)```, multiplying by conjugates until the denominator is rational
+    let (mut num, mut den) = (Surd::rational(Rational::from(Integer::ONE)), self.clone());
+    while let Some(p) = den.conjugation() {
+      // ```p = -1```: complex conjugation, otherwise ```p^(1/2) -> -p^(1/2)```
+      let moved = |r: &Integer| {
+        if p == Integer::NEG_ONE {
+          r.is_negative()
+        } else {
+          r.clone().rem_euclid(p.clone()) == Integer::ZERO
+        }
+      };
+      let conj = Surd(den.0.iter().map(|(r, c)| (r.clone(), if moved(r) { -c.clone() } else { c.clone() })).collect());
+      num = num.mul(&conj);
+      den = den.mul(&conj);
+    }
+
+    let q = den.0.get(&Integer::ONE)?.clone();
+    Some(num.mul(&Surd::rational(Rational::new(q.den, q.num))))
+  }
+
+  // A radical to move by a field automorphism: `i` first, then a square-free `p` that divides some radicals and is
+  // coprime with the others.
+  fn conjugation(&self) -> Option<Integer> {
+    if self.0.keys().any(Integer::is_negative) {
+      return Some(Integer::NEG_ONE);
+    }
+
+    let mut p = self.0.keys().find(|r| **r != Integer::ONE)?.clone();
+    while let Some(g) = self.0.keys().map(|r| Integer::gcd(p.clone(), r.clone())).find(|g| *g != Integer::ONE && *g != p) {
+      p = g;
+    }
+    Some(p)
+  }
+
+  fn powi(&self, n: Integer) -> Option<Surd> {
+    if n.is_negative() {
+      return self.inv()?.powi(-n);
+    }
+    let n = n.abs();
+    Some((0..n.bits()).rev().fold(Surd::rational(Rational::from(Integer::ONE)), |acc, i| {
+      let acc = acc.mul(&acc);
+      if n.bit(i) {
+        acc.mul(self)
+      } else {
+        acc
+      }
+    }))
+  }
+}
+
+impl Tree {
+  /// Rationalize the denominators of numerical square root expressions.
+  ///
+  /// Every subexpression built from Gaussian rationals and square roots of integers is brought to the canonical form
+  /// ```c_1*r_1^(1/2) + ... + c_n*r_n^(1/2)``` with square-free `r_k`.
+  pub fn radsimp(self) -> SymbolicResult<Tree> {
+    // from the leaves, the nodes failing to simplify being replaced by indeterminate forms
+    self.try_map_post_order(|e, node| {
+      let out = match Surd::from_tree(&node) {
+        Some(s) => s.to_tree(),
+        None => budget::simplify_node(node, Tree::node_trivial),
+      };
+      match out {
+        Err(form) if *e != self && form.halt().is_none() => Ok(Tree::Form),
+        out => out,
+      }
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Constant, Form, Symbol};
+
+  fn sqrt(n: i64) -> Tree {
+    Tree::from(n).sqrt()
+  }
+
+  #[test]
+  fn extraction() -> Result<(), Form> {
+    assert_eq!(extract_power(Natural::from(72u64), 2), (Natural::from(6u64), Natural::TWO));
+    assert_eq!(extract_power(Natural::from(1000003u64 * 1000003 * 5), 2), (Natural::from(1000003u64), Natural::from(5u64)));
+    assert_eq!(extract_power(Natural::from(48u64), 3), (Natural::TWO, Natural::from(6u64)));
+
+    // ```12^(1/2) = 2*3^(1/2)```
+    assert_eq!(sqrt(12).trivial()?, Tree::from(2).mul(sqrt(3)).trivial()?);
+    // ```(1/2)^(1/2) = 2^(1/2)/2```
+    assert_eq!(Tree::from(Rational::new(Integer::ONE, Integer::TWO)).sqrt().trivial()?, sqrt(2).div(Tree::from(2)).trivial()?);
+    // ```2^(1/2)*6^(1/2) = 2*3^(1/2)```
+    assert_eq!(sqrt(2).mul(sqrt(6)).trivial()?, Tree::from(2).mul(sqrt(3)).trivial()?);
+    // ```(-8)^(1/2) = 2*i*2^(1/2)```
+    assert_eq!(
+      sqrt(-8).trivial()?,
+      Tree::from(Complex::new(Rational::from(Integer::ZERO), Rational::from(Integer::TWO))).mul(sqrt(2)).trivial()?
+    );
+    // ```(-2)^(1/2)*(-3)^(1/2) = -6^(1/2)```
+    assert_eq!(sqrt(-2).mul(sqrt(-3)).trivial()?, sqrt(6).neg().trivial()?);
+    // ```(-8)^(1/3) = 2*(-1)^(1/3)```
+    let cbrt = Tree::from(Rational::new(Integer::ONE, Integer::from(3)));
+    assert_eq!(Tree::from(-8).pow(cbrt.clone()).trivial()?, Tree::from(2).mul(Tree::from(-1).pow(cbrt)).trivial()?);
+
+    Ok(())
+  }
+
+  #[test]
+  fn rationalization() -> Result<(), Form> {
+    // ```1/(1 + 2^(1/2)) = 2^(1/2) - 1```
+    let x = Tree::from(1).div(Tree::from(1).add(sqrt(2)));
+    assert_eq!(x.radsimp()?, sqrt(2).sub(Tree::from(1)).trivial()?);
+
+    // ```1/(2^(1/2) + 3^(1/2) + 5^(1/2))```
+    let x = Tree::from(1).div(sqrt(2).add(sqrt(3)).add(sqrt(5))).radsimp()?;
+    assert_eq!(format!("{x}"), "1/4*2^(1/2) + 1/6*3^(1/2) + -1/12*30^(1/2)");
+
+    // ```x/(3^(1/2) - 2^(1/2)) = x*(2^(1/2) + 3^(1/2))```
+    let x = Tree::Sym(Symbol::new("x", Number::R).expect("failed to declare symbol `x`"));
+    let y = x.clone().div(sqrt(3).sub(sqrt(2))).radsimp()?;
+    assert_eq!(y, x.mul(sqrt(2).add(sqrt(3))).trivial()?);
+
+    // ```1/(1 + i*2^(1/2)) = (1 - i*2^(1/2))/3```
+    let x = Tree::from(1).div(Tree::from(1).add(sqrt(-2))).radsimp()?;
+    assert_eq!(x, Tree::from(1).sub(sqrt(-2)).div(Tree::from(3)).radsimp()?);
+
+    // ```1/0 = ~∞```
+    assert_eq!(Tree::from(1).div(sqrt(2).sub(sqrt(2))).radsimp()?, Tree::Cte(Constant::Infinity(Ordering::Equal)));
+
+    Ok(())
+  }
+
+  #[test]
+  fn denesting() -> Result<(), Form> {
+    // ```(5 + 2*6^(1/2))^(1/2) = 2^(1/2) + 3^(1/2)```
+    let x = Tree::from(5).add(Tree::from(2).mul(sqrt(6))).sqrt();
+    assert_eq!(x.trivial()?, sqrt(2).add(sqrt(3)).trivial()?);
+
+    // ```(3 - 2*2^(1/2))^(1/2) = 2^(1/2) - 1```
+    let x = Tree::from(3).sub(Tree::from(2).mul(sqrt(2))).sqrt();
+    assert_eq!(x.trivial()?, sqrt(2).sub(Tree::from(1)).trivial()?);
+
+    // ```(-3 - 2*2^(1/2))^(1/2) = i*(1 + 2^(1/2))```
+    let x = Tree::from(-3).sub(Tree::from(2).mul(sqrt(2))).sqrt();
+    assert_eq!(x.trivial()?, Tree::from(Complex::I).mul(Tree::from(1).add(sqrt(2))).trivial()?);
+
+    // ```(3 + 2*3^(1/2))^(1/2) = 3^(1/4)*((3/2)^(1/2) + (1/2)^(1/2))```
+    let x = Tree::from(3).add(Tree::from(2).mul(sqrt(3))).sqrt().trivial()?;
+    let y = Tree::from(3).pow(Tree::from(Rational::new(Integer::ONE, Integer::from(4))));
+    let y = y.mul(
+      Tree::from(Rational::new(Integer::from(3), Integer::TWO))
+        .sqrt()
+        .add(Tree::from(Rational::new(Integer::ONE, Integer::TWO)).sqrt()),
+    );
+    assert_eq!(x, y.trivial()?);
+
+    // ```(1 + 2^(1/2))^(1/2)``` does not denest
+    let x = Tree::from(1).add(sqrt(2)).sqrt();
+    assert!(matches!(x.trivial()?, Tree::Alg(Algebra::BExpr { map: BOp::Pow, .. })));
+
+    Ok(())
+  }
+}
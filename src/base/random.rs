@@ -0,0 +1,237 @@
+//! Seeded random generation of numbers and expressions.
+//!
+//! The generator is [xoshiro256**](https://prng.di.unimi.it/), seeded with SplitMix64, so that a seed reproduces
+//! the same values on every platform.
+
+use std::ops::Range;
+
+use crate::base::alg::AOp;
+use crate::base::algebra::repr::{Array, Digits, Word, WORD_BITS};
+use crate::base::fun::EOp;
+use crate::{Expr, Integer, Natural, Rational, Symbol, Tree};
+
+/// A seeded pseudo-random generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random {
+  state: [u64; 4],
+}
+
+/// The shape of random expressions generated by [`Random::tree`].
+#[derive(Debug, Clone)]
+pub struct TreeShape {
+  /// Maximum depth of the expression.
+  pub depth: usize,
+  /// Associative operations, applied to two or three operands.
+  pub assoc: Vec<AOp>,
+  /// Whether powers with small integer exponents appear.
+  pub pow: bool,
+  /// Elementary functions.
+  pub elem: Vec<EOp>,
+  /// Symbols appearing in the leaves.
+  pub symbols: Vec<Symbol>,
+  /// Maximum bit size of the nonzero integers appearing in the leaves.
+  pub bits: usize,
+}
+
+impl TreeShape {
+  /// Create the shape of polynomial-like expressions of depth 3 in `symbols`.
+  pub fn new(symbols: Vec<Symbol>) -> TreeShape {
+    TreeShape {
+      depth: 3,
+      assoc: vec![AOp::Add, AOp::Mul],
+      pow: true,
+      elem: vec![],
+      symbols,
+      bits: 4,
+    }
+  }
+}
+
+impl Random {
+  /// Create a generator from a seed.
+  pub fn new(seed: u64) -> Random {
+    // SplitMix64
+    let mut z = seed;
+    let mut next = || {
+      z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+      let x = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+      let x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+      x ^ (x >> 31)
+    };
+    Random {
+      state: [next(), next(), next(), next()],
+    }
+  }
+
+  /// Return the next 64 random bits.
+  pub fn next_u64(&mut self) -> u64 {
+    let s = &mut self.state;
+    let out = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+    let t = s[1] << 17;
+    s[2] ^= s[0];
+    s[3] ^= s[1];
+    s[1] ^= s[2];
+    s[0] ^= s[3];
+    s[2] ^= t;
+    s[3] = s[3].rotate_left(45);
+    out
+  }
+
+  /// Return a uniform integer in `[0, n[`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `n = 0`.
+  pub fn below(&mut self, n: u64) -> u64 {
+    assert!(n != 0, "cannot sample empty range");
+    // reject the incomplete last copy of `[0, n[`
+    let threshold = n.wrapping_neg() % n;
+    loop {
+      let x = self.next_u64();
+      if x >= threshold {
+        return x % n;
+      }
+    }
+  }
+
+  /// Return a uniform natural with exactly `bits` significant bits (`0` for `bits = 0`).
+  pub fn natural(&mut self, bits: usize) -> Natural {
+    let mut n = self.uniform(bits);
+    if bits > 0 {
+      n.set_bit(bits - 1, true);
+    }
+    n
+  }
+
+  /// Return a uniform natural in `[0, n[`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `n = 0`.
+  pub fn natural_below(&mut self, n: &Natural) -> Natural {
+    assert!(n != &Natural::ZERO, "cannot sample empty range");
+    loop {
+      let x = self.uniform(n.bits());
+      if &x < n {
+        return x;
+      }
+    }
+  }
+
+  /// Return an integer of uniform sign whose magnitude has exactly `bits` significant bits.
+  pub fn integer(&mut self, bits: usize) -> Integer {
+    let z = Integer::from(self.natural(bits));
+    if self.next_u64() & 1 == 1 {
+      -z
+    } else {
+      z
+    }
+  }
+
+  /// Return a uniform integer in `range`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `range` is empty.
+  pub fn integer_in(&mut self, range: Range<Integer>) -> Integer {
+    assert!(range.start < range.end, "cannot sample empty range");
+    let len = (range.end - range.start.clone()).abs();
+    range.start + Integer::from(self.natural_below(&len))
+  }
+
+  /// Return a reduced rational whose numerator and denominator have at most `bits` significant bits.
+  pub fn rational(&mut self, bits: usize) -> Rational {
+    let num = Integer::from(self.uniform(bits));
+    let num = if self.next_u64() & 1 == 1 { -num } else { num };
+    let den = Integer::from(self.uniform(bits).max(Natural::ONE));
+    Rational::new(num, den).reduce()
+  }
+
+  /// Generate a random expression of the given shape.
+  pub fn tree(&mut self, shape: &TreeShape) -> Tree {
+    self.subtree(shape, shape.depth)
+  }
+
+  // Helpers
+  fn uniform(&mut self, bits: usize) -> Natural {
+    let mut words: Array = (0..bits.div_ceil(WORD_BITS)).map(|_| self.next_u64()).collect();
+    let unused = words.len() * WORD_BITS - bits;
+    if let Some(top) = words.last_mut() {
+      *top &= Word::MAX >> unused;
+    }
+    Natural(Digits::from(words))
+  }
+
+  fn subtree(&mut self, shape: &TreeShape, depth: usize) -> Tree {
+    let ops = shape.assoc.len() + usize::from(shape.pow) + shape.elem.len();
+    // stop early once in a while to vary the depth of the branches
+    if depth == 0 || ops == 0 || self.below(4) == 0 {
+      return self.leaf(shape);
+    }
+
+    let op = self.below(ops as u64) as usize;
+    if let Some(&map) = shape.assoc.get(op) {
+      let arity = 2 + self.below(2);
+      let arg = (0..arity).map(|_| self.subtree(shape, depth - 1).edge()).collect();
+      Tree::assoc(map, arg)
+    } else if shape.pow && op == shape.assoc.len() {
+      let exp = 2 + self.below(2);
+      self.subtree(shape, depth - 1).pow(Tree::from(exp))
+    } else {
+      let map = shape.elem[op - shape.assoc.len() - usize::from(shape.pow)];
+      Tree::elem(map, self.subtree(shape, depth - 1).edge())
+    }
+  }
+
+  fn leaf(&mut self, shape: &TreeShape) -> Tree {
+    if !shape.symbols.is_empty() && self.below(2) == 0 {
+      let sym = &shape.symbols[self.below(shape.symbols.len() as u64) as usize];
+      return Tree::Sym(sym.clone());
+    }
+
+    let bits = 1 + self.below(shape.bits.max(1) as u64) as usize;
+    Tree::from(self.integer(bits))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Number;
+
+  #[test]
+  fn numbers() {
+    let (mut rng, mut other) = (Random::new(42), Random::new(42));
+    assert_eq!((0..8).map(|_| rng.next_u64()).collect::<Vec<_>>(), (0..8).map(|_| other.next_u64()).collect::<Vec<_>>());
+    assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+
+    for bits in [0, 1, 7, 64, 65, 200] {
+      assert_eq!(rng.natural(bits).bits(), bits);
+      assert!(rng.rational(bits).den.is_positive());
+    }
+
+    let n = Natural::from(10u32).pow(30);
+    assert!((0..32).all(|_| rng.natural_below(&n) < n));
+    let (lo, hi) = (Integer::from(-3), Integer::from(4));
+    let samples: Vec<_> = (0..256).map(|_| rng.integer_in(lo.clone()..hi.clone())).collect();
+    assert!(samples.iter().all(|z| &lo <= z && z < &hi));
+    assert!(samples.contains(&lo) && samples.contains(&Integer::from(3)));
+  }
+
+  #[test]
+  fn trees() {
+    let x = Symbol::new("x", Number::R).expect("failed to declare symbol `x`");
+    let mut shape = TreeShape::new(vec![x.clone()]);
+    shape.elem = vec![EOp::Sin, EOp::Exp];
+
+    let trees: Vec<_> = (0..16).map(|_| Random::new(7).tree(&shape)).collect();
+    assert!(trees.iter().all(|t| t == &trees[0]));
+
+    let mut rng = Random::new(7);
+    for _ in 0..64 {
+      let t = rng.tree(&shape);
+      // a leaf, one operation per level
+      assert!(t.iter().fold_rec(0, &|n, _| n + 1) <= 1 + 3 + 9 + 27);
+    }
+  }
+}
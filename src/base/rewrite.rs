@@ -0,0 +1,338 @@
+//! Pattern matching and rewrite rules.
+//!
+//! A pattern is a [`Tree`] in which symbols ending with `_` are wildcards: `a_` matches any subexpression in the
+//! domain of the symbol, and the same subexpression at each of its occurrences. Sums and products are matched up to
+//! associativity and commutativity:
+//! * `sin(a_)^2 + cos(a_)^2` matches `cos(x)^2 + sin(x)^2`, with ```a_ = x```.
+//! * `exp(a_ + b_)` matches `exp(x + y + z)`, with ```a_ = x``` and ```b_ = y + z```.
+//! * `exp(a_)*exp(b_)` matches the product `2*exp(x)*exp(y)`, whose remaining factors are kept by the rewrite.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::mem;
+
+use crate::base::alg::{AOp, Algebra, Assoc};
+use crate::base::algebra::{Number, NumberSystem};
+use crate::base::budget;
+use crate::base::fun::Function;
+use crate::{Edge, Expr, Form, Substitution, Symbol, SymbolicResult, Tree};
+
+// Maximum number of rewriting passes before giving up on a fixpoint.
+const MAX_PASSES: usize = 64;
+
+/// The subexpressions bound to the wildcards of a pattern.
+pub type Bindings = HashMap<Symbol, Tree>;
+
+/// A rewrite rule `lhs -> rhs`, with wildcards in `lhs` that are replaced by their bindings in `rhs`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Rule {
+  lhs: Tree,
+  rhs: Tree,
+  /// Predicates on the bindings of wildcards.
+  guards: Vec<(Symbol, Guard)>,
+}
+
+/// An ordered list of rewrite rules.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Default)]
+pub struct RuleSet {
+  rules: Vec<Rule>,
+}
+
+#[derive(Clone, Copy)]
+struct Guard(fn(&Tree) -> bool);
+
+struct Matcher<'r> {
+  guards: &'r [(Symbol, Guard)],
+}
+
+// The operands of a sum or product being matched.
+struct Operands<'t> {
+  map: AOp,
+  subj: Vec<&'t Tree>,
+  used: Vec<bool>,
+  /// Allow unused operands.
+  rest: bool,
+}
+
+impl Rule {
+  /// Create the rule `lhs -> rhs`, with a trivially simplified `lhs` so that it has the shape of simplified
+  /// expressions.
+  pub fn new(lhs: Tree, rhs: Tree) -> Rule {
+    Rule {
+      lhs: lhs.clone().trivial().unwrap_or(lhs),
+      rhs,
+      guards: vec![],
+    }
+  }
+
+  /// Constrain the wildcard `name` to the subexpressions satisfying `pred`.
+  pub fn when(mut self, name: &str, pred: fn(&Tree) -> bool) -> Rule {
+    if let Some(wildcard) = Symbol::new(name, Number::C) {
+      self.guards.push((wildcard, Guard(pred)));
+    }
+    self
+  }
+
+  /// Return the pattern.
+  pub fn lhs(&self) -> &Tree {
+    &self.lhs
+  }
+
+  /// Return the replacement.
+  pub fn rhs(&self) -> &Tree {
+    &self.rhs
+  }
+
+  /// Match the whole expression, returning the bindings of the wildcards.
+  pub fn matches(&self, expr: &Tree) -> Option<Bindings> {
+    let mut out = None;
+    Matcher { guards: &self.guards }.unify(&self.lhs, expr, Bindings::new(), &mut |b| {
+      out = Some(b);
+      true
+    });
+    out
+  }
+
+  /// Rewrite the expression at its root, or return `None` if the rule does not apply.
+  pub fn apply(&self, expr: &Tree) -> Option<Tree> {
+    let matcher = Matcher { guards: &self.guards };
+    let mut out = None;
+    match (&self.lhs, expr) {
+      // rewrite a part of a sum or product
+      (Tree::Alg(Algebra::AssocExpr(Assoc { map: lhs_map, arg: lhs })), Tree::Alg(Algebra::AssocExpr(Assoc { map, arg }))) if lhs_map == map && lhs.len() < arg.len() => {
+        let pats = Matcher::order(lhs);
+        let mut ops = Operands::new(*map, arg, true);
+        matcher.unify_ac(&mut ops, &pats, Bindings::new(), &mut |b, used| {
+          let rest = arg.iter().zip(used).filter(|(_, used)| !**used).map(|(e, _)| e.clone());
+          out = Some(Tree::assoc(*map, iter::once(substitute(&self.rhs, &b).edge()).chain(rest).collect()));
+          true
+        });
+      }
+
+      (lhs, expr) => {
+        matcher.unify(lhs, expr, Bindings::new(), &mut |b| {
+          out = Some(substitute(&self.rhs, &b));
+          true
+        });
+      }
+    }
+    out
+  }
+}
+
+impl RuleSet {
+  /// Create an empty [`RuleSet`].
+  pub fn new() -> RuleSet {
+    RuleSet::default()
+  }
+
+  /// Append a rule, tried after the previous ones.
+  pub fn push(&mut self, rule: Rule) -> &mut Self {
+    self.rules.push(rule);
+    self
+  }
+
+  /// Return the rules.
+  pub fn rules(&self) -> &[Rule] {
+    &self.rules
+  }
+
+  /// Return `true` if there is no rule.
+  pub fn is_empty(&self) -> bool {
+    self.rules.is_empty()
+  }
+
+  /// Return `true` if a rule applies to a subexpression.
+  pub fn applies(&self, expr: &Tree) -> bool {
+    expr.pre_order().any(|e| self.rules.iter().any(|rule| rule.apply(e).is_some()))
+  }
+
+  /// Rewrite the expression to a fixpoint.
+  ///
+  /// Each pass rewrites the subexpressions before their parent, with the first rule that applies, then simplifies
+  /// the result trivially. Rewriting stops after a pass without change, and fails without a fixpoint after
+  /// [`MAX_PASSES`] passes. The expression is returned as is if no rule applies.
+  pub fn rewrite(&self, expr: Tree) -> SymbolicResult<Tree> {
+    if self.rules.is_empty() {
+      return Ok(expr);
+    }
+
+    let (mut next, mut changed) = (expr.clone().trivial()?, false);
+    for _ in 0..MAX_PASSES {
+      budget::step()?;
+      let out = self.pass(&next).trivial()?;
+      if out == next {
+        return Ok(if changed { next } else { expr });
+      }
+      (next, changed) = (out, true);
+    }
+    Err(Form::default()) // no fixpoint
+  }
+
+  fn pass(&self, expr: &Tree) -> Tree {
+    expr.map_post_order(|_, node| self.rules.iter().find_map(|rule| rule.apply(&node)).unwrap_or(node))
+  }
+}
+
+impl FromIterator<Rule> for RuleSet {
+  fn from_iter<I: IntoIterator<Item = Rule>>(iter: I) -> RuleSet {
+    RuleSet { rules: iter.into_iter().collect() }
+  }
+}
+
+impl<'t> Operands<'t> {
+  fn new(map: AOp, arg: &'t [Edge], rest: bool) -> Operands<'t> {
+    Operands {
+      map,
+      subj: arg.iter().map(|e| e.as_ref()).collect(),
+      used: vec![false; arg.len()],
+      rest,
+    }
+  }
+}
+
+impl Matcher<'_> {
+  // Match `pat` with `expr` under the bindings `b`, then call `k` on each extension until it returns `true`.
+  fn unify(&self, pat: &Tree, expr: &Tree, b: Bindings, k: &mut dyn FnMut(Bindings) -> bool) -> bool {
+    match (pat, expr) {
+      (Tree::Sym(w), _) if is_wildcard(w) => match b.get(w) {
+        Some(bound) => bound == expr && k(b),
+        None if self.admits(w, expr) => {
+          let mut b = b;
+          b.insert(w.clone(), expr.clone());
+          k(b)
+        }
+        None => false,
+      },
+
+      (Tree::Alg(Algebra::AssocExpr(Assoc { map: pat_map, arg: pat })), Tree::Alg(Algebra::AssocExpr(Assoc { map, arg }))) => {
+        let pats = Matcher::order(pat);
+        pat_map == map && self.unify_ac(&mut Operands::new(*map, arg, false), &pats, b, &mut |b, _| k(b))
+      }
+
+      (pat, expr) if pat.is_literal() => pat == expr && k(b),
+      (pat, expr) => same_head(pat, expr) && self.unify_seq(&children(pat), &children(expr), b, k),
+    }
+  }
+
+  fn unify_seq(&self, pat: &[Tree], expr: &[Tree], b: Bindings, k: &mut dyn FnMut(Bindings) -> bool) -> bool {
+    match (pat.split_first(), expr.split_first()) {
+      (None, None) => k(b),
+      (Some((p, pat)), Some((e, expr))) => self.unify(p, e, b, &mut |b| self.unify_seq(pat, expr, b, k)),
+      _ => false,
+    }
+  }
+
+  // Match the operands `pat` with distinct unused operands of `ops`. Without `rest`, every operand must be used,
+  // and a last unbound wildcard absorbs all the remaining operands.
+  fn unify_ac(&self, ops: &mut Operands, pat: &[&Tree], b: Bindings, k: &mut dyn FnMut(Bindings, &[bool]) -> bool) -> bool {
+    let unused = ops.used.iter().filter(|used| !**used).count();
+    match pat.split_first() {
+      None => (ops.rest || unused == 0) && k(b, &ops.used),
+
+      Some((Tree::Sym(w), [])) if !ops.rest && unused >= 2 && is_wildcard(w) && !b.contains_key(w) => {
+        let arg = ops.subj.iter().zip(ops.used.iter()).filter(|(_, used)| !**used).map(|(e, _)| (*e).clone().edge()).collect();
+        let absorbed = Tree::assoc(ops.map, arg);
+        if !self.admits(w, &absorbed) {
+          return false;
+        }
+
+        let mut b = b;
+        b.insert(w.clone(), absorbed);
+        let all = vec![true; ops.used.len()];
+        k(b, &all)
+      }
+
+      Some((p, pat)) => {
+        for i in 0..ops.subj.len() {
+          if ops.used[i] {
+            continue;
+          }
+          let e = ops.subj[i];
+          ops.used[i] = true;
+          if self.unify(p, e, b.clone(), &mut |b| self.unify_ac(ops, pat, b, k)) {
+            return true;
+          }
+          ops.used[i] = false;
+        }
+        false
+      }
+    }
+  }
+
+  // Try the most constrained operands first, so that wildcards bind to what remains.
+  fn order(arg: &[Edge]) -> Vec<&Tree> {
+    let mut pats: Vec<&Tree> = arg.iter().map(|e| e.as_ref()).collect();
+    pats.sort_by_key(|p| matches!(p, Tree::Sym(w) if is_wildcard(w)));
+    pats
+  }
+
+  fn admits(&self, w: &Symbol, expr: &Tree) -> bool {
+    in_domain(w.dom, expr) && self.guards.iter().filter(|(g, _)| g == w).all(|(_, Guard(pred))| pred(expr))
+  }
+}
+
+/// Return `true` if the symbol is a wildcard (its name ends with `_`).
+pub fn is_wildcard(sym: &Symbol) -> bool {
+  sym.name.len() > 1 && sym.name.ends_with('_')
+}
+
+// ```expr ∈ dom```, for the domains of wildcards
+fn in_domain(dom: NumberSystem, expr: &Tree) -> bool {
+  match dom {
+    NumberSystem::AS | NumberSystem::C => true,
+    NumberSystem::N => Function::integral(expr) && (expr.dom() == NumberSystem::N || matches!(expr, Tree::Num(Number::Int(z)) if !z.is_negative())),
+    NumberSystem::Z => Function::integral(expr),
+    dom => expr.dom() != NumberSystem::AS && expr.dom() <= dom,
+  }
+}
+
+fn same_head(pat: &Tree, expr: &Tree) -> bool {
+  match (pat, expr) {
+    (Tree::Alg(Algebra::UExpr { map: p, .. }), Tree::Alg(Algebra::UExpr { map: e, .. })) => p == e,
+    (Tree::Alg(Algebra::BExpr { map: p, .. }), Tree::Alg(Algebra::BExpr { map: e, .. })) => p == e,
+    (Tree::Fun(Function::ElemExpr { map: p, .. }), Tree::Fun(Function::ElemExpr { map: e, .. })) => p == e,
+    (Tree::Fun(Function::SpecExpr(p)), Tree::Fun(Function::SpecExpr(e))) => mem::discriminant(p) == mem::discriminant(e),
+    (Tree::Fun(Function::CombExpr { map: p, .. }), Tree::Fun(Function::CombExpr { map: e, .. })) => p == e,
+    (Tree::Fun(Function::MapExpr { map: p, .. }), Tree::Fun(Function::MapExpr { map: e, .. })) => p == e,
+    (Tree::Cal(p), Tree::Cal(e)) => p.map == e.map && p.var == e.var,
+    (Tree::Sq(p), Tree::Sq(e)) => p.map == e.map && p.idx == e.idx,
+    _ => false,
+  }
+}
+
+fn children(expr: &Tree) -> Vec<Tree> {
+  expr.children().into_iter().cloned().collect()
+}
+
+// Replace the wildcards by their bindings, renaming the variables bound in `expr` which would capture them.
+fn substitute(expr: &Tree, b: &Bindings) -> Tree {
+  Substitution::new(b.iter().map(|(w, e)| (Tree::Sym(w.clone()), e.clone()))).apply(expr)
+}
+
+impl fmt::Debug for Guard {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Guard({:p})", self.0 as *const ())
+  }
+}
+
+impl Eq for Guard {}
+impl PartialEq for Guard {
+  fn eq(&self, o: &Guard) -> bool {
+    std::ptr::fn_addr_eq(self.0, o.0)
+  }
+}
+
+impl Hash for Guard {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    (self.0 as *const ()).hash(state);
+  }
+}
+
+impl fmt::Display for Rule {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} -> {}", self.lhs, self.rhs)
+  }
+}
@@ -0,0 +1,306 @@
+//! Cost-driven simplification.
+//!
+//! A [`Simplifier`] rewrites an expression from its leaves: at each node, every [`Strategy`] is tried on the trivially
+//! simplified node, and the candidate of lowest [`Cost`] is kept, until none improves it. The default cost is the
+//! [`Complexity`] of the expression, a weighted sum of its length, depth and operators.
+
+use crate::base::alg::{AOp, Algebra, Assoc, BOp};
+use crate::base::algebra::poly::Poly;
+use crate::base::algebra::{Number, NumberSystem};
+use crate::base::budget;
+use crate::base::fun::Function;
+use crate::base::rewrite::{Rule, RuleSet};
+use crate::{Expr, Symbol, SymbolicResult, Tree, Zero};
+
+// Maximum number of rounds of strategies at a node.
+const MAX_ROUNDS: usize = 8;
+
+/// A measure of the complexity of expressions, the lower the simpler.
+pub trait Cost {
+  /// Return the cost of `expr`.
+  fn cost(&self, expr: &Tree) -> u64;
+}
+
+impl<F> Cost for F
+where
+  F: Fn(&Tree) -> u64,
+{
+  fn cost(&self, expr: &Tree) -> u64 {
+    self(expr)
+  }
+}
+
+/// The weighted sum ```len*helper_len + depth*depth + Σ weight(op)``` over the operators of an expression.
+#[derive(Debug, Clone, Copy)]
+pub struct Complexity {
+  /// Weight of the length of the leaves.
+  pub len: u64,
+  /// Weight of the depth.
+  pub depth: u64,
+  /// Weight of an operator node.
+  pub weight: fn(&Tree) -> u64,
+}
+
+/// A transformation tried by the [`Simplifier`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Strategy {
+  /// Expand products and positive integer powers.
+  Expand,
+  /// Factor univariate polynomials over ℚ.
+  Factor,
+  /// Cancel the common factors of univariate rational functions.
+  Cancel,
+  /// Trigonometric identities.
+  Trig,
+  /// Logarithm and exponential identities.
+  Log,
+  /// Rationalize and denest square roots.
+  Denest,
+  /// Recognize zero by the randomized [`ZeroTest`](crate::ZeroTest), tried at the root only. The test concludes from
+  /// exact evaluations, or from numerical values below its precision at every point.
+  Zero,
+}
+
+/// A cost-driven simplifier.
+#[derive(Debug, Clone)]
+pub struct Simplifier<C = Complexity> {
+  cost: C,
+  strategies: Vec<Strategy>,
+}
+
+impl Complexity {
+  /// Weight of an operator: calculus and sequences are heavier than functions, than powers, than sums and products.
+  pub fn weight(expr: &Tree) -> u64 {
+    match expr {
+      Tree::Alg(Algebra::AssocExpr(Assoc { arg, .. })) => arg.len().saturating_sub(1) as u64,
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, .. }) => 2,
+      Tree::Alg(_) => 1,
+      Tree::Fun(Function::MapExpr { .. }) => 2,
+      Tree::Fun(_) => 3,
+      Tree::Cal(_) | Tree::Sq(_) => 5,
+      Tree::Sym(_) | Tree::Cte(_) | Tree::Num(_) | Tree::Form => 0,
+    }
+  }
+}
+
+impl Default for Complexity {
+  fn default() -> Complexity {
+    Complexity {
+      len: 1,
+      depth: 1,
+      weight: Complexity::weight,
+    }
+  }
+}
+
+impl Cost for Complexity {
+  fn cost(&self, expr: &Tree) -> u64 {
+    let ops: u64 = expr.pre_order().map(|e| (self.weight)(e)).sum();
+    self.len * expr.helper_len() + self.depth * expr.depth() + ops
+  }
+}
+
+impl Strategy {
+  /// All the strategies.
+  pub const ALL: [Strategy; 7] = [Strategy::Expand, Strategy::Factor, Strategy::Cancel, Strategy::Trig, Strategy::Log, Strategy::Denest, Strategy::Zero];
+
+  /// Apply the strategy to `expr`, or return `None` if it does not apply.
+  pub fn apply(&self, expr: &Tree) -> Option<Tree> {
+    match self {
+      Strategy::Expand => expr.clone().expand().trivial().ok(),
+      Strategy::Factor => factor(expr),
+      Strategy::Cancel => cancel(expr),
+      Strategy::Trig => trig().rewrite(expr.clone()).ok(),
+      Strategy::Log => log().rewrite(expr.clone()).ok(),
+      Strategy::Denest => expr.clone().radsimp().ok(),
+      Strategy::Zero => (!expr.is_value() && expr.is_zero() == Zero::Yes).then_some(Tree::ZERO),
+    }
+  }
+}
+
+impl Simplifier {
+  /// Create a simplifier trying every strategy, with the default [`Complexity`].
+  pub fn new() -> Simplifier {
+    Simplifier {
+      cost: Complexity::default(),
+      strategies: Strategy::ALL.to_vec(),
+    }
+  }
+}
+
+impl Default for Simplifier {
+  fn default() -> Simplifier {
+    Simplifier::new()
+  }
+}
+
+impl<C: Cost> Simplifier<C> {
+  /// Replace the cost function.
+  pub fn cost<D: Cost>(self, cost: D) -> Simplifier<D> {
+    Simplifier { cost, strategies: self.strategies }
+  }
+
+  /// Replace the strategies, tried in order.
+  pub fn strategies<I>(mut self, strategies: I) -> Simplifier<C>
+  where
+    I: IntoIterator<Item = Strategy>,
+  {
+    self.strategies = strategies.into_iter().collect();
+    self
+  }
+
+  /// Return the simplest form of `expr` found.
+  pub fn apply(&self, expr: &Tree) -> SymbolicResult<Tree> {
+    let expr = expr.clone().trivial()?;
+
+    expr.try_map_post_order(|e, node| match self.apply_at(node, *e == expr) {
+      Err(form) if *e != expr && form.halt().is_none() => Ok(Tree::Form),
+      out => out,
+    })
+  }
+
+  // Simplify the node, its children being simplified, the zero test being only tried at the root.
+  fn apply_at(&self, expr: Tree, root: bool) -> SymbolicResult<Tree> {
+    let expr = budget::simplify_node(expr, Tree::node_trivial)?;
+
+    let (mut best, mut cost) = (expr.clone(), self.cost.cost(&expr));
+    for _ in 0..MAX_ROUNDS {
+      budget::step()?;
+      let next = self
+        .strategies
+        .iter()
+        .filter(|s| root || **s != Strategy::Zero)
+        .filter_map(|s| s.apply(&best))
+        .filter(|e| *e != Tree::Form)
+        .map(|e| (self.cost.cost(&e), e))
+        .min_by_key(|(c, _)| *c);
+      match next {
+        Some((c, e)) if c < cost => (best, cost) = (e, c),
+        _ => break,
+      }
+    }
+    Ok(best)
+  }
+}
+
+impl Tree {
+  /// Return the depth of the expression tree, a leaf having depth 1.
+  pub fn depth(&self) -> u64 {
+    let mut iter = self.pre_order();
+    let mut depth = 0;
+    while iter.next().is_some() {
+      depth = depth.max(iter.path().len() as u64 + 1);
+    }
+    depth
+  }
+
+  /// Return the number of nodes of the expression tree.
+  pub fn nodes(&self) -> u64 {
+    self.pre_order().count() as u64
+  }
+
+  /// Return the simplest form of the expression found by the default [`Simplifier`].
+  pub fn simplify(&self) -> SymbolicResult<Tree> {
+    Simplifier::new().apply(self)
+  }
+}
+
+// Variables of the expression.
+fn symbols(expr: &Tree) -> Vec<Tree> {
+  let mut syms: Vec<Tree> = expr.pre_order().filter(|e| matches!(e, Tree::Sym(_))).cloned().collect();
+  syms.sort();
+  syms.dedup();
+  syms
+}
+
+// ```lead*p_1^k_1*...*p_n^k_n```, for a univariate polynomial.
+fn factor(expr: &Tree) -> Option<Tree> {
+  let [x] = &symbols(expr)[..] else {
+    return None;
+  };
+  let (lead, factors) = Poly::from_tree(expr, x)?.factor();
+  // a factorization cut short by the budget is not used
+  budget::step().ok()?;
+  if factors.iter().map(|(_, k)| k).sum::<usize>() < 2 {
+    return None;
+  }
+
+  let arg = factors.iter().map(|(p, k)| p.to_tree(x).pow(Tree::from(*k as u64)).edge());
+  Tree::assoc(AOp::Mul, std::iter::once(Tree::from(lead).edge()).chain(arg).collect()).trivial().ok()
+}
+
+// ```p/q = (p/gcd(p, q))/(q/gcd(p, q))```, for univariate polynomials `p` and `q`.
+fn cancel(expr: &Tree) -> Option<Tree> {
+  let Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Mul, arg })) = expr else {
+    return None;
+  };
+  let [x] = &symbols(expr)[..] else {
+    return None;
+  };
+
+  let (mut num, mut den) = (vec![], vec![]);
+  for e in arg {
+    match e.as_ref() {
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, exp) }) if matches!(exp.as_ref(), Tree::Num(Number::Int(n)) if n.is_negative()) => {
+        den.push(b.clone().pow(exp.clone().neg()).trivial().ok()?.edge())
+      }
+      _ => num.push(e.clone()),
+    }
+  }
+  let p = Poly::from_tree(&Tree::assoc(AOp::Mul, num), x)?;
+  let q = Poly::from_tree(&Tree::assoc(AOp::Mul, den), x)?;
+  let g = Poly::gcd(&p, &q);
+  if g.deg()? == 0 {
+    return None;
+  }
+
+  let (p, q) = (p.div_rem(&g)?.0, q.div_rem(&g)?.0);
+  p.to_tree(x).div(q.to_tree(x)).trivial().ok()
+}
+
+fn wild(name: &str, dom: NumberSystem) -> Tree {
+  Tree::Sym(Symbol::new(name, dom).expect("failed to declare wildcard"))
+}
+
+fn trig() -> RuleSet {
+  let a = wild("a_", NumberSystem::C);
+  let (sin, cos) = (a.clone().sin(), a.clone().cos());
+  [
+    // ```sin(a)^2 + cos(a)^2 = 1```
+    Rule::new(sin.clone().pow(Tree::TWO).add(cos.clone().pow(Tree::TWO)), Tree::ONE),
+    // ```cos(a)^2 - sin(a)^2 = cos(2*a)```
+    Rule::new(cos.clone().pow(Tree::TWO).sub(sin.clone().pow(Tree::TWO)), Tree::TWO.mul(a.clone()).cos()),
+    // ```1 - sin(a)^2 = cos(a)^2```, ```1 - cos(a)^2 = sin(a)^2```
+    Rule::new(Tree::ONE.sub(sin.clone().pow(Tree::TWO)), cos.clone().pow(Tree::TWO)),
+    Rule::new(Tree::ONE.sub(cos.clone().pow(Tree::TWO)), sin.clone().pow(Tree::TWO)),
+    // ```2*sin(a)*cos(a) = sin(2*a)```
+    Rule::new(Tree::TWO.mul(sin.clone()).mul(cos.clone()), Tree::TWO.mul(a.clone()).sin()),
+    // ```sin(a)/cos(a) = tan(a)```
+    Rule::new(sin.clone().div(cos.clone()), a.clone().tan()),
+    Rule::new(cos.div(sin), Tree::ONE.div(a.tan())),
+  ]
+  .into_iter()
+  .collect()
+}
+
+fn log() -> RuleSet {
+  let (a, b) = (wild("a_", NumberSystem::C), wild("b_", NumberSystem::C));
+  let positive = |e: &Tree| e.facts().is_positive();
+  [
+    // ```log(a) + log(b) = log(a*b)```, ```log(a) - log(b) = log(a/b)```, ```a, b > 0```
+    Rule::new(a.clone().log().add(b.clone().log()), a.clone().mul(b.clone()).log())
+      .when("a_", positive)
+      .when("b_", positive),
+    Rule::new(a.clone().log().sub(b.clone().log()), a.clone().div(b.clone()).log())
+      .when("a_", positive)
+      .when("b_", positive),
+    // ```b*log(a) = log(a^b)```, ```a > 0, b ∈ ℝ```
+    Rule::new(b.clone().mul(a.clone().log()), a.clone().pow(b.clone()).log())
+      .when("a_", positive)
+      .when("b_", |e| e.facts().is_real()),
+    // ```exp(a)*exp(b) = exp(a + b)```
+    Rule::new(a.clone().exp().mul(b.clone().exp()), a.add(b).exp()),
+  ]
+  .into_iter()
+  .collect()
+}
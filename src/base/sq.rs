@@ -4,6 +4,7 @@ use crate::{Edge, Expr, Tree};
 use crate::{Number, Symbol, SymbolicResult};
 
 use crate::base::alg::AOp;
+use crate::base::budget;
 
 /// A list of sequential operators.
 #[derive(Debug, Clone, Hash, PartialEq, PartialOrd, Eq, Ord, Copy)]
@@ -32,19 +33,17 @@ impl Sequence {
       // ```_{k=l->u} f = f[k = l] _ f[k = l + 1] _ ... _ f[k = u - 1] _ f[k = u], l ∈ ℤ, u ∈ ℤ```
       (Tree::Num(Number::Int(l)), Tree::Num(Number::Int(u))) => {
         let mut k = l;
-        let sq = std::iter::from_fn(|| {
-          if k < u {
-            let e = arg.clone().evaluate(Tree::Sym(self.idx.clone()), Tree::from(k.clone())).edge();
-            k.incr();
-            Some(e)
-          } else {
-            None
-          }
-        });
+        let mut sq = vec![];
+        while k <= u {
+          // stops unrolling once the budget is exhausted
+          budget::step()?;
+          sq.push(arg.clone().evaluate(Tree::Sym(self.idx.clone()), Tree::from(k.clone())).edge());
+          k.incr();
+        }
 
         Tree::assoc(
           alg, //.
-          sq.collect(),
+          sq,
         )
         .trivial()
       }
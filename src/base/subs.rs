@@ -0,0 +1,208 @@
+//! Simultaneous substitution.
+//!
+//! All the replacements of a [`Substitution`] are applied at once, so that `x -> y, y -> x` swaps the symbols, and
+//! replaced subexpressions are not substituted again. Occurrences bound by a sequence or a calculus operator are kept,
+//! and a bound variable is renamed when a replacement would be captured by it:
+//! ```∑{k=1->n} k*x``` with ```x -> k``` is ```∑{k_1=1->n} k_1*k```.
+
+use std::collections::{HashMap, HashSet};
+use std::iter;
+use std::sync::Arc;
+
+use crate::base::alg::{AOp, Algebra, Assoc};
+use crate::{Edge, Expr, Symbol, Tree};
+
+/// A set of replacements applied at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Substitution {
+  rules: Vec<(Tree, Tree)>,
+  /// Match the sums and products among the terms of larger sums and products.
+  assoc: bool,
+}
+
+// A step of a substitution.
+enum Task<'r> {
+  // substitute in the expression with the rules in scope
+  Visit(Tree, Vec<&'r (Tree, Tree)>),
+  // rebuild the node from its substituted children
+  Node(Tree, usize),
+  // rebuild a sum or product from a replacement and the substituted remaining terms
+  Assoc(AOp, Tree, usize),
+}
+
+impl Substitution {
+  /// Create a substitution replacing each `expr` by `replace`.
+  pub fn new<I>(rules: I) -> Substitution
+  where
+    I: IntoIterator<Item = (Tree, Tree)>,
+  {
+    Substitution {
+      rules: rules.into_iter().collect(),
+      assoc: false,
+    }
+  }
+
+  /// Also replace a sum or product among the terms of a larger one: ```a + b -> c``` turns ```a + b + d``` into
+  /// ```c + d```.
+  pub fn assoc(mut self, assoc: bool) -> Substitution {
+    self.assoc = assoc;
+    self
+  }
+
+  /// Apply the substitution to `expr`.
+  pub fn apply(&self, expr: &Tree) -> Tree {
+    // substituted subexpressions, the children before their parent
+    let mut out: Vec<Tree> = vec![];
+    let mut stack = vec![Task::Visit(expr.clone(), self.rules.iter().collect())];
+    while let Some(task) = stack.pop() {
+      match task {
+        Task::Visit(expr, rules) => self.visit(expr, rules, &mut stack, &mut out),
+        Task::Node(expr, n) => {
+          let arg = out.split_off(out.len() - n);
+          out.push(expr.map_children(|i, _| arg[i].clone()));
+        }
+        Task::Assoc(map, replace, n) => {
+          let rest = out.split_off(out.len() - n).into_iter().map(Tree::edge);
+          out.push(Tree::assoc(map, iter::once(replace.edge()).chain(rest).collect()));
+        }
+      }
+    }
+    out.pop().unwrap_or(Tree::Form)
+  }
+
+  // Substitute in `expr`, pushing the substitutions of its subexpressions on the stack.
+  fn visit<'r>(&self, expr: Tree, rules: Vec<&'r (Tree, Tree)>, stack: &mut Vec<Task<'r>>, out: &mut Vec<Tree>) {
+    if let Some((_, replace)) = rules.iter().find(|(e, _)| *e == expr) {
+      return out.push(replace.clone());
+    }
+    if let Some((map, replace, rest)) = self.subs_assoc(&expr, &rules) {
+      stack.push(Task::Assoc(map, replace, rest.len()));
+      stack.extend(rest.into_iter().rev().map(|e| Task::Visit(e, rules.clone())));
+      return;
+    }
+    // nothing to replace, checked at binders only so that bound variables are not renamed needlessly
+    let binder = matches!(expr, Tree::Sq(_) | Tree::Cal(_));
+    if rules.is_empty() || (binder && !self.assoc && rules.iter().all(|(e, _)| expr.free(e))) {
+      return out.push(expr);
+    }
+
+    let expr = self.rename(expr, &rules);
+    let children: Vec<Tree> = expr.children().into_iter().cloned().collect();
+    let visits: Vec<_> = children
+      .into_iter()
+      .enumerate()
+      .map(|(i, e)| {
+        // bound occurrences are kept
+        let bound = expr.bound(i);
+        Task::Visit(e, rules.iter().copied().filter(|(e, _)| !e.binds(bound)).collect())
+      })
+      .collect();
+    stack.push(Task::Node(expr, visits.len()));
+    stack.extend(visits.into_iter().rev());
+  }
+
+  // ```a + b -> c``` in ```a + b + d```, returning the operation, the replacement and the remaining terms.
+  fn subs_assoc(&self, expr: &Tree, rules: &[&(Tree, Tree)]) -> Option<(AOp, Tree, Vec<Tree>)> {
+    let Tree::Alg(Algebra::AssocExpr(Assoc { map, arg })) = expr else {
+      return None;
+    };
+    if !self.assoc {
+      return None;
+    }
+    let arg = flatten(*map, arg);
+
+    rules.iter().find_map(|(e, replace)| {
+      let Tree::Alg(Algebra::AssocExpr(Assoc { map: sub_map, arg: sub })) = e else {
+        return None;
+      };
+      if sub_map != map || sub.len() >= arg.len() {
+        return None;
+      }
+
+      let mut rest: Vec<Option<&Edge>> = arg.iter().map(Some).collect();
+      for e in &flatten(*map, sub) {
+        let j = rest.iter().position(|r| r.is_some_and(|r| r == e))?;
+        rest[j] = None;
+      }
+      let rest = rest.into_iter().flatten().map(|e| e.as_ref().clone()).collect();
+      Some((*map, replace.clone(), rest))
+    })
+  }
+
+  // Rename the variables bound by `expr` which would capture a replacement.
+  fn rename(&self, expr: Tree, rules: &[&(Tree, Tree)]) -> Tree {
+    let bound = match &expr {
+      Tree::Sq(sq) => vec![sq.idx.clone()],
+      Tree::Cal(cal) => cal.var.clone(),
+      _ => return expr,
+    };
+    let capture: Vec<_> = bound
+      .into_iter()
+      .filter(|var| {
+        let var = Tree::Sym(var.clone());
+        rules.iter().any(|(e, replace)| e.free(&var) && !replace.free(&var))
+      })
+      .collect();
+    if capture.is_empty() {
+      return expr;
+    }
+
+    // names in use
+    let mut used: HashSet<Arc<str>> = HashSet::new();
+    let trees = iter::once(&expr).chain(rules.iter().flat_map(|(e, replace)| [e, replace]));
+    for tree in trees {
+      used.extend(tree.pre_order().filter_map(|e| match e {
+        Tree::Sym(s) => Some(s.name.clone()),
+        _ => None,
+      }));
+    }
+
+    let fresh: HashMap<Symbol, Symbol> = capture
+      .into_iter()
+      .map(|var| {
+        let name = (1..).map(|i| format!("{}_{i}", var.name)).find(|name| !used.contains(name.as_str())).expect("fresh name");
+        used.insert(Arc::from(name.as_str()));
+        let sym = Symbol {
+          name: Arc::from(name.as_str()),
+          ..var.clone()
+        };
+        (var, sym)
+      })
+      .collect();
+
+    let alpha = Substitution::new(fresh.iter().map(|(var, sym)| (Tree::Sym(var.clone()), Tree::Sym(sym.clone()))));
+    let rename = |var: &Symbol| fresh.get(var).cloned().unwrap_or_else(|| var.clone());
+    match expr {
+      Tree::Sq(mut sq) => {
+        sq.arg = alpha.apply(&sq.arg).edge();
+        sq.idx = rename(&sq.idx);
+        Tree::Sq(sq)
+      }
+      Tree::Cal(mut cal) => {
+        cal.arg = alpha.apply(&cal.arg).edge();
+        cal.var = cal.var.iter().map(rename).collect();
+        Tree::Cal(cal)
+      }
+      expr => expr,
+    }
+  }
+}
+
+// Terms of nested sums or products.
+fn flatten(map: AOp, arg: &[Edge]) -> Vec<Edge> {
+  arg.iter().fold(vec![], |mut acc, e| {
+    match e.as_ref() {
+      Tree::Alg(Algebra::AssocExpr(Assoc { map: sub_map, arg })) if *sub_map == map => acc.extend(flatten(map, arg)),
+      _ => acc.push(e.clone()),
+    }
+    acc
+  })
+}
+
+impl Tree {
+  /// Substitute in-place every expression of `map` by its replacement, all at once.
+  pub fn subs_many(&mut self, map: &HashMap<Tree, Tree>) -> &mut Self {
+    *self = Substitution::new(map.iter().map(|(e, replace)| (e.clone(), replace.clone()))).apply(self);
+    self
+  }
+}
@@ -0,0 +1,440 @@
+//! Traversal of expression trees.
+//!
+//! Every child of a node is reached, including the argument of [`Calculus`] operators, in the order of
+//! [`Tree::children`]. A [`Path`] addresses a subtree by the indices of the children leading to it, and the variables
+//! bound by a node (the index of a [`Sequence`], the variables of a [`Calculus`] operator) are reported to
+//! [`Visitor`] and [`Fold`] implementations when entering their scope.
+//!
+//! The variables of a calculus operator are bound in its argument like the index of a sequence, so that substitutions
+//! do not reach them: ```∂(x^2)/∂x``` with ```x -> 2``` is kept as is, and the derivative is to be evaluated first.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::mem;
+
+use crate::base::alg::{Algebra, Assoc};
+use crate::base::cal::Calculus;
+use crate::base::fun::{Function, Special};
+use crate::base::sq::Sequence;
+use crate::{Constant, Edge, Number, Symbol, Tree};
+
+/// The position of a subtree, as the indices of the children leading to it from the root.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Path(Vec<usize>);
+
+impl Path {
+  /// The position of the root.
+  pub fn root() -> Path {
+    Path(Vec::new())
+  }
+
+  /// Return the position of the `i`-th child.
+  pub fn child(&self, i: usize) -> Path {
+    let mut path = self.clone();
+    path.0.push(i);
+    path
+  }
+
+  /// Return the position of the parent, or `None` for the root.
+  pub fn parent(&self) -> Option<Path> {
+    let (_, parent) = self.0.split_last()?;
+    Some(Path(parent.to_vec()))
+  }
+
+  /// Return the indices of the children leading to the position.
+  pub fn indices(&self) -> &[usize] {
+    &self.0
+  }
+
+  /// Return the depth of the position.
+  pub fn depth(&self) -> usize {
+    self.0.len()
+  }
+}
+
+impl From<Vec<usize>> for Path {
+  fn from(indices: Vec<usize>) -> Path {
+    Path(indices)
+  }
+}
+
+impl fmt::Display for Path {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "/")?;
+    let mut iter = self.0.iter();
+    if let Some(i) = iter.next() {
+      write!(f, "{i}")?;
+      for i in iter {
+        write!(f, "/{i}")?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// A visitor of expression trees, with a hook per node variant.
+///
+/// The hooks of operators visit their children by default, so that implementations only override the variants they
+/// act on and call the corresponding `walk_*` function to keep descending.
+pub trait Visitor {
+  /// Visit a node, dispatching to the hook of its variant.
+  fn visit(&mut self, tree: &Tree) {
+    match tree {
+      Tree::Form => self.visit_form(),
+      Tree::Sym(sym) => self.visit_sym(sym),
+      Tree::Cte(cte) => self.visit_cte(cte),
+      Tree::Num(num) => self.visit_num(num),
+      Tree::Alg(alg) => self.visit_alg(alg),
+      Tree::Fun(fun) => self.visit_fun(fun),
+      Tree::Cal(cal) => self.visit_cal(cal),
+      Tree::Sq(sq) => self.visit_sq(sq),
+    }
+  }
+
+  fn visit_form(&mut self) {}
+  fn visit_sym(&mut self, _sym: &Symbol) {}
+  fn visit_cte(&mut self, _cte: &Constant) {}
+  fn visit_num(&mut self, _num: &Number) {}
+
+  fn visit_alg(&mut self, alg: &Algebra) {
+    walk_alg(self, alg)
+  }
+  fn visit_fun(&mut self, fun: &Function) {
+    walk_fun(self, fun)
+  }
+  fn visit_cal(&mut self, cal: &Calculus) {
+    walk_cal(self, cal)
+  }
+  fn visit_sq(&mut self, sq: &Sequence) {
+    walk_sq(self, sq)
+  }
+
+  /// Enter the scope of the variables `bound`.
+  fn enter(&mut self, _bound: &[Symbol]) {}
+  /// Exit the scope of the variables `bound`.
+  fn exit(&mut self, _bound: &[Symbol]) {}
+}
+
+/// Visit the children of an algebraic operator.
+pub fn walk_alg<V: Visitor + ?Sized>(v: &mut V, alg: &Algebra) {
+  (0..).map_while(|i| alg_child(alg, i)).for_each(|e| v.visit(e));
+}
+
+/// Visit the children of a function.
+pub fn walk_fun<V: Visitor + ?Sized>(v: &mut V, fun: &Function) {
+  (0..).map_while(|i| fun_child(fun, i)).for_each(|e| v.visit(e));
+}
+
+/// Visit the argument of a calculus operator, in the scope of its variables.
+pub fn walk_cal<V: Visitor + ?Sized>(v: &mut V, cal: &Calculus) {
+  v.enter(&cal.var);
+  v.visit(&cal.arg);
+  v.exit(&cal.var);
+}
+
+/// Visit the argument of a sequence in the scope of its index, then its bounds.
+pub fn walk_sq<V: Visitor + ?Sized>(v: &mut V, sq: &Sequence) {
+  let idx = [sq.idx.clone()];
+  v.enter(&idx);
+  v.visit(&sq.arg);
+  v.exit(&idx);
+  v.visit(&sq.lo);
+  v.visit(&sq.up);
+}
+
+/// A transformation of expression trees, with a hook per node variant.
+///
+/// The hooks of operators rebuild the node from their transformed children by default (see [`Visitor`]).
+pub trait Fold {
+  /// Transform a node, dispatching to the hook of its variant.
+  fn fold(&mut self, tree: Tree) -> Tree {
+    match tree {
+      Tree::Form => self.fold_form(),
+      Tree::Sym(sym) => self.fold_sym(sym),
+      Tree::Cte(cte) => self.fold_cte(cte),
+      Tree::Num(num) => self.fold_num(num),
+      Tree::Alg(alg) => self.fold_alg(alg),
+      Tree::Fun(fun) => self.fold_fun(fun),
+      Tree::Cal(cal) => self.fold_cal(cal),
+      Tree::Sq(sq) => self.fold_sq(sq),
+    }
+  }
+
+  fn fold_form(&mut self) -> Tree {
+    Tree::Form
+  }
+  fn fold_sym(&mut self, sym: Symbol) -> Tree {
+    Tree::Sym(sym)
+  }
+  fn fold_cte(&mut self, cte: Constant) -> Tree {
+    Tree::Cte(cte)
+  }
+  fn fold_num(&mut self, num: Number) -> Tree {
+    Tree::Num(num)
+  }
+
+  fn fold_alg(&mut self, alg: Algebra) -> Tree {
+    fold_children(self, Tree::Alg(alg))
+  }
+  fn fold_fun(&mut self, fun: Function) -> Tree {
+    fold_children(self, Tree::Fun(fun))
+  }
+  fn fold_cal(&mut self, cal: Calculus) -> Tree {
+    fold_children(self, Tree::Cal(cal))
+  }
+  fn fold_sq(&mut self, sq: Sequence) -> Tree {
+    fold_children(self, Tree::Sq(sq))
+  }
+
+  /// Enter the scope of the variables `bound`.
+  fn enter(&mut self, _bound: &[Symbol]) {}
+  /// Exit the scope of the variables `bound`.
+  fn exit(&mut self, _bound: &[Symbol]) {}
+}
+
+/// Rebuild a node from its children transformed by `f`, in the scope of the variables they are bound by.
+pub fn fold_children<F: Fold + ?Sized>(f: &mut F, tree: Tree) -> Tree {
+  let bound: Vec<_> = (0..tree.children().len()).map(|i| tree.bound(i).to_vec()).collect();
+  tree.map_children(|i, e| {
+    f.enter(&bound[i]);
+    let e = f.fold(e);
+    f.exit(&bound[i]);
+    e
+  })
+}
+
+impl Tree {
+  /// Return the children of the node.
+  pub fn children(&self) -> Vec<&Tree> {
+    (0..).map_while(|i| self.child(i)).collect()
+  }
+
+  /// Return the `i`-th child of the node.
+  pub fn child(&self, i: usize) -> Option<&Tree> {
+    match self {
+      Tree::Form | Tree::Sym(_) | Tree::Cte(_) | Tree::Num(_) => None,
+      Tree::Alg(alg) => alg_child(alg, i),
+      Tree::Fun(fun) => fun_child(fun, i),
+      Tree::Cal(cal) => (i == 0).then_some(cal.arg.as_ref()),
+      Tree::Sq(sq) => [&sq.arg, &sq.lo, &sq.up].into_iter().nth(i).map(Edge::as_ref),
+    }
+  }
+
+  /// Return the variables bound by the node in its `i`-th child: the index of a sequence in its argument, and the
+  /// variables of a calculus operator in its argument.
+  pub fn bound(&self, i: usize) -> &[Symbol] {
+    match (self, i) {
+      (Tree::Sq(sq), 0) => std::slice::from_ref(&sq.idx),
+      (Tree::Cal(cal), 0) => &cal.var,
+      _ => &[],
+    }
+  }
+
+  /// Return the subtree at position `path`.
+  pub fn at(&self, path: &Path) -> Option<&Tree> {
+    path.0.iter().try_fold(self, |tree, &i| tree.child(i))
+  }
+
+  /// Apply `f` to the subtree at position `path`, or return `None` if there is none.
+  pub fn modify_at<R, F>(&mut self, path: &Path, f: F) -> Option<R>
+  where
+    F: FnOnce(&mut Tree) -> R,
+  {
+    self.modify_at_indices(&path.0, f)
+  }
+
+  /// Replace the subtree at position `path` by `sub`, and return the replaced subtree.
+  pub fn replace_at(&mut self, path: &Path, sub: Tree) -> Option<Tree> {
+    self.modify_at(path, |e| mem::replace(e, sub))
+  }
+
+  /// Rebuild the node with each `i`-th child `e` replaced by `f(i, e)`.
+  pub fn map_children<F>(mut self, mut f: F) -> Tree
+  where
+    F: FnMut(usize, Tree) -> Tree,
+  {
+    let mut i = 0;
+    while self.modify_child(i, |e| *e = f(i, mem::replace(e, Tree::Form))).is_some() {
+      i += 1;
+    }
+    self
+  }
+
+  /// Rebuild the tree from its leaves: each subtree `e`, with its children replaced by their images, is replaced by
+  /// `f(e, node)`. The subtrees are mapped from an explicit work stack, and equal subtrees once.
+  pub(crate) fn try_map_post_order<E, F>(&self, mut f: F) -> Result<Tree, E>
+  where
+    F: FnMut(&Tree, Tree) -> Result<Tree, E>,
+  {
+    let mut done: HashMap<Tree, Tree> = HashMap::new();
+    let mut stack = vec![(self.clone(), false)];
+    while let Some((e, ready)) = stack.pop() {
+      if done.contains_key(&e) {
+        continue;
+      }
+
+      if ready {
+        let node = e.clone().map_children(|_, c| done[&c].clone());
+        let out = f(&e, node)?;
+        done.insert(e, out);
+      } else {
+        let children: Vec<Tree> = e.children().into_iter().cloned().collect();
+        stack.push((e, true));
+        stack.extend(children.into_iter().map(|c| (c, false)));
+      }
+    }
+    Ok(done.remove(self).unwrap_or(Tree::Form))
+  }
+
+  /// Rebuild the tree from its leaves, as [`Tree::try_map_post_order`] with an infallible `f`.
+  pub(crate) fn map_post_order<F>(&self, mut f: F) -> Tree
+  where
+    F: FnMut(&Tree, Tree) -> Tree,
+  {
+    let out: Result<Tree, Infallible> = self.try_map_post_order(|e, node| Ok(f(e, node)));
+    out.unwrap_or_else(|never| match never {})
+  }
+
+  /// Return a pre-order iterator over the subtrees.
+  pub fn pre_order(&self) -> PreOrder<'_> {
+    PreOrder {
+      stack: vec![(0, 0, self)],
+      path: vec![],
+    }
+  }
+
+  /// Return a post-order iterator over the subtrees.
+  pub fn post_order(&self) -> PostOrder<'_> {
+    PostOrder {
+      stack: vec![(0, 0, self, false)],
+      path: vec![],
+    }
+  }
+
+  // Apply `f` to the subtree at the position given by `indices`.
+  fn modify_at_indices<R, F>(&mut self, indices: &[usize], f: F) -> Option<R>
+  where
+    F: FnOnce(&mut Tree) -> R,
+  {
+    match indices.split_first() {
+      None => Some(f(self)),
+      Some((&i, rest)) => self.modify_child(i, |e| e.modify_at_indices(rest, f)).flatten(),
+    }
+  }
+
+  // Apply `f` to the `i`-th child.
+  fn modify_child<R, F>(&mut self, i: usize, f: F) -> Option<R>
+  where
+    F: FnOnce(&mut Tree) -> R,
+  {
+    let edge = match self {
+      Tree::Form | Tree::Sym(_) | Tree::Cte(_) | Tree::Num(_) => None,
+      Tree::Alg(Algebra::UExpr { map: _, arg }) => (i == 0).then_some(arg),
+      Tree::Alg(Algebra::BExpr { map: _, arg }) => [&mut arg.0, &mut arg.1].into_iter().nth(i),
+      Tree::Alg(Algebra::AssocExpr(Assoc { map: _, arg })) => arg.get_mut(i),
+      Tree::Fun(Function::ElemExpr { map: _, arg }) => (i == 0).then_some(arg),
+      Tree::Fun(Function::SpecExpr(map)) => match map {
+        Special::Gamma(arg) | Special::Floor(arg) | Special::Ceil(arg) | Special::Round(arg) | Special::Frac(arg) => (i == 0).then_some(arg),
+        Special::Mod(lhs, rhs) => [lhs, rhs].into_iter().nth(i),
+      },
+      Tree::Fun(Function::CombExpr { map: _, arg }) => arg.get_mut(i),
+      Tree::Fun(Function::MapExpr { map: _, arg }) => return arg.get_mut(i).map(f),
+      Tree::Cal(cal) => (i == 0).then_some(&mut cal.arg),
+      Tree::Sq(sq) => [&mut sq.arg, &mut sq.lo, &mut sq.up].into_iter().nth(i),
+    };
+    edge.map(|e| e.modify(f))
+  }
+}
+
+fn alg_child(alg: &Algebra, i: usize) -> Option<&Tree> {
+  let edge = match alg {
+    Algebra::UExpr { map: _, arg } => (i == 0).then_some(arg),
+    Algebra::BExpr { map: _, arg } => [&arg.0, &arg.1].into_iter().nth(i),
+    Algebra::AssocExpr(Assoc { map: _, arg }) => arg.get(i),
+  };
+  edge.map(Edge::as_ref)
+}
+
+fn fun_child(fun: &Function, i: usize) -> Option<&Tree> {
+  let edge = match fun {
+    Function::ElemExpr { map: _, arg } => (i == 0).then_some(arg),
+    Function::SpecExpr(map) => match map {
+      Special::Gamma(arg) | Special::Floor(arg) | Special::Ceil(arg) | Special::Round(arg) | Special::Frac(arg) => (i == 0).then_some(arg),
+      Special::Mod(lhs, rhs) => [lhs, rhs].into_iter().nth(i),
+    },
+    Function::CombExpr { map: _, arg } => arg.get(i),
+    Function::MapExpr { map: _, arg } => return arg.get(i),
+  };
+  edge.map(Edge::as_ref)
+}
+
+/// A pre-order iterator over the subtrees of an expression.
+pub struct PreOrder<'t> {
+  // depth, index in the parent and subtree
+  stack: Vec<(usize, usize, &'t Tree)>,
+  path: Vec<usize>,
+}
+
+impl PreOrder<'_> {
+  /// Return the position of the last subtree returned.
+  pub fn path(&self) -> &[usize] {
+    &self.path
+  }
+}
+
+impl<'t> Iterator for PreOrder<'t> {
+  type Item = &'t Tree;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (depth, i, tree) = self.stack.pop()?;
+    locate(&mut self.path, depth, i);
+    push_children(&mut self.stack, depth, tree, |depth, i, e| (depth, i, e));
+    Some(tree)
+  }
+}
+
+/// A post-order iterator over the subtrees of an expression.
+pub struct PostOrder<'t> {
+  // depth, index in the parent, subtree and whether its children were returned
+  stack: Vec<(usize, usize, &'t Tree, bool)>,
+  path: Vec<usize>,
+}
+
+impl PostOrder<'_> {
+  /// Return the position of the last subtree returned.
+  pub fn path(&self) -> &[usize] {
+    &self.path
+  }
+}
+
+impl<'t> Iterator for PostOrder<'t> {
+  type Item = &'t Tree;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let (depth, i, tree, done) = self.stack.pop()?;
+      locate(&mut self.path, depth, i);
+      if done {
+        return Some(tree);
+      }
+      self.stack.push((depth, i, tree, true));
+      push_children(&mut self.stack, depth, tree, |depth, i, e| (depth, i, e, false));
+    }
+  }
+}
+
+// Move the path to the `i`-th child of the node at depth `depth - 1`.
+fn locate(path: &mut Vec<usize>, depth: usize, i: usize) {
+  path.truncate(depth.saturating_sub(1));
+  if depth > 0 {
+    path.push(i);
+  }
+}
+
+// Push the children of `tree` on the stack, the first one on top.
+fn push_children<'t, T>(stack: &mut Vec<T>, depth: usize, tree: &'t Tree, entry: impl Fn(usize, usize, &'t Tree) -> T) {
+  let start = stack.len();
+  stack.extend((0..).map_while(|i| tree.child(i).map(|e| entry(depth + 1, i, e))));
+  stack[start..].reverse();
+}
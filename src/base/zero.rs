@@ -0,0 +1,312 @@
+//! Probabilistic zero-equivalence testing.
+//!
+//! An expression is evaluated at random points of the domains of its variables:
+//! * rational expressions (sums, products and integer powers of rationals and symbols) at random points of the prime
+//!   field ```𝔽_p, p = 2^61 - 1```, where a nonzero rational function vanishes with probability at most
+//!   ```deg/p``` (Schwartz-Zippel), bounding the total degree `deg` of its numerator and denominator. The test is
+//!   inconclusive when ```deg*points``` is not much smaller than `p`,
+//! * other expressions at random exact rational points, or Gaussian rational points for complex variables, where they
+//!   vanish only if they simplify exactly to zero.
+//!
+//! A nonzero value at any point proves that the expression is not zero, and real values are also bounded away from zero
+//! numerically with ball arithmetic. Vanishing at every point makes it zero with high probability, growing with the
+//! number of points: exactly, or numerically when the ball of its value lies below ```2^-prec``` as the working
+//! precision rises past the precision `prec` of the test, so that ```sin(x)^2 + cos(x)^2 - 1``` is zero. A value
+//! below ```2^-prec``` at every point is then mistaken for zero, and a point where the expression neither simplifies
+//! nor evaluates is inconclusive and does not count. Expressions without variables are zero only if they simplify to
+//! zero.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::base::alg::{AOp, Algebra, Assoc, BOp, UOp};
+use crate::base::numeric::Fault;
+use crate::{Complex, Expr, Facts, FiniteField, Gf, Integer, Natural, Number, Random, Rational, Symbol, Tree};
+
+// Mersenne prime ```2^61 - 1```.
+const PRIME: u64 = (1 << 61) - 1;
+// Bit size of the random rational coordinates.
+const BITS: usize = 16;
+// Bits by which ```deg*points``` must be below the prime for the modular test.
+const MARGIN: u32 = 20;
+// Bits of the working precision beyond the precision of the test.
+const GUARD: usize = 64;
+
+/// The outcome of a zero test.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Zero {
+  /// The expression is zero, with the confidence of the test.
+  Yes,
+  /// The expression is not zero (certain).
+  No,
+  /// Too few points could be evaluated.
+  Unknown,
+}
+
+/// A randomized zero test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZeroTest {
+  /// Number of points at which the expression must vanish.
+  points: usize,
+  /// Precision (in bits) of the numerical evaluation, below which values are taken as zero.
+  prec: usize,
+  seed: u64,
+}
+
+impl ZeroTest {
+  /// Create a test at 8 points, with a numerical precision of 256 bits.
+  pub fn new() -> ZeroTest {
+    ZeroTest { points: 8, prec: 256, seed: 0x5eed }
+  }
+
+  /// Set the number of points at which the expression must vanish to be zero.
+  pub fn confidence(mut self, points: usize) -> ZeroTest {
+    self.points = points.max(1);
+    self
+  }
+
+  /// Set the precision (in bits) of the numerical evaluation: values below ```2^-prec``` are taken as zero.
+  pub fn precision(mut self, prec: usize) -> ZeroTest {
+    self.prec = prec.max(64);
+    self
+  }
+
+  /// Set the seed of the random points.
+  pub fn seed(mut self, seed: u64) -> ZeroTest {
+    self.seed = seed;
+    self
+  }
+
+  /// Test whether `expr` is zero.
+  pub fn test(&self, expr: &Tree) -> Zero {
+    let Ok(expr) = expr.clone().trivial() else {
+      return Zero::Unknown;
+    };
+    if let Tree::Num(n) = &expr {
+      return if n.is_zero() { Zero::Yes } else { Zero::No };
+    }
+
+    let mut vars: Vec<Symbol> = expr
+      .pre_order()
+      .filter_map(|e| match e {
+        Tree::Sym(s) => Some(s.clone()),
+        _ => None,
+      })
+      .collect();
+    vars.sort();
+    vars.dedup();
+    vars.retain(|s| !expr.free(&Tree::Sym(s.clone())));
+
+    let mut rng = Random::new(self.seed);
+    if rational(&expr) {
+      // the probability of vanishing at every point is too large to conclude
+      if degree(&expr).saturating_mul(self.points as u64) > PRIME >> MARGIN {
+        return Zero::Unknown;
+      }
+      self.modular(&expr, &vars, &mut rng)
+    } else {
+      self.numeric(&expr, &vars, &mut rng)
+    }
+  }
+
+  fn modular(&self, expr: &Tree, vars: &[Symbol], rng: &mut Random) -> Zero {
+    let field = FiniteField::prime(Natural::from(PRIME)).expect("2^61 - 1 is prime");
+    self.sample(|| {
+      let point: HashMap<&Symbol, Gf> = vars.iter().map(|s| (s, field.random(rng))).collect();
+      // a pole is skipped
+      eval_mod(expr, &field, &point).map(|v| v.is_zero())
+    })
+  }
+
+  fn numeric(&self, expr: &Tree, vars: &[Symbol], rng: &mut Random) -> Zero {
+    // no random point to draw: the expression did not simplify to zero, and can only be shown nonzero
+    if vars.is_empty() {
+      return match self.at(expr) {
+        Some(false) => Zero::No,
+        _ => Zero::Unknown,
+      };
+    }
+
+    let mut complex = false;
+    self.sample(|| {
+      let mut point = HashMap::new();
+      for s in vars {
+        point.insert(Tree::Sym(s.clone()), coordinate(s, complex, rng)?);
+      }
+      complex = !complex;
+
+      let mut e = expr.clone();
+      e.subs_many(&point);
+      self.at(&e)
+    })
+  }
+
+  /// Evaluate `at` until it vanishes at `points` points, allowing as many inconclusive points.
+  fn sample<F>(&self, mut at: F) -> Zero
+  where
+    F: FnMut() -> Option<bool>,
+  {
+    let (mut zeros, mut fails) = (0, 0);
+    while zeros < self.points {
+      match at() {
+        Some(true) => zeros += 1,
+        Some(false) => return Zero::No,
+        None if fails < self.points => fails += 1,
+        None => return Zero::Unknown,
+      }
+    }
+    Zero::Yes
+  }
+
+  /// Whether a constant expression is zero, or `None` if it is inconclusive: it neither simplifies to a number, nor is
+  /// bounded away from zero or below ```2^-prec``` at the maximum working precision.
+  fn at(&self, expr: &Tree) -> Option<bool> {
+    match expr.clone().trivial().ok()? {
+      Tree::Num(Number::Int(z)) => Some(z == Integer::ZERO),
+      Tree::Num(Number::Rat(q)) => Some(q.num == Integer::ZERO),
+      Tree::Num(Number::Cpx(z)) => Some(z == Complex::ZERO),
+      e => {
+        let max = self.prec + GUARD;
+        let mut w = 64;
+        loop {
+          match e.ball(w) {
+            Ok(ball) => match ball.sgn() {
+              Some(ord) => return Some(ord == Ordering::Equal),
+              None if ball.below(-(self.prec as i64)) => return Some(true),
+              None if w >= max => return None,
+              None => {}
+            },
+            Err(Fault::Precision) if w < max => {}
+            Err(_) => return None,
+          }
+          w = (2 * w).min(max);
+        }
+      }
+    }
+  }
+}
+
+impl Default for ZeroTest {
+  fn default() -> ZeroTest {
+    ZeroTest::new()
+  }
+}
+
+impl Tree {
+  /// Test whether the expression is identically zero, with the default [`ZeroTest`].
+  pub fn is_zero(&self) -> Zero {
+    ZeroTest::new().test(self)
+  }
+}
+
+// Sums, products and integer powers of rationals and symbols.
+fn rational(expr: &Tree) -> bool {
+  expr.pre_order().all(|e| match e {
+    Tree::Sym(_) | Tree::Num(Number::Int(_) | Number::Rat(_)) => true,
+    Tree::Alg(Algebra::AssocExpr(_) | Algebra::UExpr { map: UOp::Id, .. }) => true,
+    Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (_, e) }) => matches!(e.as_ref(), Tree::Num(Number::Int(_))),
+    _ => false,
+  })
+}
+
+// Bound on the total degrees of the numerator and denominator of a rational expression, saturating.
+fn degree(expr: &Tree) -> u64 {
+  // degrees of the subexpressions, the children before their parent
+  let mut degrees: Vec<u64> = vec![];
+  for expr in expr.post_order() {
+    let arg = degrees.split_off(degrees.len() - expr.children().len());
+    let deg = match expr {
+      Tree::Sym(_) => 1,
+      // ```a/b + c/d = (a*d + b*c)/(b*d)```
+      Tree::Alg(Algebra::AssocExpr(_)) => arg.iter().fold(0u64, |acc, d| acc.saturating_add(*d)),
+      Tree::Alg(Algebra::UExpr { map: UOp::Id, .. }) => arg[0],
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (_, e) }) => match e.as_ref() {
+        Tree::Num(Number::Int(n)) => u64::try_from(n.clone().abs()).map_or(u64::MAX, |n| n.saturating_mul(arg[0])),
+        _ => u64::MAX,
+      },
+      _ => 0,
+    };
+    degrees.push(deg);
+  }
+  degrees.pop().unwrap_or(0)
+}
+
+// Value in ```𝔽_p```, or `None` at a pole.
+fn eval_mod(expr: &Tree, field: &FiniteField, point: &HashMap<&Symbol, Gf>) -> Option<Gf> {
+  // values of the subexpressions, the children before their parent
+  let mut values: Vec<Gf> = vec![];
+  for expr in expr.post_order() {
+    let mut arg = values.split_off(values.len() - expr.children().len()).into_iter();
+    let value = match expr {
+      Tree::Sym(s) => point.get(s).cloned(),
+      Tree::Num(Number::Int(z)) => Some(field.from_integer(z)),
+      Tree::Num(Number::Rat(q)) => field.from_rational(q),
+      Tree::Alg(Algebra::AssocExpr(Assoc { map, .. })) => Some(arg.fold(
+        match map {
+          AOp::Add => field.zero(),
+          AOp::Mul => field.one(),
+        },
+        |acc, e| match map {
+          AOp::Add => acc + e,
+          AOp::Mul => acc * e,
+        },
+      )),
+      Tree::Alg(Algebra::UExpr { map: UOp::Id, .. }) => arg.next(),
+      Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (_, e) }) => {
+        let Tree::Num(Number::Int(n)) = e.as_ref() else {
+          return None;
+        };
+        let b = arg.next()?.pow(&n.clone().abs());
+        if n.is_negative() {
+          b.inv()
+        } else {
+          Some(b)
+        }
+      }
+      _ => None,
+    };
+    values.push(value?);
+  }
+  values.pop()
+}
+
+// A random value of the variable `s`, complex if it is not known to be real and `complex` is set.
+fn coordinate(s: &Symbol, complex: bool, rng: &mut Random) -> Option<Tree> {
+  let facts = s.facts();
+  if complex && !facts.is_real() {
+    return Some(Tree::from(Complex::new(rng.rational(BITS), rng.rational(BITS))));
+  }
+
+  // a few tries to satisfy the assumptions
+  (0..8).find_map(|_| {
+    let r = rng.rational(BITS);
+    let abs = if r.is_negative() { -r.clone() } else { r.clone() };
+    let q = match facts.bounds() {
+      (Some(lo), Some(hi)) => {
+        let u = Rational::new(Integer::from(rng.below(1 << BITS)), Integer::from(1u64 << BITS));
+        lo.clone() + (hi.clone() - lo.clone()) * u
+      }
+      (Some(lo), None) => lo.clone() + abs,
+      (None, Some(hi)) => hi.clone() - abs,
+      (None, None) if facts.is_nonnegative() => abs,
+      (None, None) if facts.is_nonpositive() => -abs,
+      (None, None) => r,
+    };
+    let q = if facts.is_integer() { Rational::from(q.round()) } else { q };
+    admits(facts, &q).then(|| Tree::from(q))
+  })
+}
+
+fn admits(facts: &Facts, q: &Rational) -> bool {
+  let zero = Rational::from(Integer::ZERO);
+  let sign = q.cmp(&zero);
+  let (lo, hi) = facts.bounds();
+  !(facts.is_positive() && sign != Ordering::Greater
+    || facts.is_negative() && sign != Ordering::Less
+    || facts.is_nonnegative() && sign == Ordering::Less
+    || facts.is_nonpositive() && sign == Ordering::Greater
+    || facts.is_nonzero() && sign == Ordering::Equal
+    || facts.is_integer() && q.den != Integer::ONE
+    || lo.is_some_and(|lo| q < lo)
+    || hi.is_some_and(|hi| q > hi))
+}
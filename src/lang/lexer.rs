@@ -37,6 +37,8 @@ pub enum Token {
   Def,
   /// `_`
   Pre,
+  /// `->`
+  Rule,
 
   /// `?` keyword to request module.
   Use,
@@ -147,6 +149,19 @@ impl<'t> Lexer<'t> {
     ))
   }
 
+  /// Helper for [`Token::Rule`] extraction.
+  fn rule(&mut self) -> Result<TokenState<'t>, Error> {
+    let len = self.advance().zip(self.advance()).map(|(arrow, head)| arrow + head).ok_or(Error {
+      kind: ErrorKind::Internal { file: file!(), line: line!() },
+      spot: Some(self.pos),
+    })?;
+
+    Ok(self.state(
+      Token::Rule,
+      len, //.
+    ))
+  }
+
   /// Helper for special [`Token`]s (possibly from multiple characters).
   fn special(&mut self) -> Result<TokenState<'t>, Error> {
     let len = self.advance_while(|c| !c.is_whitespace())?;
@@ -190,6 +205,7 @@ impl<'t> Iterator for Lexer<'t> {
     loop {
       return match self.first()? {
         '+' => Some(self.token(Token::Add)),
+        '-' if self.cur.clone().nth(1) == Some('>') => Some(self.rule()),
         '-' => Some(self.token(Token::Sub)),
         '*' => Some(self.token(Token::Mul)),
         '/' => Some(self.token(Token::Div)),
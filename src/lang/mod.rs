@@ -7,7 +7,7 @@ mod prec_parser;
 pub use lexer::{Lexer, Token, TokenState};
 pub use prec_parser::Parser;
 
-use crate::{base::Function, Symbol, Tree};
+use crate::{base::Function, Rule, RuleSet, Symbol, Tree};
 
 use std::any;
 use std::collections::HashMap;
@@ -27,6 +27,7 @@ pub type Term = Tree;
 /// - Expression evaluation `x`.
 /// - Variable declaration `v = x`.
 /// - Function definition `f(a1, ..., an) = ...`.
+/// - Rewrite rule `sin(a_)^2 + cos(a_)^2 -> 1`, applied to every following expression.
 /// - Context evaluation `(x1 ... xn)[x1 = y1] ... [xn = yn]`.
 ///
 /// # Examples
@@ -37,6 +38,9 @@ pub enum Ast {
 
   /// `v = x` or `f(a_) = ...`
   Def(Term, Term),
+
+  /// `f(a_) -> g(a_)`
+  Rule(Term, Term),
   //
   // Modules
   // (Use)
@@ -72,6 +76,8 @@ pub struct Environment {
   variables: HashMap<Tree, Term>,
   /// Functions storage.
   functions: HashMap<Symbol, FunctionMap>,
+  /// Rewrite rules.
+  rules: RuleSet,
   /// Last executed command.
   last: Option<Term>,
 }
@@ -102,14 +108,26 @@ impl Environment {
     match ast {
       Ast::Expr(expr) => {
         // lookup
-        Ok(Some(self.last.insert(self.compose(expr)?).clone()))
+        let expr = self.compose(expr)?;
+        // rewrite, the expression being kept as is when no rule applies
+        let expr = self.rules.rewrite(expr.clone()).map_err(|_| Error {
+          kind: ErrorKind::Context(Semantic::Rewrite(Box::new(expr))),
+          spot: None,
+        })?;
+        Ok(Some(self.last.insert(expr).clone()))
+      }
+
+      Ast::Rule(lhs, rhs) => {
+        let rule = Rule::new(self.compose(lhs)?, self.compose(rhs)?);
+        self.register_rule(rule);
+        Ok(None)
       }
 
       Ast::Def(lhs, rhs) => {
         match lhs {
           Tree::Num(_) | Tree::Cte(_) => {
             return Err(Error {
-              kind: ErrorKind::Context(Semantic::CteDef(lhs)), //.
+              kind: ErrorKind::Context(Semantic::CteDef(Box::new(lhs))), //.
               spot: None,
             });
           }
@@ -146,6 +164,15 @@ impl Environment {
     self.registry.insert(name, f);
   }
 
+  /// Register a rewrite rule in the environment, applied after the previous ones.
+  pub fn register_rule(
+    //.
+    &mut self,
+    rule: Rule,
+  ) {
+    self.rules.push(rule);
+  }
+
   /// Register a package in the environment.
   pub fn register_package<P>(
     //.
@@ -170,7 +197,7 @@ impl Environment {
       if let Some(res) = self.functions.get(map) {
         if arg.len() != res.arg.len() {
           return Err(Error {
-            kind: ErrorKind::Context(Semantic::NumArg(map.clone(), Some((res.arg.len(), arg.len())))), //.
+            kind: ErrorKind::Context(Semantic::NumArg(Box::new(map.clone()), Some((res.arg.len(), arg.len())))), //.
             spot: None,
           });
         }
@@ -206,7 +233,7 @@ impl Environment {
 
 /// Parse a single expression without creating a dedicated [`Environment`].
 pub fn parse(stmt: &str) -> Result<Term, Error> {
-  let (Ast::Expr(rhs) | Ast::Def(_, rhs)) = Parser::parse(stmt, &Environment::default())?;
+  let (Ast::Expr(rhs) | Ast::Def(_, rhs) | Ast::Rule(_, rhs)) = Parser::parse(stmt, &Environment::default())?;
   Ok(rhs)
 }
 
@@ -241,9 +268,11 @@ pub trait Package {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Semantic {
   /// A constant or number on declaration's lhs.
-  CteDef(Term),
+  CteDef(Box<Term>),
   /// Invalid function arguments.
-  NumArg(Symbol, Construction),
+  NumArg(Box<Symbol>, Construction),
+  /// A rewrite leading to an indeterminate form.
+  Rewrite(Box<Term>),
 }
 
 /// A list of general lang errors.
@@ -295,6 +324,7 @@ impl fmt::Display for Semantic {
       Semantic::CteDef(lhs) => write!(f, "definition requires a non-constant expression, found `{lhs}` on lhs"),
       Semantic::NumArg(map, Some((arg, given))) => write!(f, "function `{map}` takes {arg} argument(s) ({given} given)"),
       Semantic::NumArg(map, None) => write!(f, "function `{map}` received invalid argument(s) type(s)"),
+      Semantic::Rewrite(expr) => write!(f, "rewriting `{expr}` leads to an indeterminate form"),
     }
   }
 }
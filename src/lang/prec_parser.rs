@@ -26,8 +26,11 @@ use std::iter::Peekable;
 ///
 /// <Definition> ::= <Expression> "=" <Expression>
 ///
+/// <Rule> ::= <Expression> "->" <Expression>
+///
 /// <Main> ::=
 ///    <Definition>
+///  | <Rule>
 ///  | <Expression>
 /// ```
 /// The parser can handle infix and postfix operators as continuities. For prefix operators, parsing is
@@ -84,6 +87,19 @@ impl<'t> Parser<'t> {
         ))
       }
 
+      Some(TokenState {
+        //.
+        kind: Token::Rule,
+        loc: _,
+        src: _,
+      }) => {
+        let rhs = self.expression(0)?;
+        Ok(Ast::Rule(
+          lhs, //.
+          rhs,
+        ))
+      }
+
       Some(end) => Err(Error {
         kind: ErrorKind::Parsing(format!("end of statement, found remaining token `{:?}`", end.kind)),
         spot: Some(end.loc),
@@ -167,7 +183,7 @@ impl<'t> Parser<'t> {
         let num = src
           .parse::<Natural>() // ∈ ℕ
           .map_err(|err| Error {
-            kind: ErrorKind::Parsing(format!("{err:?}")),
+            kind: ErrorKind::Parsing(format!("{err}")),
             spot: Some(loc),
           })?;
 
@@ -270,8 +286,10 @@ impl<'t> Parser<'t> {
     let args = self.list()?;
 
     if let Some(f) = self.ctx.registry.get(&name) {
+      // resolve variables and functions in arguments
+      let args = args.into_iter().map(|arg| self.ctx.compose(arg)).collect::<Result<_, _>>()?;
       f(args).map_err(|err| Error {
-        kind: ErrorKind::Context(Semantic::NumArg(name, err)),
+        kind: ErrorKind::Context(Semantic::NumArg(Box::new(name), err)),
         spot: None,
       })
     } else {
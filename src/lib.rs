@@ -46,8 +46,11 @@ pub mod num;
 #[cfg(feature = "cycle_plot")]
 pub mod plot;
 
-pub use crate::base::algebra::{Constant, Form, Integer, Natural, Number, Rational, SymbolicResult, Theory};
-pub use crate::base::{Edge, Expr, Node, Symbol, Tree};
+pub use crate::base::algebra::{Algebraic, Complex, Constant, FiniteField, Float, Form, Gf, Integer, Natural, Number, Padic, Rational, SymbolicResult, Theory};
+pub use crate::base::{
+  Assume, Budget, Cancel, Complexity, Cost, Cse, Edge, Expr, Facts, Fold, Halt, Interval, Node, Path, Random, Rule, RuleSet, Simplifier, Strategy, Substitution, Symbol, Tree, TreeShape, Visitor,
+  Zero, ZeroTest,
+};
 
 // Types reexport.
 pub mod types {
@@ -12,3 +12,17 @@ fn parse() -> Result<(), lang::Error> {
 fn eval() -> Result<(), lang::Error> {
   Ok(())
 }
+
+#[test]
+fn rules() -> Result<(), lang::Error> {
+  let mut env = lang::Environment::default();
+  assert_eq!(env.run("sin(a_)^2 + cos(a_)^2 -> 1")?, None);
+  assert_eq!(env.run("cos(x + 1)^2 + y + sin(x + 1)^2")?, Some(lang::parse("y + 1")?.trivial().unwrap()));
+  // statements matching no rule are kept as is
+  assert_eq!(env.run("sin(x)^2 + cos(y)^2")?, Some(lang::parse("sin(x)^2 + cos(y)^2")?));
+  assert_eq!(env.run("x + x")?, Some(lang::parse("x + x")?));
+  // rewrites leading to an indeterminate form are reported
+  assert_eq!(env.run("log(a_) -> 0/0")?, None);
+  assert!(env.run("log(2)").is_err());
+  Ok(())
+}
@@ -1,5 +1,26 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cycle::base::alg::{AOp, Algebra, Assoc, BOp};
+use cycle::base::algebra::NumberSystem;
 use cycle::*;
 
+/// Declare a real symbol.
+fn sym(name: &str) -> Tree {
+  sym_in(name, Number::R)
+}
+
+/// Declare a symbol of the number system `dom`.
+fn sym_in(name: &str, dom: NumberSystem) -> Tree {
+  Tree::Sym(Symbol::new(name, dom).expect("failed to declare symbol"))
+}
+
+/// Declare a symbol of the number system `dom` satisfying `assume`.
+fn assumed(name: &str, dom: NumberSystem, assume: &[Assume]) -> Tree {
+  let s = Symbol::new(name, dom).expect("failed to declare symbol");
+  Tree::Sym(assume.iter().try_fold(s, |s, a| s.assume(a.clone())).expect("inconsistent assumptions"))
+}
+
 #[test]
 fn quadratic_equation() -> Result<(), Form> {
   /// [Quadratic formula](https://en.wikipedia.org/wiki/Quadratic_formula)
@@ -34,3 +55,687 @@ fn quadratic_equation() -> Result<(), Form> {
 
   Ok(())
 }
+
+#[test]
+fn gaussian_rationals() -> Result<(), Form> {
+  let i = Tree::Cte(Constant::i);
+
+  // ```(1 + 2*i)*(3 - i) = 5 + 5*i```
+  let z = Tree::from(1).add(Tree::from(2).mul(i.clone())).mul(Tree::from(3).sub(i.clone()));
+  let z = z.trivial()?;
+  assert_eq!(z, Tree::from(Complex::new(Rational::from(Integer::from(5)), Rational::from(Integer::from(5)))));
+  assert_eq!(format!("{z}"), "5 + 5*i");
+
+  // ```(1 + i)/(1 - i) = i```
+  let q = Tree::from(1).add(i.clone()).div(Tree::from(1).sub(i.clone()));
+  assert_eq!(q.trivial()?, Tree::from(Complex::I));
+
+  // ```x^2 + 1 = 0, x = sqrt(-4)/2 = i```
+  let x = Tree::from(-4).sqrt().div(Tree::from(2)).trivial()?;
+  assert_eq!(x, Tree::from(Complex::I));
+  assert_eq!(x.pow(Tree::from(2)).add(Tree::from(1)).trivial()?, Tree::ZERO);
+
+  Ok(())
+}
+
+#[test]
+fn algebraic_numbers() -> Result<(), Form> {
+  let sqrt = |n: i64| Tree::from(n).sqrt();
+
+  // ```sqrt(2)*sqrt(3) - sqrt(6) = 0```
+  assert_eq!(sqrt(2).mul(sqrt(3)).sub(sqrt(6)).trivial()?, Tree::ZERO);
+  // ```(1 + sqrt(2))^2 - 3 - 2*sqrt(2) = 0```
+  let z = Tree::from(1).add(sqrt(2)).pow(Tree::from(2)).sub(Tree::from(3)).sub(Tree::from(2).mul(sqrt(2)));
+  assert_eq!(z.trivial()?, Tree::ZERO);
+
+  // ```x^5 - x - 1 = 0```
+  let x = Tree::Sym(Symbol::new("x", Number::R).expect("failed to declare symbol `x`"));
+  let p = x.clone().pow(Tree::from(5)).sub(x.clone()).sub(Tree::from(1));
+  let r = Algebraic::root_of(&base::algebra::poly::Poly::from_tree(&p, &x).expect("polynomial in `x`"), 0).expect("one real root");
+  let r = Tree::Num(Number::Alg(r));
+  assert_eq!(format!("{r}"), "RootOf(x^5 + -1*x + -1, 0)");
+  assert_eq!(r.clone().pow(Tree::from(5)).sub(r.clone()).trivial()?, Tree::ONE);
+  assert_eq!(r.to_algebraic().map(|a| a.sgn()), Some(std::cmp::Ordering::Greater));
+
+  // numerator and denominator only of integers and rationals
+  let q = Number::Rat(Rational::new(Integer::from(-1), Integer::from(3)));
+  assert_eq!((q.num(), q.den()), (Some(&Integer::from(-1)), Some(Integer::from(3))));
+  let Tree::Num(r) = r else { unreachable!() };
+  assert_eq!((r.num(), r.den(), r.sgn()), (None, None, std::cmp::Ordering::Greater));
+  let i = Number::Cpx(Complex::I);
+  assert_eq!((i.num(), i.den()), (None, None));
+
+  Ok(())
+}
+
+#[test]
+fn random_consistency() -> Result<(), Form> {
+  let s = Symbol::new("x", Number::R).expect("failed to declare symbol `x`");
+  let mut shape = TreeShape::new(vec![s.clone()]);
+  shape.elem = vec![base::fun::EOp::Sin, base::fun::EOp::Exp];
+
+  // ```x = 1/3```, simplification keeps the value
+  let at = Tree::from(Rational::new(Integer::from(1), Integer::from(3))).interval(128)?;
+  let mut rng = Random::new(2024);
+  let mut compared = 0;
+  for _ in 0..200 {
+    let expr = rng.tree(&shape);
+    let (Ok(lhs), Ok(rhs)) = (expr.interval_at(&s, &at), expr.clone().trivial().and_then(|e| e.interval_at(&s, &at))) else {
+      continue;
+    };
+    assert!(lhs.intersect(&rhs).is_some(), "{expr}: {lhs} and {rhs}");
+    compared += 1;
+  }
+  // undefined or overflowing samples are skipped, nearly all are compared
+  assert!(compared >= 180, "only {compared} samples compared");
+
+  Ok(())
+}
+
+#[test]
+fn assumptions() -> Result<(), Form> {
+  let x = assumed("x", Number::R, &[Assume::Positive]);
+  let y = assumed("y", Number::R, &[Assume::Positive]);
+  let z = Tree::Sym(Symbol::new("z", Number::C).expect("failed to declare symbol `z`"));
+
+  // symbols are told apart by their domain and assumptions
+  assert_ne!(x, Tree::Sym(Symbol::new("x", Number::C).expect("failed to declare symbol `x`")));
+  assert_ne!(z, Tree::Sym(Symbol::new("z", Number::R).expect("failed to declare symbol `z`")));
+  // ```sqrt(m^2) = m, m ∈ ℕ```, whether or not a complex ```m``` is alive
+  let (m_c, m_n) = (sym_in("m", Number::C).pow(Tree::from(2)).sqrt(), sym_in("m", Number::N));
+  assert_eq!(m_n.clone().pow(Tree::from(2)).sqrt().trivial()?, m_n);
+  assert_eq!(m_c.clone().trivial()?, m_c);
+
+  // ```sqrt(x^2) = x, x > 0```, kept for ```z ∈ ℂ```
+  assert_eq!(x.clone().pow(Tree::from(2)).sqrt().trivial()?, x);
+  assert_eq!(z.clone().pow(Tree::from(2)).sqrt().trivial()?, z.clone().pow(Tree::from(2)).sqrt());
+  // ```sqrt(x*y) = sqrt(x)*sqrt(y), x, y > 0```
+  assert_eq!(x.clone().mul(y.clone()).sqrt().trivial()?, x.clone().sqrt().mul(y.clone().sqrt()).trivial()?);
+
+  // ```log(x*y) = log(x) + log(y)```, ```log(x^3) = 3*log(x)```, kept for ```z ∈ ℂ```
+  assert_eq!(x.clone().mul(y.clone()).log().trivial()?, x.clone().log().add(y.clone().log()).trivial()?);
+  assert_eq!(x.clone().pow(Tree::from(3)).log().trivial()?, Tree::from(3).mul(x.clone().log()).trivial()?);
+  assert!(matches!(x.clone().mul(z.clone()).log().trivial()?, Tree::Fun(_)));
+
+  // ```sgn(x + y^2) = 1```, ```⌊n⌋ = n, n ∈ ℤ```
+  assert_eq!(x.clone().add(y.clone().pow(Tree::from(2))).sign(), Some(std::cmp::Ordering::Greater));
+  let n = assumed("n", Number::R, &[Assume::Integer]);
+  assert_eq!(n.clone().floor().trivial()?, n);
+
+  // ```∂(k^2)/∂k = 2*k```, whatever the domain of ```k```
+  let k = Symbol::new("k", Number::Z).expect("failed to declare symbol `k`");
+  assert_eq!(
+    Tree::Sym(k.clone()).pow(Tree::from(2)).derivative(vec![k.clone()]).trivial()?,
+    Tree::from(2).mul(Tree::Sym(k)).trivial()?
+  );
+  // ```∂sqrt(x^2)/∂x = 1, x > 0```
+  assert_eq!(x.clone().pow(Tree::from(2)).sqrt().derivative(vec![Symbol::try_from(x)?]).trivial()?, Tree::ONE);
+
+  Ok(())
+}
+
+#[test]
+fn canonical_order() -> Result<(), Form> {
+  let (x, y, z) = (sym("x"), sym("y"), sym("z"));
+
+  // ```x*sin(y) = sin(y)*x```, ```cos(x) + sin(x) + e + π = π + sin(x) + e + cos(x)```
+  assert_eq!(x.clone().mul(y.clone().sin()).trivial()?, y.clone().sin().mul(x.clone()).trivial()?);
+  let lhs = x.clone().cos().add(x.clone().sin()).add(Tree::Cte(Constant::e)).add(Tree::Cte(Constant::pi));
+  let rhs = Tree::Cte(Constant::pi).add(x.clone().sin()).add(Tree::Cte(Constant::e)).add(x.clone().cos());
+  assert_eq!(lhs.trivial()?, rhs.trivial()?);
+
+  // ```(x*y)*z ~ z*(y*x)```
+  let lhs = x.clone().mul(y.clone()).mul(z.clone());
+  let rhs = z.clone().mul(y.clone().mul(x.clone()));
+  assert!(lhs.equivalent(&rhs));
+  assert!(!lhs.equivalent(&x.clone().mul(y.clone())));
+  Ok(())
+}
+
+#[test]
+fn pattern_matching() -> Result<(), Form> {
+  let (x, y) = (sym("x"), sym("y"));
+  let a = sym_in("a_", Number::C);
+  let b = sym_in("b_", Number::C);
+
+  // ```sin(a_)^2 + cos(a_)^2 -> 1```
+  let pythagoras = Rule::new(a.clone().sin().pow(Tree::TWO).add(a.clone().cos().pow(Tree::TWO)), Tree::ONE);
+  let expr = x.clone().cos().pow(Tree::TWO).add(x.clone().sin().pow(Tree::TWO)).trivial()?;
+  assert_eq!(pythagoras.matches(&expr).unwrap()[&Symbol::try_from(a.clone())?], x);
+  assert_eq!(pythagoras.apply(&expr), Some(Tree::ONE));
+  // non-linear patterns bind the same subexpression
+  let expr = x.clone().sin().pow(Tree::TWO).add(y.clone().cos().pow(Tree::TWO)).trivial()?;
+  assert_eq!(pythagoras.apply(&expr), None);
+  // remaining terms are kept
+  let expr = x.clone().sin().pow(Tree::TWO).add(y.clone()).add(x.clone().cos().pow(Tree::TWO)).trivial()?;
+  assert_eq!(pythagoras.apply(&expr).unwrap().trivial(), Ok(y.clone().add(Tree::ONE).trivial()?));
+
+  // a last wildcard absorbs the remaining operands
+  let split = Rule::new(a.clone().add(b.clone()).exp(), a.clone().exp().mul(b.clone().exp()));
+  let expr = x.clone().add(y.clone()).add(Tree::TWO).exp().trivial()?;
+  let bindings = split.matches(&expr).unwrap();
+  assert_eq!(bindings.len(), 2);
+  assert!(bindings.values().any(|e| matches!(e, Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Add, .. })))));
+
+  // domains and guards
+  let n = sym_in("n_", Number::Z);
+  let parity = Rule::new(Tree::NEG_ONE.pow(n.clone().mul(Tree::TWO)), Tree::ONE);
+  assert!(parity.apply(&Tree::NEG_ONE.pow(sym("k").mul(Tree::TWO)).trivial()?).is_none());
+  let k = sym_in("k", Number::Z);
+  assert_eq!(parity.apply(&Tree::NEG_ONE.pow(k.mul(Tree::TWO)).trivial()?), Some(Tree::ONE));
+  let positive = Rule::new(a.clone().sin(), a.clone()).when("a_", |e| matches!(e, Tree::Num(n) if n.sgn().is_gt()));
+  assert_eq!(positive.apply(&Tree::from(3).sin()), Some(Tree::from(3)));
+  assert_eq!(positive.apply(&Tree::from(-3).sin()), None);
+
+  Ok(())
+}
+
+#[test]
+fn rule_rewriting() -> Result<(), Form> {
+  let (x, y, z) = (sym("x"), sym("y"), sym("z"));
+  let a = sym_in("a_", Number::C);
+  let b = sym_in("b_", Number::C);
+
+  // ```log(a_*b_) -> log(a_) + log(b_)``` to a fixpoint
+  let rules: RuleSet = [Rule::new(a.clone().mul(b.clone()).log(), a.clone().log().add(b.clone().log()))].into_iter().collect();
+  let expr = x.clone().mul(y.clone()).mul(z.clone()).log();
+  let expected = x.clone().log().add(y.clone().log()).add(z.clone().log()).trivial()?;
+  assert_eq!(rules.rewrite(expr), Ok(expected));
+
+  // subexpressions, with the remaining factors of a product
+  let mut rules = RuleSet::new();
+  rules.push(Rule::new(a.clone().exp().mul(b.clone().exp()), a.clone().add(b.clone()).exp()));
+  let expr = x.clone().exp().mul(Tree::TWO).mul(y.clone().exp()).sin();
+  let expected = x.clone().add(y.clone()).exp().mul(Tree::TWO).sin().trivial()?;
+  assert_eq!(rules.rewrite(expr), Ok(expected));
+  assert_eq!(rules.rules()[0].to_string(), format!("{} -> {}", rules.rules()[0].lhs(), rules.rules()[0].rhs()));
+  // applying to no subexpression, the expression is kept as is
+  let expr = x.clone().add(x.clone());
+  assert_eq!(rules.rewrite(expr.clone()), Ok(expr));
+
+  // without a fixpoint, rewriting fails
+  let rules: RuleSet = [Rule::new(a.clone().exp(), a.clone().add(Tree::ONE).exp())].into_iter().collect();
+  assert!(rules.rewrite(x.clone().exp()).is_err());
+
+  // ```sin(a_) -> ∑{k=1->n} a_*k``` renames the index rather than capturing the binding
+  let (k, k_1, n) = (sym("k"), sym("k_1"), sym("n"));
+  let sum = a.clone().mul(k.clone()).sum(Symbol::try_from(k.clone())?, Tree::ONE, n.clone());
+  let rules: RuleSet = [Rule::new(a.clone().sin(), sum)].into_iter().collect();
+  let expected = k.clone().mul(k_1.clone()).sum(Symbol::try_from(k_1)?, Tree::ONE, n).trivial()?;
+  assert_eq!(rules.rewrite(k.sin()), Ok(expected));
+
+  Ok(())
+}
+
+#[test]
+fn fact_inference() -> Result<(), Form> {
+  let x = assumed("x", Number::R, &[Assume::Positive]);
+  let y = assumed("y", Number::R, &[Assume::Positive]);
+  let z = sym("z");
+  let n = sym_in("n", Number::N);
+
+  assert!(x.clone().add(y.clone()).facts().is_positive());
+  assert!(x.clone().mul(y.clone()).div(Tree::from(2)).facts().is_positive());
+  assert!(x.clone().sub(y.clone()).facts().sign().is_none());
+  assert!(z.clone().pow(Tree::from(2)).facts().is_nonnegative());
+  assert!(z.clone().pow(Tree::from(2)).add(x.clone()).facts().is_positive());
+  assert!(!z.clone().pow(Tree::from(3)).facts().is_nonnegative());
+  assert!(z.clone().exp().facts().is_positive() && !z.clone().log().facts().is_real());
+  assert!(x.clone().sqrt().facts().is_positive());
+  assert!(n.clone().add(Tree::from(1)).facts().is_positive());
+  assert!(n.clone().mul(n.clone()).facts().is_integer());
+  assert!(Tree::Cte(Constant::pi).sub(Tree::from(3)).facts().is_positive());
+  assert!(!Tree::Cte(Constant::i).facts().is_real() && Tree::Cte(Constant::i).facts().is_nonzero());
+
+  // ```1 ≤ b ≤ 2```
+  let b = assumed("b", Number::R, &[Assume::Bounded(Some(Rational::from(Integer::ONE)), Some(Rational::from(Integer::TWO)))]);
+  assert!(b.clone().sub(Tree::from(1)).facts().is_nonnegative());
+  assert!(Tree::from(3).sub(b.clone()).facts().is_positive());
+  assert!(b.clone().pow(Tree::from(2)).sub(Tree::from(4)).facts().is_nonpositive());
+  assert!(b.clone().sin().facts().bounds() == (Some(&Rational::from(Integer::NEG_ONE)), Some(&Rational::from(Integer::ONE))));
+
+  Ok(())
+}
+
+#[test]
+fn contradictory_assumptions() -> Result<(), Form> {
+  let x = Symbol::new("x", Number::R).expect("failed to declare symbol");
+  assert!(x.clone().assume(Assume::Positive).and_then(|x| x.assume(Assume::NonPositive)).is_none());
+  assert!(Symbol::new("n", Number::N).expect("failed to declare symbol").assume(Assume::Negative).is_none());
+  assert!(x
+    .clone()
+    .assume(Assume::Bounded(Some(Rational::from(Integer::ONE)), None))
+    .and_then(|x| x.assume(Assume::Bounded(None, Some(Rational::from(Integer::ZERO)))))
+    .is_none());
+  // ```0 < n < 1``` has no integer solution
+  let half = Rational::new(Integer::ONE, Integer::TWO);
+  assert!(Symbol::new("n", Number::Z)
+    .expect("failed to declare symbol")
+    .assume(Assume::Bounded(Some(half.clone()), Some(half)))
+    .is_none());
+  assert!(x.assume(Assume::NonZero).is_some_and(|x| x.facts().is_nonzero() && x.facts().sign().is_none()));
+
+  Ok(())
+}
+
+#[test]
+fn tree_positions() -> Result<(), Form> {
+  let (x, y) = (sym("x"), sym("y"));
+  // ```sin(x) + y^2```
+  let mut expr = x.clone().sin().add(y.clone().pow(Tree::TWO));
+  let path = Path::from(vec![1, 0]);
+  assert_eq!(expr.at(&path), Some(&y));
+  assert_eq!(path.to_string(), "/1/0");
+  assert_eq!(path.parent().and_then(|p| expr.at(&p).cloned()), Some(y.clone().pow(Tree::TWO)));
+  assert_eq!(expr.at(&Path::from(vec![2])), None);
+
+  assert_eq!(expr.replace_at(&path, x.clone()), Some(y.clone()));
+  assert_eq!(expr, x.clone().sin().add(x.clone().pow(Tree::TWO)));
+
+  let mut pre = vec![];
+  let mut iter = expr.pre_order();
+  while let Some(e) = iter.next() {
+    let path = Path::from(iter.path().to_vec());
+    assert_eq!(expr.at(&path), Some(e));
+    pre.push(path.to_string());
+  }
+  assert_eq!(pre, ["/", "/0", "/0/0", "/1", "/1/0", "/1/1"]);
+  let mut post = vec![];
+  let mut iter = expr.post_order();
+  while let Some(e) = iter.next() {
+    let path = Path::from(iter.path().to_vec());
+    assert_eq!(expr.at(&path), Some(e));
+    post.push(path.to_string());
+  }
+  assert_eq!(post, ["/0/0", "/0", "/1/0", "/1/1", "/1", "/"]);
+
+  Ok(())
+}
+
+#[test]
+fn bound_variables() -> Result<(), Form> {
+  /// Free symbols, outside the scope of the variables bound to them.
+  #[derive(Default)]
+  struct FreeSymbols {
+    scope: Vec<Symbol>,
+    free: Vec<Symbol>,
+  }
+
+  impl Visitor for FreeSymbols {
+    fn visit_sym(&mut self, sym: &Symbol) {
+      if !self.scope.contains(sym) && !self.free.contains(sym) {
+        self.free.push(sym.clone());
+      }
+    }
+    fn enter(&mut self, bound: &[Symbol]) {
+      self.scope.extend_from_slice(bound);
+    }
+    fn exit(&mut self, bound: &[Symbol]) {
+      self.scope.truncate(self.scope.len() - bound.len());
+    }
+  }
+
+  /// Replace the free occurrences of a symbol.
+  struct Rename(Symbol, Symbol, usize);
+
+  impl Fold for Rename {
+    fn fold_sym(&mut self, sym: Symbol) -> Tree {
+      Tree::Sym(if sym == self.0 && self.2 == 0 { self.1.clone() } else { sym })
+    }
+    fn enter(&mut self, bound: &[Symbol]) {
+      self.2 += bound.contains(&self.0) as usize;
+    }
+    fn exit(&mut self, bound: &[Symbol]) {
+      self.2 -= bound.contains(&self.0) as usize;
+    }
+  }
+
+  let (tk, tn, tx) = (sym("k"), sym("n"), sym("x"));
+  let (k, n, x) = (Symbol::try_from(tk.clone())?, Symbol::try_from(tn.clone())?, Symbol::try_from(tx.clone())?);
+  // ```∑{k=1->n} k*x```, ```∂(x*n)/∂x```
+  let sum = tk.clone().mul(tx.clone()).sum(k.clone(), Tree::ONE, tn.clone());
+  let der = tx.clone().mul(tn.clone()).derivative(vec![x.clone()]);
+
+  assert!(sum.free(&tk) && !sum.free(&tn) && !sum.free(&tx));
+  assert!(!der.free(&tx) && !der.free(&tn) && der.free(&tk));
+
+  let mut v = FreeSymbols::default();
+  v.visit(&sum.clone().add(der.clone()));
+  assert_eq!(v.free, [x.clone(), n.clone()]);
+
+  // bound occurrences are kept
+  let mut s = sum.clone();
+  s.subs(&tk, &Tree::from(2)).subs(&tn, &Tree::from(3));
+  assert_eq!(s, tk.clone().mul(tx.clone()).sum(k.clone(), Tree::ONE, Tree::from(3)));
+  let mut d = der.clone();
+  d.subs(&tn, &tk).subs(&tx, &Tree::ONE);
+  assert_eq!(d, tx.clone().mul(tk.clone()).derivative(vec![x.clone()]));
+  assert_eq!(Rename(x, n.clone(), 0).fold(der.clone()), der);
+  let tm = sym("m");
+  assert_eq!(Rename(n, Symbol::try_from(tm.clone())?, 0).fold(sum), tk.mul(tx).sum(k, Tree::ONE, tm));
+
+  Ok(())
+}
+
+#[test]
+fn simultaneous_substitution() -> Result<(), Form> {
+  let (x, y, z) = (sym("x"), sym("y"), sym("z"));
+  let mut expr = x.clone().pow(y.clone()).add(z.clone());
+  expr.subs_many(&HashMap::from([(x.clone(), y.clone()), (y.clone(), x.clone())]));
+  assert_eq!(expr, y.clone().pow(x.clone()).add(z.clone()));
+
+  // ```a + b -> c``` inside sums, only with `assoc`
+  let (a, b, c) = (sym("a"), sym("b"), sym("c"));
+  let sum = a.clone().add(z.clone()).add(b.clone()).sin();
+  let s = Substitution::new([(a.clone().add(b.clone()), c.clone())]);
+  assert_eq!(s.apply(&sum), sum);
+  assert_eq!(s.clone().assoc(true).apply(&sum), c.clone().add(z.clone()).sin());
+  let prod = Substitution::new([(a.clone().mul(b.clone()), x.clone())]).assoc(true);
+  assert_eq!(prod.apply(&a.clone().mul(b.clone()).mul(c.clone()).add(a.clone())), x.clone().mul(c.clone()).add(a.clone()));
+
+  Ok(())
+}
+
+#[test]
+fn capture_avoiding_substitution() -> Result<(), Form> {
+  let (k, n, x) = (sym("k"), sym("n"), sym("x"));
+  let k_1 = sym("k_1");
+  let ks = Symbol::try_from(k.clone())?;
+  let xs = Symbol::try_from(x.clone())?;
+
+  // ```∑{k=1->n} k*x``` with ```x -> k```, ```n -> k``` renames the index
+  let mut sum = k.clone().mul(x.clone()).sum(ks.clone(), Tree::ONE, n.clone());
+  sum.subs_many(&HashMap::from([(x.clone(), k.clone()), (n.clone(), k.clone())]));
+  let k1 = Symbol::try_from(k_1.clone())?;
+  assert_eq!(sum, k_1.clone().mul(k.clone()).sum(k1.clone(), Tree::ONE, k.clone()));
+  // the bound index itself is not replaced
+  sum.subs(&k_1, &Tree::TWO);
+  assert_eq!(sum, k_1.clone().mul(k.clone()).sum(k1, Tree::ONE, k.clone()));
+
+  // ```∂(x*y)/∂x``` with ```y -> x```
+  let y = sym("y");
+  let mut der = x.clone().mul(y.clone()).derivative(vec![xs]);
+  der.subs(&y, &x);
+  let x_1 = sym("x_1");
+  assert_eq!(der, x_1.clone().mul(x.clone()).derivative(vec![Symbol::try_from(x_1)?]));
+
+  Ok(())
+}
+
+#[test]
+fn total_order() -> Result<(), Form> {
+  let (x, y) = (sym("x"), sym("y"));
+  let trees = [
+    Tree::Form,
+    Tree::ONE,
+    Tree::Cte(Constant::pi),
+    Tree::Cte(Constant::e),
+    x.clone(),
+    x.clone().sin(),
+    x.clone().cos(),
+    y.clone().sin(),
+    x.clone().floor(),
+    x.clone().ceil(),
+    x.clone().mul(y.clone()),
+    x.clone().add(y.clone()),
+    x.clone().derivative(vec![Symbol::try_from(x.clone())?]),
+    x.clone().sum(Symbol::try_from(x.clone())?, Tree::ONE, y.clone()),
+    x.clone().sum(Symbol::try_from(y.clone())?, Tree::ONE, y.clone()),
+    Tree::from(-2),
+    Tree::Cte(Constant::i),
+    x.clone().pow(Tree::TWO),
+    Tree::TWO.mul(x.clone()),
+    // symbols of the same name are told apart by their domain and assumptions
+    sym_in("x", Number::C),
+    assumed("x", Number::R, &[Assume::Positive]),
+    x.clone().derivative(vec![Symbol::try_from(sym_in("x", Number::C))?]),
+    x.clone().sum(Symbol::try_from(sym_in("x", Number::N))?, Tree::ONE, y.clone()),
+  ];
+  for l in &trees {
+    for r in &trees {
+      assert_eq!(l.cmp(r).is_eq(), l == r, "{l} <> {r}");
+      assert_eq!(l.cmp_structural(r).is_eq(), l == r, "{l} <> {r}");
+      assert_eq!(l.cmp(r), r.cmp(l).reverse(), "{l} <> {r}");
+      for m in &trees {
+        if l < m && m < r {
+          assert!(l < r, "{l} < {m} < {r}");
+        }
+      }
+    }
+  }
+
+  // sorting does not depend on the order of the input
+  let mut sorted = [Tree::Cte(Constant::pi), x.clone(), Tree::ONE];
+  sorted.sort();
+  for mut perm in [[Tree::ONE, Tree::Cte(Constant::pi), x.clone()], [x.clone(), Tree::ONE, Tree::Cte(Constant::pi)]] {
+    perm.sort();
+    assert_eq!(perm, sorted);
+  }
+
+  Ok(())
+}
+
+#[test]
+fn ac_equivalence() -> Result<(), Form> {
+  let (x, y, z) = (sym("x"), sym("y"), sym("z"));
+  let sum = |arg: Vec<Edge>| Tree::Alg(Algebra::AssocExpr(Assoc { map: AOp::Add, arg }));
+  let lhs = sum(vec![x.clone().edge(), sum(vec![y.clone().edge(), z.clone().sin().edge()]).edge()]);
+  let rhs = sum(vec![z.clone().sin().edge(), y.clone().edge(), x.clone().edge()]);
+  assert_ne!(lhs, rhs);
+  assert!(lhs.equivalent(&rhs));
+  assert_eq!(lhs.canonical(), rhs.canonical());
+  assert!(!lhs.equivalent(&x.clone().add(y.clone())));
+
+  Ok(())
+}
+
+#[test]
+fn complexity_metrics() -> Result<(), Form> {
+  let x = sym("x");
+  let expr = x.clone().sin().add(Tree::ONE).trivial()?;
+  assert_eq!(expr.depth(), 3);
+  assert_eq!(expr.nodes(), 4);
+  assert!(Complexity::default().cost(&x) < Complexity::default().cost(&expr));
+
+  Ok(())
+}
+
+#[test]
+fn simplification_strategies() -> Result<(), Form> {
+  let (x, y) = (sym("x"), sym("y"));
+
+  // ```x^2 + 2*x + 1 = (x + 1)^2```
+  let square = x.clone().pow(Tree::TWO).add(Tree::TWO.mul(x.clone())).add(Tree::ONE);
+  assert_eq!(Strategy::Factor.apply(&square.clone().trivial()?), x.clone().add(Tree::ONE).pow(Tree::TWO).trivial().ok());
+  // ```(x^2 - 1)/(x - 1) = x + 1```
+  let frac = x.clone().pow(Tree::TWO).sub(Tree::ONE).div(x.clone().sub(Tree::ONE));
+  assert_eq!(frac.simplify(), x.clone().add(Tree::ONE).trivial());
+  // ```(x + y)^2 - x^2 - y^2 = 2*x*y```
+  let diff = x.clone().add(y.clone()).pow(Tree::TWO).sub(x.clone().pow(Tree::TWO)).sub(y.clone().pow(Tree::TWO));
+  assert_eq!(diff.simplify(), Tree::TWO.mul(x.clone()).mul(y.clone()).trivial());
+  // ```sin(x)^2 + cos(x)^2 + y = 1 + y```
+  let trig = x.clone().sin().pow(Tree::TWO).add(y.clone()).add(x.clone().cos().pow(Tree::TWO));
+  assert_eq!(trig.simplify(), y.clone().add(Tree::ONE).trivial());
+  // ```x*(exp(10^-50) - 1)``` is tiny, but not zero at the precision of the zero test
+  let tiny = x.clone().mul(Tree::from(10).pow(Tree::from(-50)).exp().sub(Tree::ONE));
+  assert_ne!(tiny.simplify(), Ok(Tree::ZERO));
+
+  // expansions with too many terms are kept
+  let huge = x.clone().add(y.clone()).pow(Tree::from(1_000_000u64));
+  assert_eq!(huge.clone().expand().trivial(), huge.clone().trivial());
+  let wide = x.clone().add(y.clone()).add(Tree::ONE).pow(Tree::from(100u64));
+  assert_eq!(wide.clone().expand().trivial(), wide.clone().trivial());
+
+  // a cost function preferring expanded polynomials
+  let expanded = |e: &Tree| {
+    e.pre_order()
+      .filter(|e| matches!(e, Tree::Alg(Algebra::BExpr { map: BOp::Pow, arg: (b, _) }) if !matches!(b.as_ref(), Tree::Sym(_))))
+      .count() as u64
+  };
+  let cube = x.clone().add(Tree::ONE).pow(Tree::from(3));
+  let poly = Simplifier::new().cost(expanded).strategies([Strategy::Expand]).apply(&cube)?;
+  assert_eq!(
+    poly,
+    x.clone()
+      .pow(Tree::from(3))
+      .add(Tree::from(3).mul(x.clone().pow(Tree::TWO)))
+      .add(Tree::from(3).mul(x.clone()))
+      .add(Tree::ONE)
+      .trivial()?
+  );
+
+  Ok(())
+}
+
+#[test]
+fn zero_rational_functions() -> Result<(), Form> {
+  let (x, y) = (sym("x"), sym("y"));
+  // ```(x + y)^3 - x^3 - 3*x^2*y - 3*x*y^2 - y^3```
+  let expr = x
+    .clone()
+    .add(y.clone())
+    .pow(Tree::from(3))
+    .sub(x.clone().pow(Tree::from(3)))
+    .sub(Tree::from(3).mul(x.clone().pow(Tree::TWO)).mul(y.clone()));
+  let expr = expr.sub(Tree::from(3).mul(x.clone()).mul(y.clone().pow(Tree::TWO))).sub(y.clone().pow(Tree::from(3)));
+  assert_eq!(expr.is_zero(), Zero::Yes);
+  assert_eq!(expr.clone().add(Tree::ONE.div(x.clone().pow(Tree::from(20)))).is_zero(), Zero::No);
+  // ```1/(x - 1) - 1/(x + 1) - 2/(x^2 - 1)```
+  let frac = Tree::ONE
+    .div(x.clone().sub(Tree::ONE))
+    .sub(Tree::ONE.div(x.clone().add(Tree::ONE)))
+    .sub(Tree::TWO.div(x.clone().pow(Tree::TWO).sub(Tree::ONE)));
+  assert_eq!(frac.is_zero(), Zero::Yes);
+  // ```x^(2^61) - x``` vanishes on ```𝔽_p```, but its degree is too large to conclude
+  let fermat = x.clone().pow(Tree::from(1u64 << 61)).sub(x.clone());
+  assert_eq!(fermat.is_zero(), Zero::Unknown);
+  assert_eq!(x.clone().pow(Tree::from(1u64 << 20)).sub(x.clone()).is_zero(), Zero::No);
+
+  Ok(())
+}
+
+#[test]
+fn zero_transcendental() -> Result<(), Form> {
+  let (x, y) = (sym("x"), sym("y"));
+  // identities which do not simplify exactly at rational points are decided numerically
+  assert_eq!(x.clone().sin().pow(Tree::TWO).add(x.clone().cos().pow(Tree::TWO)).sub(Tree::ONE).is_zero(), Zero::Yes);
+  assert_eq!(x.clone().add(y.clone()).exp().sub(x.clone().exp().mul(y.clone().exp())).is_zero(), Zero::Yes);
+  assert_eq!(x.clone().sin().sub(x.clone()).is_zero(), Zero::No);
+  // ```exp(10^-100) - 1``` is below the precision of the test, but not zero
+  let tiny = Tree::from(10).pow(Tree::from(-100)).exp().sub(Tree::ONE);
+  assert_eq!(tiny.is_zero(), Zero::Unknown);
+  assert_eq!(x.clone().mul(tiny.clone()).is_zero(), Zero::Yes);
+  assert_eq!(ZeroTest::new().precision(512).test(&x.clone().mul(tiny.clone())), Zero::No);
+  assert_eq!(ZeroTest::new().precision(512).test(&tiny), Zero::No);
+  assert_eq!(Tree::Cte(Constant::pi).sub(Tree::from(Rational::new(Integer::from(355), Integer::from(113)))).is_zero(), Zero::No);
+
+  // ```sqrt(x^2) = x``` only for ```x >= 0```
+  let sqrt = |x: &Tree| x.clone().pow(Tree::TWO).sqrt().sub(x.clone());
+  assert_eq!(sqrt(&x).is_zero(), Zero::No);
+  let p = assumed("p", Number::R, &[Assume::Positive]);
+  assert_eq!(sqrt(&p).is_zero(), Zero::Yes);
+  // ```log(p*q) = log(p) + log(q)```, ```p, q > 0```
+  let q = assumed("q", Number::R, &[Assume::Positive]);
+  let expr = p
+    .clone()
+    .mul(Tree::TWO)
+    .add(Tree::ONE)
+    .log()
+    .add(q.clone().log())
+    .sub(p.clone().mul(Tree::TWO).add(Tree::ONE).mul(q.clone()).log());
+  assert_eq!(ZeroTest::new().confidence(4).test(&expr), Zero::Yes);
+
+  Ok(())
+}
+
+#[test]
+fn subexpression_elimination() -> Result<(), Form> {
+  let (x, y) = (sym("x"), sym("y"));
+  let s = x.clone().add(Tree::ONE).sin();
+  let expr = s.clone().pow(Tree::TWO).add(s.clone().mul(x.clone().add(Tree::ONE))).trivial()?;
+  let cse = expr.cse();
+  assert_eq!(cse.temps.len(), 2);
+  assert_eq!(cse.temps[0].1, x.clone().add(Tree::ONE).trivial()?);
+  assert_eq!(cse.inline(), expr);
+  assert_eq!(cse.to_string().lines().count(), 3);
+
+  // a product rule derivative
+  let f = x.clone().mul(y.clone()).sin().mul(x.clone().mul(y.clone()).exp()).mul(x.clone().pow(Tree::TWO).add(y.clone()));
+  let der = f.derivative(vec![Symbol::try_from(x.clone())?]).trivial()?;
+  let cse = der.cse();
+  assert!(!cse.temps.is_empty());
+  assert!(cse.expr.nodes() + cse.temps.iter().map(|(_, e)| e.nodes()).sum::<u64>() < der.nodes());
+  assert_eq!(cse.inline(), der);
+
+  // nothing repeated
+  assert!(x.clone().sin().cse().temps.is_empty());
+
+  Ok(())
+}
+
+#[test]
+fn subexpression_scopes() -> Result<(), Form> {
+  let (k, n, x) = (sym("k"), sym("n"), sym("x"));
+  let ks = Symbol::try_from(k.clone())?;
+  // ```∑{k=1->n} (k + 1)*(x + 1) + (k + 1)*(x + 1)```, where `x + 1` is hoisted but not `k + 1`
+  let term = k.clone().add(Tree::ONE).mul(x.clone().add(Tree::ONE));
+  let expr = term.clone().sum(ks, Tree::ONE, n.clone()).add(term.clone()).trivial()?;
+  let cse = expr.cse();
+  assert_eq!(cse.temps.len(), 1);
+  assert_eq!(cse.temps[0].1, x.clone().add(Tree::ONE).trivial()?);
+  assert_eq!(cse.inline(), expr);
+
+  Ok(())
+}
+
+#[test]
+fn budget_limits() -> Result<(), Form> {
+  let (k, n, x) = (sym("k"), sym("n"), sym("x"));
+  let ks = Symbol::try_from(k.clone())?;
+  let sum = |up: u64| k.clone().mul(x.clone()).sin().sum(ks.clone(), Tree::ONE, Tree::from(up));
+
+  // a small sum fits, a large one exhausts the steps
+  assert!(Budget::new().steps(10_000).trivial(sum(10)).is_ok());
+  assert_eq!(Budget::new().steps(10_000).trivial(sum(1_000_000)), Err(Halt::Steps));
+  assert_eq!(Budget::new().time(Duration::from_millis(50)).trivial(sum(100_000_000)), Err(Halt::Time));
+  // indeterminate forms are reported as such
+  assert_eq!(Budget::new().trivial(Tree::Form), Err(Halt::Form));
+
+  let cancel = Cancel::new();
+  cancel.cancel();
+  assert_eq!(Budget::new().cancel(&cancel).trivial(n.clone().add(Tree::ONE)), Err(Halt::Cancelled));
+  assert!(Budget::new().trivial(n.add(Tree::ONE)).is_ok());
+
+  // evaluations are bounded too
+  let wide = (1..=1_000).fold(Tree::ZERO, |acc, k| acc.add(Tree::from(k).sin()));
+  assert_eq!(Budget::new().steps(100).run(|| wide.evalf(10)), Err(Halt::Steps));
+
+  // the reason propagates through the simplification
+  let mut form = None;
+  assert_eq!(Budget::new().steps(100).run(|| form = sum(1_000).trivial().err()), Err(Halt::Steps));
+  assert_eq!(form.and_then(|form| form.halt()), Some(Halt::Steps));
+
+  Ok(())
+}
+
+#[test]
+fn deep_nesting() -> Result<(), Form> {
+  // ```sin(sin(...sin(x)...))```
+  let nested = (0..100_000).fold(sym("x"), |acc, _| acc.sin());
+  assert!(nested.trivial().is_ok());
+
+  // on a small stack, which the depth of the trees would exhaust if they were traversed recursively
+  let small = std::thread::Builder::new().stack_size(1 << 18);
+  let deep = small.spawn(|| -> Result<(), Form> {
+    let nested = (0..2_000).fold(sym("x"), |acc, _| acc.sin());
+    assert_eq!(nested.is_zero(), Zero::No);
+    // ```1/(1 + 1/(1 + ...))```
+    let fraction = (0..2_000).fold(Tree::ONE, |acc, _| Tree::ONE.div(Tree::ONE.add(acc)));
+    assert_eq!(fraction.evalf(6)?.to_string(), "0.618034");
+    assert!(fraction.interval(64).is_ok());
+    let nested = (0..40).fold(sym("x"), |acc, _| acc.sin().add(Tree::ONE));
+    assert_eq!(nested.simplify()?, nested.trivial()?);
+    Ok(())
+  });
+  deep.expect("failed to spawn a thread").join().expect("stack overflow")
+}